@@ -274,6 +274,7 @@ pub enum ExprPrecedence {
     Mac,
 
     Array,
+    ConstBlock,
     Repeat,
     Tup,
     Lit,
@@ -338,6 +339,7 @@ impl ExprPrecedence {
 
             // Never need parens
             ExprPrecedence::Array |
+            ExprPrecedence::ConstBlock |
             ExprPrecedence::Repeat |
             ExprPrecedence::Tup |
             ExprPrecedence::Lit |
@@ -6,13 +6,15 @@ use rustc_ast::ptr::P as AstP;
 use rustc_data_structures::fx::FxHashMap;
 use rustc_data_structures::stack::ensure_sufficient_stack;
 use rustc_data_structures::thin_vec::ThinVec;
-use rustc_errors::struct_span_err;
+use rustc_errors::{struct_span_err, Applicability};
 use rustc_hir as hir;
-use rustc_hir::def::Res;
+use rustc_hir::def::{CtorKind, DefKind, Res};
+use rustc_session::parse::feature_err;
 use rustc_span::hygiene::ForLoopLoc;
 use rustc_span::source_map::{respan, DesugaringKind, Span, Spanned};
 use rustc_span::symbol::{sym, Ident, Symbol};
 use rustc_target::asm;
+use smallvec::SmallVec;
 use std::collections::hash_map::Entry;
 use std::fmt::Write;
 
@@ -30,6 +32,10 @@ impl<'hir> LoweringContext<'_, 'hir> {
             let kind = match e.kind {
                 ExprKind::Box(ref inner) => hir::ExprKind::Box(self.lower_expr(inner)),
                 ExprKind::Array(ref exprs) => hir::ExprKind::Array(self.lower_exprs(exprs)),
+                ExprKind::ConstBlock(ref anon_const) => {
+                    let anon_const = self.lower_anon_const(anon_const);
+                    hir::ExprKind::ConstBlock(anon_const)
+                }
                 ExprKind::Repeat(ref expr, ref count) => {
                     let expr = self.lower_expr(expr);
                     let count = self.lower_anon_const(count);
@@ -142,7 +148,7 @@ impl<'hir> LoweringContext<'_, 'hir> {
                     hir::ExprKind::Block(self.lower_block(blk, opt_label.is_some()), opt_label)
                 }
                 ExprKind::Assign(ref el, ref er, span) => {
-                    hir::ExprKind::Assign(self.lower_expr(el), self.lower_expr(er), span)
+                    self.lower_expr_assign(el, er, span, e.span)
                 }
                 ExprKind::AssignOp(op, ref el, ref er) => hir::ExprKind::AssignOp(
                     self.lower_binop(op),
@@ -182,8 +188,18 @@ impl<'hir> LoweringContext<'_, 'hir> {
                 }
                 ExprKind::InlineAsm(ref asm) => self.lower_expr_asm(e.span, asm),
                 ExprKind::LlvmInlineAsm(ref asm) => self.lower_expr_llvm_asm(asm),
-                ExprKind::Struct(ref path, ref fields, ref maybe_expr) => {
-                    let maybe_expr = maybe_expr.as_ref().map(|x| self.lower_expr(x));
+                ExprKind::Struct(ref path, ref fields, ref rest) => {
+                    let maybe_expr = match rest {
+                        StructRest::Base(e) => Some(self.lower_expr(e)),
+                        StructRest::Rest(sp) => {
+                            self.sess
+                                .struct_span_err(*sp, "base expression required after `..`")
+                                .span_label(*sp, "add a base expression here")
+                                .emit();
+                            Some(&*self.arena.alloc(self.expr_err(*sp)))
+                        }
+                        StructRest::None => None,
+                    };
                     hir::ExprKind::Struct(
                         self.arena.alloc(self.lower_qpath(
                             e.id,
@@ -355,6 +371,90 @@ impl<'hir> LoweringContext<'_, 'hir> {
         hir::ExprKind::Match(scrutinee, arena_vec![self; then_arm, else_arm], desugar)
     }
 
+    /// Desugars `let PAT: TY = INIT else { ELS }; TAIL` into:
+    ///
+    /// ```rust
+    /// let _t: TY = INIT;
+    /// match _t {
+    ///     PAT => { TAIL }
+    ///     _ => { ELS }
+    /// }
+    /// ```
+    ///
+    /// where `TAIL` are the statements following the `let...else` in the enclosing block. The
+    /// intermediate binding is only introduced when there is a type annotation, so that the
+    /// initializer remains a coercion site; otherwise `INIT` is matched on directly, which keeps
+    /// by-reference bindings into places working. A scrutinee that isn't a place is wrapped in
+    /// `DropTemps`, so that the temporaries it creates are dropped before `TAIL` runs, just like
+    /// those of the `let` binding `_t`, unless a `let` would extend their lifetime to the
+    /// enclosing block. The statements preceding the `match` are pushed onto `stmts`.
+    pub(super) fn lower_let_else(
+        &mut self,
+        stmt: &Stmt,
+        local: &Local,
+        tail: &[Stmt],
+        stmts: &mut SmallVec<[hir::Stmt<'hir>; 8]>,
+    ) -> &'hir hir::Expr<'hir> {
+        let (init, els) = match (&local.init, &local.els) {
+            (Some(init), Some(els)) => (init, els),
+            _ => panic!("`let...else` without an initializer or `else` block"),
+        };
+        let span = self.mark_span_with_reason(DesugaringKind::LetElse, local.span, None);
+
+        let init = self.lower_expr(init);
+        let (ty, item_ids) = self.lower_local_ty(local);
+        for item_id in item_ids {
+            let item_id = hir::ItemId { id: self.lower_node_id(item_id) };
+            stmts.push(self.stmt(stmt.span, hir::StmtKind::Item(item_id)));
+        }
+        let scrutinee = match ty {
+            // `let _t: TY = INIT;`
+            Some(ty) => {
+                let ident = Ident::new(sym::val, span);
+                let (pat, binding) = self.pat_ident(span, ident);
+                let hir_local = hir::Local {
+                    hir_id: self.lower_node_id(local.id),
+                    ty: Some(ty),
+                    pat,
+                    init: Some(init),
+                    span,
+                    attrs: AttrVec::new(),
+                    source: hir::LocalSource::Normal,
+                };
+                stmts.push(self.stmt(span, hir::StmtKind::Local(self.arena.alloc(hir_local))));
+                self.expr_ident(span, ident, binding)
+            }
+            None if init.is_place_expr(|_| false) || has_extended_temporaries(init) => init,
+            // `match DropTemps(INIT)`
+            None => self.expr_drop_temps(span, init, AttrVec::new()),
+        };
+
+        // `PAT => { TAIL }`:
+        let pat = self.lower_pat(&local.pat);
+        let (tail_stmts, tail_expr) = self.lower_stmts(tail);
+        let tail_span = match tail {
+            [first, .., last] => first.span.to(last.span),
+            [only] => only.span,
+            [] => stmt.span.shrink_to_hi(),
+        };
+        let tail_block = self.block_all(tail_span, tail_stmts, tail_expr);
+        let tail_expr = self.arena.alloc(self.expr_block(tail_block, AttrVec::new()));
+        let then_arm = self.arm(pat, tail_expr);
+
+        // `_ => { ELS }`:
+        // This uses the unmarked span so that `irrefutable_let_patterns` still fires on it.
+        let else_pat = self.pat_wild(local.span);
+        let else_expr = self.arena.alloc(self.lower_block_expr(els));
+        let else_arm = self.arm(else_pat, else_expr);
+
+        let kind = hir::ExprKind::Match(
+            scrutinee,
+            arena_vec![self; then_arm, else_arm],
+            hir::MatchSource::LetElseDesugar,
+        );
+        self.arena.alloc(self.expr(span, kind, local.attrs.clone()))
+    }
+
     fn lower_expr_while_in_loop_scope(
         &mut self,
         span: Span,
@@ -478,7 +578,12 @@ impl<'hir> LoweringContext<'_, 'hir> {
             attrs: self.lower_attrs(&arm.attrs),
             pat: self.lower_pat(&arm.pat),
             guard: match arm.guard {
-                Some(ref x) => Some(hir::Guard::If(self.lower_expr(x))),
+                Some(ref cond) => match cond.kind {
+                    ExprKind::Let(ref pat, ref scrutinee) => {
+                        Some(hir::Guard::IfLet(self.lower_pat(pat), self.lower_expr(scrutinee)))
+                    }
+                    _ => Some(hir::Guard::If(self.lower_expr(cond))),
+                },
                 _ => None,
             },
             body: self.lower_expr(&arm.body),
@@ -840,6 +945,232 @@ impl<'hir> LoweringContext<'_, 'hir> {
         })
     }
 
+    /// Lowers `<lhs> = <rhs>`. Ordinary assignments to a place are lowered as is; if `<lhs>` is
+    /// a tuple, slice, struct or tuple struct expression, the assignment is destructured:
+    ///
+    /// ```ignore (pseudo-Rust)
+    /// {
+    ///     let (lhs, lhs) = <rhs>;
+    ///     a = lhs;
+    ///     b = lhs;
+    /// }
+    /// ```
+    ///
+    /// for `(a, b) = <rhs>`, where each `lhs` is a fresh binding.
+    fn lower_expr_assign(
+        &mut self,
+        lhs: &Expr,
+        rhs: &Expr,
+        eq_sign_span: Span,
+        whole_span: Span,
+    ) -> hir::ExprKind<'hir> {
+        if !self.is_destructuring_assignee(lhs) {
+            return hir::ExprKind::Assign(self.lower_expr(lhs), self.lower_expr(rhs), eq_sign_span);
+        }
+
+        if !self.sess.features_untracked().destructuring_assignment {
+            feature_err(
+                &self.sess.parse_sess,
+                sym::destructuring_assignment,
+                eq_sign_span,
+                "destructuring assignments are unstable",
+            )
+            .span_label(lhs.span, "cannot assign to this expression")
+            .emit();
+        }
+
+        let mut assignments = vec![];
+
+        // The left-hand side becomes a pattern, e.g. `(lhs, lhs)`.
+        let pat = self.destructure_assign(lhs, eq_sign_span, &mut assignments);
+        let rhs = self.lower_expr(rhs);
+
+        // `let (lhs, lhs) = <rhs>;`
+        let destructure_let = self.stmt_let_pat(
+            AttrVec::new(),
+            whole_span,
+            Some(rhs),
+            pat,
+            hir::LocalSource::AssignDesugar(eq_sign_span),
+        );
+
+        // `a = lhs; b = lhs;`
+        let stmts = self
+            .arena
+            .alloc_from_iter(std::iter::once(destructure_let).chain(assignments.into_iter()));
+
+        hir::ExprKind::Block(self.block_all(whole_span, stmts, None), None)
+    }
+
+    /// Whether `<lhs> = <rhs>` is a destructuring assignment rather than an assignment to a place.
+    fn is_destructuring_assignee(&mut self, lhs: &Expr) -> bool {
+        match &lhs.kind {
+            ExprKind::Array(..) | ExprKind::Struct(..) | ExprKind::Tup(..) => true,
+            ExprKind::Call(callee, ..) => self.extract_tuple_struct_path(callee).is_some(),
+            ExprKind::Paren(e) => match e.kind {
+                // `(..)` is destructured, for consistency with patterns.
+                ExprKind::Range(None, None, RangeLimits::HalfOpen) => true,
+                _ => self.is_destructuring_assignee(e),
+            },
+            _ => false,
+        }
+    }
+
+    /// If `callee` is the path of a tuple struct or tuple variant constructor, returns the path.
+    fn extract_tuple_struct_path<'a>(&mut self, callee: &'a Expr) -> Option<&'a Path> {
+        if let ExprKind::Path(None, path) = &callee.kind {
+            if let Some(partial_res) = self.resolver.get_partial_res(callee.id) {
+                if partial_res.unresolved_segments() == 0 {
+                    if let Res::Def(DefKind::Ctor(_, CtorKind::Fn), _) = partial_res.base_res() {
+                        return Some(path);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Converts the left-hand side of a destructuring assignment into a pattern, pushing an
+    /// assignment from a fresh binding in the pattern to each place in `lhs` onto `assignments`.
+    fn destructure_assign(
+        &mut self,
+        lhs: &Expr,
+        eq_sign_span: Span,
+        assignments: &mut Vec<hir::Stmt<'hir>>,
+    ) -> &'hir hir::Pat<'hir> {
+        match &lhs.kind {
+            // Slice patterns.
+            ExprKind::Array(elements) => {
+                let (pats, rest) =
+                    self.destructure_sequence(elements, "slice", eq_sign_span, assignments);
+                let slice_pat = if let Some((i, span)) = rest {
+                    let (before, after) = pats.split_at(i);
+                    hir::PatKind::Slice(before, Some(self.pat_wild(span)), after)
+                } else {
+                    hir::PatKind::Slice(pats, None, &[])
+                };
+                return self.pat(lhs.span, slice_pat);
+            }
+            // Tuple structs.
+            ExprKind::Call(callee, args) => {
+                if let Some(path) = self.extract_tuple_struct_path(callee) {
+                    let (pats, rest) = self.destructure_sequence(
+                        args,
+                        "tuple struct or variant",
+                        eq_sign_span,
+                        assignments,
+                    );
+                    let qpath = self.lower_qpath(
+                        callee.id,
+                        &None,
+                        path,
+                        ParamMode::Optional,
+                        ImplTraitContext::disallowed(),
+                    );
+                    let tuple_struct_pat =
+                        hir::PatKind::TupleStruct(qpath, pats, rest.map(|(i, _)| i));
+                    return self.pat(lhs.span, tuple_struct_pat);
+                }
+            }
+            // Structs.
+            ExprKind::Struct(path, fields, rest) => {
+                let field_pats = self.arena.alloc_from_iter(fields.iter().map(|f| {
+                    let pat = self.destructure_assign(&f.expr, eq_sign_span, assignments);
+                    hir::FieldPat {
+                        hir_id: self.next_id(),
+                        ident: f.ident,
+                        pat,
+                        is_shorthand: f.is_shorthand,
+                        span: f.span,
+                    }
+                }));
+                let qpath = self.lower_qpath(
+                    lhs.id,
+                    &None,
+                    path,
+                    ParamMode::Optional,
+                    ImplTraitContext::disallowed(),
+                );
+                let fields_omitted = match rest {
+                    StructRest::Base(e) => {
+                        self.sess
+                            .struct_span_err(
+                                e.span,
+                                "functional record updates are not allowed in destructuring \
+                                 assignments",
+                            )
+                            .span_suggestion(
+                                e.span,
+                                "consider removing the trailing pattern",
+                                String::new(),
+                                Applicability::MachineApplicable,
+                            )
+                            .emit();
+                        true
+                    }
+                    StructRest::Rest(_) => true,
+                    StructRest::None => false,
+                };
+                let struct_pat = hir::PatKind::Struct(qpath, field_pats, fields_omitted);
+                return self.pat(lhs.span, struct_pat);
+            }
+            // Tuples.
+            ExprKind::Tup(elements) => {
+                let (pats, rest) =
+                    self.destructure_sequence(elements, "tuple", eq_sign_span, assignments);
+                let tuple_pat = hir::PatKind::Tuple(pats, rest.map(|(i, _)| i));
+                return self.pat(lhs.span, tuple_pat);
+            }
+            ExprKind::Paren(e) => {
+                // `(..)` is destructured, for consistency with patterns.
+                if let ExprKind::Range(None, None, RangeLimits::HalfOpen) = e.kind {
+                    let tuple_pat = hir::PatKind::Tuple(&[], Some(0));
+                    return self.pat(lhs.span, tuple_pat);
+                } else {
+                    return self.destructure_assign(e, eq_sign_span, assignments);
+                }
+            }
+            _ => {}
+        }
+        // Everything else is assigned to as a place: `<lhs> = lhs`. If it is not a place, this
+        // is reported when type checking the assignment.
+        let ident = Ident::new(sym::lhs, lhs.span);
+        let (pat, binding) = self.pat_ident(lhs.span, ident);
+        let ident = self.expr_ident(lhs.span, ident, binding);
+        let assign = hir::ExprKind::Assign(self.lower_expr(lhs), ident, eq_sign_span);
+        let expr = self.expr(lhs.span, assign, ThinVec::new());
+        assignments.push(self.stmt_expr(lhs.span, expr));
+        pat
+    }
+
+    /// Destructures the elements of a tuple, tuple struct or slice on the left-hand side of a
+    /// destructuring assignment. Returns the patterns for the elements, and the index and span
+    /// of the `..`, if any.
+    fn destructure_sequence(
+        &mut self,
+        elements: &[AstP<Expr>],
+        ctx: &str,
+        eq_sign_span: Span,
+        assignments: &mut Vec<hir::Stmt<'hir>>,
+    ) -> (&'hir [&'hir hir::Pat<'hir>], Option<(usize, Span)>) {
+        let mut rest = None;
+        let elements =
+            self.arena.alloc_from_iter(elements.iter().enumerate().filter_map(|(i, e)| {
+                // `..` is a rest pattern.
+                if let ExprKind::Range(None, None, RangeLimits::HalfOpen) = e.kind {
+                    if let Some((_, prev_span)) = rest {
+                        self.ban_extra_rest_pat(e.span, prev_span, ctx);
+                    } else {
+                        rest = Some((i, e.span));
+                    }
+                    None
+                } else {
+                    Some(self.destructure_assign(e, eq_sign_span, assignments))
+                }
+            }));
+        (elements, rest)
+    }
+
     /// Desugar `<start>..=<end>` into `std::ops::RangeInclusive::new(<start>, <end>)`.
     fn lower_expr_range_closed(&mut self, span: Span, e1: &Expr, e2: &Expr) -> hir::ExprKind<'hir> {
         let id = self.next_id();
@@ -1828,3 +2159,20 @@ impl<'hir> LoweringContext<'_, 'hir> {
         }
     }
 }
+
+/// Returns whether `expr` creates temporaries that live until the end of the enclosing block when
+/// it initializes a `let`, mirroring `record_rvalue_scope_if_borrow_expr` in region resolution.
+fn has_extended_temporaries(expr: &hir::Expr<'_>) -> bool {
+    match expr.kind {
+        hir::ExprKind::AddrOf(..) => true,
+        hir::ExprKind::Struct(_, fields, _) => {
+            fields.iter().any(|field| has_extended_temporaries(&field.expr))
+        }
+        hir::ExprKind::Array(exprs) | hir::ExprKind::Tup(exprs) => {
+            exprs.iter().any(|expr| has_extended_temporaries(expr))
+        }
+        hir::ExprKind::Cast(ref expr, _) => has_extended_temporaries(expr),
+        hir::ExprKind::Block(ref block, _) => block.expr.map_or(false, has_extended_temporaries),
+        _ => false,
+    }
+}
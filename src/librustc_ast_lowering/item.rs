@@ -1,4 +1,4 @@
-use super::{AnonymousLifetimeMode, LoweringContext, ParamMode};
+use super::{AnonymousLifetimeMode, AsyncFnRet, LoweringContext, ParamMode};
 use super::{ImplTraitContext, ImplTraitPosition};
 use crate::Arena;
 
@@ -11,13 +11,16 @@ use rustc_errors::struct_span_err;
 use rustc_hir as hir;
 use rustc_hir::def::{DefKind, Res};
 use rustc_hir::def_id::LocalDefId;
+use rustc_hir::definitions::DefPathData;
+use rustc_span::hygiene::ExpnId;
 use rustc_span::source_map::{respan, DesugaringKind};
-use rustc_span::symbol::{kw, sym, Ident};
+use rustc_span::symbol::{kw, sym, Ident, Symbol};
 use rustc_span::Span;
 use rustc_target::spec::abi;
 
 use smallvec::{smallvec, SmallVec};
 use std::collections::BTreeSet;
+use std::iter;
 use tracing::debug;
 
 pub(super) struct ItemLowerer<'a, 'lowering, 'hir> {
@@ -269,7 +272,7 @@ impl<'hir> LoweringContext<'_, 'hir> {
                         fn_def_id,
                         AnonymousLifetimeMode::PassThrough,
                         |this, idty| {
-                            let ret_id = asyncness.opt_return_id();
+                            let ret_id = asyncness.opt_return_id().map(AsyncFnRet::Opaque);
                             this.lower_fn_decl(
                                 &decl,
                                 Some((fn_def_id.to_def_id(), idty)),
@@ -381,11 +384,22 @@ impl<'hir> LoweringContext<'_, 'hir> {
                     },
                 );
 
+                let self_res = trait_ref.as_ref().map(|trait_ref| {
+                    let trait_def_id = match trait_ref.path.res {
+                        Res::Def(DefKind::Trait, def_id) => Some(def_id),
+                        _ => None,
+                    };
+                    Res::SelfTy(trait_def_id, Some(def_id.to_def_id()))
+                });
                 let new_impl_items =
                     self.with_in_scope_lifetime_defs(&ast_generics.params, |this| {
-                        this.arena.alloc_from_iter(
-                            impl_items.iter().map(|item| this.lower_impl_item_ref(item)),
-                        )
+                        this.arena.alloc_from_iter(impl_items.iter().flat_map(|item| {
+                            let item_ref = this.lower_impl_item_ref(item);
+                            let assoc_ty_ref = self_res.and_then(|self_res| {
+                                this.lower_async_fn_assoc_ty_impl_ref(item, def_id, self_res)
+                            });
+                            iter::once(item_ref).chain(assoc_ty_ref)
+                        }))
                     });
 
                 // `defaultness.has_value()` is never called for an `impl`, always `true` in order
@@ -406,9 +420,12 @@ impl<'hir> LoweringContext<'_, 'hir> {
             }
             ItemKind::Trait(is_auto, unsafety, ref generics, ref bounds, ref items) => {
                 let bounds = self.lower_param_bounds(bounds, ImplTraitContext::disallowed());
-                let items = self
-                    .arena
-                    .alloc_from_iter(items.iter().map(|item| self.lower_trait_item_ref(item)));
+                let def_id = self.resolver.local_def_id(id);
+                let items = self.arena.alloc_from_iter(items.iter().flat_map(|item| {
+                    let item_ref = self.lower_trait_item_ref(item);
+                    let assoc_ty_ref = self.lower_async_fn_assoc_ty_trait_ref(item, def_id);
+                    iter::once(item_ref).chain(assoc_ty_ref)
+                }));
                 hir::ItemKind::Trait(
                     is_auto,
                     self.lower_unsafety(unsafety),
@@ -756,14 +773,16 @@ impl<'hir> LoweringContext<'_, 'hir> {
             }
             AssocItemKind::Fn(_, ref sig, ref generics, None) => {
                 let names = self.lower_fn_params_to_names(&sig.decl);
+                let async_ret = self.async_fn_assoc_ty_ret(i, None);
                 let (generics, sig) =
-                    self.lower_method_sig(generics, sig, trait_item_def_id, false, None);
+                    self.lower_method_sig(generics, sig, trait_item_def_id, false, async_ret);
                 (generics, hir::TraitItemKind::Fn(sig, hir::TraitFn::Required(names)))
             }
             AssocItemKind::Fn(_, ref sig, ref generics, Some(ref body)) => {
                 let body_id = self.lower_fn_body_block(i.span, &sig.decl, Some(body));
+                let async_ret = self.async_fn_assoc_ty_ret(i, None);
                 let (generics, sig) =
-                    self.lower_method_sig(generics, sig, trait_item_def_id, false, None);
+                    self.lower_method_sig(generics, sig, trait_item_def_id, false, async_ret);
                 (generics, hir::TraitItemKind::Fn(sig, hir::TraitFn::Provided(body_id)))
             }
             AssocItemKind::TyAlias(_, ref generics, ref bounds, ref default) => {
@@ -827,12 +846,15 @@ impl<'hir> LoweringContext<'_, 'hir> {
                 let body_id =
                     self.lower_maybe_async_body(i.span, &sig.decl, asyncness, body.as_deref());
                 let impl_trait_return_allow = !self.is_in_trait_impl;
+                let async_ret = self
+                    .async_fn_assoc_ty_ret(i, asyncness.opt_return_id())
+                    .or_else(|| asyncness.opt_return_id().map(AsyncFnRet::Opaque));
                 let (generics, sig) = self.lower_method_sig(
                     generics,
                     sig,
                     impl_item_def_id,
                     impl_trait_return_allow,
-                    asyncness.opt_return_id(),
+                    async_ret,
                 );
 
                 (generics, hir::ImplItemKind::Fn(sig, body_id))
@@ -896,6 +918,87 @@ impl<'hir> LoweringContext<'_, 'hir> {
         }
     }
 
+    /// With `#![feature(async_fn_in_trait)]`, an `async fn` of a trait or trait impl returns an
+    /// anonymous associated type of its own (see `lower_async_fn_ret_assoc_ty`). This creates
+    /// the definition of that type for the method `i` of the trait or impl `parent`, so that the
+    /// type can be referred to before the method itself is lowered.
+    fn create_async_fn_assoc_ty(
+        &mut self,
+        i: &AssocItem,
+        parent: LocalDefId,
+        self_res: Res,
+    ) -> Option<(hir::HirId, Ident)> {
+        match i.kind {
+            AssocItemKind::Fn(_, ref sig, ..) if sig.header.asyncness.is_async() => {}
+            _ => return None,
+        }
+        if !self.sess.features_untracked().async_fn_in_trait {
+            return None;
+        }
+
+        let node_id = self.resolver.next_node_id();
+        let ident = async_fn_assoc_ty_ident(i.ident);
+        self.resolver.create_def(
+            parent,
+            node_id,
+            DefPathData::TypeNs(ident.name),
+            ExpnId::root(),
+            i.span,
+        );
+        self.async_fn_assoc_tys.insert(i.id, (node_id, self_res));
+        Some((self.allocate_hir_id_counter(node_id), ident))
+    }
+
+    fn lower_async_fn_assoc_ty_trait_ref(
+        &mut self,
+        i: &AssocItem,
+        trait_def_id: LocalDefId,
+    ) -> Option<hir::TraitItemRef> {
+        let self_res = Res::SelfTy(Some(trait_def_id.to_def_id()), None);
+        let (hir_id, ident) = self.create_async_fn_assoc_ty(i, trait_def_id, self_res)?;
+        Some(hir::TraitItemRef {
+            id: hir::TraitItemId { hir_id },
+            ident,
+            span: i.span,
+            defaultness: hir::Defaultness::Default { has_value: false },
+            kind: hir::AssocItemKind::Type,
+        })
+    }
+
+    fn lower_async_fn_assoc_ty_impl_ref(
+        &mut self,
+        i: &AssocItem,
+        impl_def_id: LocalDefId,
+        self_res: Res,
+    ) -> Option<hir::ImplItemRef<'hir>> {
+        let (hir_id, ident) = self.create_async_fn_assoc_ty(i, impl_def_id, self_res)?;
+        Some(hir::ImplItemRef {
+            id: hir::ImplItemId { hir_id },
+            ident,
+            span: i.span,
+            vis: respan(i.span.shrink_to_lo(), hir::VisibilityKind::Inherited),
+            defaultness: hir::Defaultness::Final,
+            kind: hir::AssocItemKind::Type,
+        })
+    }
+
+    /// The return type of the method `i` if it returns an anonymous associated type, which in a
+    /// trait impl is defined as the opaque type `opaque_ty`.
+    fn async_fn_assoc_ty_ret(
+        &self,
+        i: &AssocItem,
+        opaque_ty: Option<NodeId>,
+    ) -> Option<AsyncFnRet> {
+        let &(assoc_ty, self_res) = self.async_fn_assoc_tys.get(&i.id)?;
+        Some(AsyncFnRet::AssocTy {
+            assoc_ty,
+            ident: async_fn_assoc_ty_ident(i.ident),
+            self_res,
+            opaque_ty,
+            parent_lifetimes: self.in_scope_lifetimes.len(),
+        })
+    }
+
     /// If an `explicit_owner` is given, this method allocates the `HirId` in
     /// the address space of that item instead of the item currently being
     /// lowered. This can happen during `lower_impl_item_ref()` where we need to
@@ -1215,7 +1318,7 @@ impl<'hir> LoweringContext<'_, 'hir> {
         sig: &FnSig,
         fn_def_id: LocalDefId,
         impl_trait_return_allow: bool,
-        is_async: Option<NodeId>,
+        is_async: Option<AsyncFnRet>,
     ) -> (hir::Generics<'hir>, hir::FnSig<'hir>) {
         let header = self.lower_fn_header(sig.header);
         let (generics, decl) = self.add_in_band_defs(
@@ -1435,3 +1538,10 @@ impl<'hir> GenericsCtor<'hir> {
         }
     }
 }
+
+/// The name of the anonymous associated type returned by the `async fn` named `fn_ident` in a
+/// trait or trait impl. It can't be written in source code, so it never conflicts with the
+/// names of other associated types.
+fn async_fn_assoc_ty_ident(fn_ident: Ident) -> Ident {
+    Ident::new(Symbol::intern(&format!("{{async fn {}}}", fn_ident.name)), fn_ident.span)
+}
@@ -163,6 +163,10 @@ struct LoweringContext<'a, 'hir: 'a> {
 
     type_def_lifetime_params: DefIdMap<usize>,
 
+    /// The anonymous associated types returned by the `async fn`s of traits and trait impls,
+    /// keyed by the method, along with what `Self` resolves to in the trait or impl.
+    async_fn_assoc_tys: NodeMap<(NodeId, Res)>,
+
     current_hir_id_owner: Vec<(LocalDefId, u32)>,
     item_local_id_counters: NodeMap<u32>,
     node_id_to_hir_id: IndexVec<NodeId, Option<hir::HirId>>,
@@ -325,6 +329,7 @@ pub fn lower_crate<'a, 'hir>(
         is_in_dyn_type: false,
         anonymous_lifetime_mode: AnonymousLifetimeMode::PassThrough,
         type_def_lifetime_params: Default::default(),
+        async_fn_assoc_tys: Default::default(),
         current_module: hir::CRATE_HIR_ID,
         current_hir_id_owner: vec![(LocalDefId { local_def_index: CRATE_DEF_INDEX }, 0)],
         item_local_id_counters: Default::default(),
@@ -404,6 +409,33 @@ enum AnonymousLifetimeMode {
 
     /// Pass responsibility to `resolve_lifetime` code for all cases.
     PassThrough,
+
+    /// Use the given lifetime for `&` and `'_`, and for elided lifetimes in paths. Used for the
+    /// return type of an `async fn` in a trait or trait impl, which is lowered to an associated
+    /// type whose arguments `resolve_lifetime` can't elide.
+    Replace(hir::LifetimeName),
+}
+
+/// What the return type of an `async fn` is lowered to.
+#[derive(Copy, Clone, Debug)]
+enum AsyncFnRet {
+    /// `impl Future<Output = T>`, with the `NodeId` of the opaque type.
+    Opaque(NodeId),
+
+    /// `Self::{async fn foo}<'a, ..>`, an anonymous generic associated type bounded by
+    /// `Future<Output = T>` that is declared by the trait and defined by each trait impl.
+    /// Used with `#![feature(async_fn_in_trait)]`.
+    AssocTy {
+        assoc_ty: NodeId,
+        ident: Ident,
+        /// What `Self` resolves to in the trait or impl.
+        self_res: Res,
+        /// In a trait impl, the opaque type that the associated type is defined as.
+        opaque_ty: Option<NodeId>,
+        /// The number of lifetimes in `in_scope_lifetimes` that belong to the trait or impl
+        /// rather than to the method.
+        parent_lifetimes: usize,
+    },
 }
 
 struct ImplTraitTypeIdVisitor<'a> {
@@ -1693,6 +1725,28 @@ impl<'a, 'hir> LoweringContext<'a, 'hir> {
     }
 
     fn lower_local(&mut self, l: &Local) -> (hir::Local<'hir>, SmallVec<[NodeId; 1]>) {
+        let (ty, ids) = self.lower_local_ty(l);
+        let init = l.init.as_ref().map(|e| self.lower_expr(e));
+        (
+            hir::Local {
+                hir_id: self.lower_node_id(l.id),
+                ty,
+                pat: self.lower_pat(&l.pat),
+                init,
+                span: l.span,
+                attrs: l.attrs.clone(),
+                source: hir::LocalSource::Normal,
+            },
+            ids,
+        )
+    }
+
+    /// Lowers the type annotation of a `let` statement, if any, together with the `NodeId`s of
+    /// the `impl Trait` items it introduces.
+    fn lower_local_ty(
+        &mut self,
+        l: &Local,
+    ) -> (Option<&'hir hir::Ty<'hir>>, SmallVec<[NodeId; 1]>) {
         let mut ids = SmallVec::<[NodeId; 1]>::new();
         if self.sess.features_untracked().impl_trait_in_bindings {
             if let Some(ref ty) = l.ty {
@@ -1715,19 +1769,7 @@ impl<'a, 'hir> LoweringContext<'a, 'hir> {
                 },
             )
         });
-        let init = l.init.as_ref().map(|e| self.lower_expr(e));
-        (
-            hir::Local {
-                hir_id: self.lower_node_id(l.id),
-                ty,
-                pat: self.lower_pat(&l.pat),
-                init,
-                span: l.span,
-                attrs: l.attrs.clone(),
-                source: hir::LocalSource::Normal,
-            },
-            ids,
-        )
+        (ty, ids)
     }
 
     fn lower_fn_params_to_names(&mut self, decl: &FnDecl) -> &'hir [Ident] {
@@ -1761,7 +1803,7 @@ impl<'a, 'hir> LoweringContext<'a, 'hir> {
         decl: &FnDecl,
         mut in_band_ty_params: Option<(DefId, &mut Vec<hir::GenericParam<'hir>>)>,
         impl_trait_return_allow: bool,
-        make_ret_async: Option<NodeId>,
+        make_ret_async: Option<AsyncFnRet>,
     ) -> &'hir hir::FnDecl<'hir> {
         debug!(
             "lower_fn_decl(\
@@ -1801,12 +1843,24 @@ impl<'a, 'hir> LoweringContext<'a, 'hir> {
             }))
         });
 
-        let output = if let Some(ret_id) = make_ret_async {
-            self.lower_async_fn_ret_ty(
-                &decl.output,
-                in_band_ty_params.expect("`make_ret_async` but no `fn_def_id`").0,
-                ret_id,
-            )
+        let output = if let Some(async_ret) = make_ret_async {
+            let fn_def_id = in_band_ty_params.expect("`make_ret_async` but no `fn_def_id`").0;
+            match async_ret {
+                AsyncFnRet::Opaque(ret_id) => {
+                    self.lower_async_fn_ret_ty(&decl.output, fn_def_id, ret_id)
+                }
+                AsyncFnRet::AssocTy { assoc_ty, ident, self_res, opaque_ty, parent_lifetimes } => {
+                    self.lower_async_fn_ret_assoc_ty(
+                        &decl.output,
+                        elided_output_lifetime(decl, inputs),
+                        assoc_ty,
+                        ident,
+                        self_res,
+                        opaque_ty,
+                        parent_lifetimes,
+                    )
+                }
+            }
         } else {
             match decl.output {
                 FnRetTy::Ty(ref ty) => {
@@ -1946,9 +2000,17 @@ impl<'a, 'hir> LoweringContext<'a, 'hir> {
             //
             // Then, we will create `fn foo(..) -> Foo<'_, '_>`, and
             // hence the elision takes place at the fn site.
+            //
+            // Not `OpaqueTyOrigin::AsyncFn`: that's only used for the
+            // `impl Future` opaque type that `async fn` implicitly
+            // generates.
+            let context = ImplTraitContext::ReturnPositionOpaqueTy {
+                fn_def_id,
+                origin: hir::OpaqueTyOrigin::FnReturn,
+            };
             let future_bound = this
                 .with_anonymous_lifetime_mode(AnonymousLifetimeMode::CreateParameter, |this| {
-                    this.lower_async_fn_output_type_to_future_bound(output, fn_def_id, span)
+                    this.lower_async_fn_output_type_to_future_bound(output, context, span)
                 });
 
             debug!("lower_async_fn_ret_ty: future_bound={:#?}", future_bound);
@@ -2021,6 +2083,7 @@ impl<'a, 'hir> LoweringContext<'a, 'hir> {
                 })
             })
             .collect();
+        // Keep the signature well-formed after the error above.
         generic_args.extend(lifetime_params[input_lifetimes_count..].iter().map(|&(span, _)|
             // Output lifetime like `'_`.
             GenericArg::Lifetime(hir::Lifetime {
@@ -2039,25 +2102,225 @@ impl<'a, 'hir> LoweringContext<'a, 'hir> {
         hir::FnRetTy::Return(self.arena.alloc(opaque_ty))
     }
 
+    /// Lowers the return type of an `async fn` in a trait or trait impl to an anonymous generic
+    /// associated type. Given
+    ///
+    /// ```rust
+    /// trait Foo {
+    ///     async fn bar<'a>(&self, x: &'a Vec<f64>) -> u32;
+    /// }
+    ///
+    /// impl Foo for Baz {
+    ///     async fn bar<'a>(&self, x: &'a Vec<f64>) -> u32 { .. }
+    /// }
+    /// ```
+    ///
+    /// the trait declares the associated type and the method returns it:
+    ///
+    /// ```rust
+    /// trait Foo {
+    ///     type {async fn bar}<'a, '0>: Future<Output = u32>;
+    ///     fn bar<'a, '0>(&'0 self, x: &'a Vec<f64>) -> Self::{async fn bar}<'a, '0>;
+    /// }
+    /// ```
+    ///
+    /// and the impl defines it as an opaque type, constrained by the body of the method:
+    ///
+    /// ```rust
+    /// impl Foo for Baz {
+    ///     type {async fn bar}<'a, '0> = impl Future<Output = u32>;
+    ///     fn bar<'a, '0>(&'0 self, x: &'a Vec<f64>) -> Self::{async fn bar}<'a, '0> { .. }
+    /// }
+    /// ```
+    ///
+    /// Like the opaque type of any other `async fn` (see `lower_async_fn_ret_ty`), the
+    /// associated type captures all the lifetimes of the method that don't belong to the trait
+    /// or impl. This means that the impl has to use lifetimes in its signature the same way
+    /// the trait does.
+    ///
+    /// Lifetimes elided in the return type are resolved here to `elided_lifetime`, the lifetime
+    /// that lifetime elision picks for the method. They can't be passed to the associated type
+    /// as `'_` instead: they usually resolve to one of the input lifetimes, so the opaque type
+    /// in the impl would be used with the same lifetime twice, which doesn't define it.
+    fn lower_async_fn_ret_assoc_ty(
+        &mut self,
+        output: &FnRetTy,
+        elided_lifetime: Option<hir::LifetimeName>,
+        assoc_ty_node_id: NodeId,
+        ident: Ident,
+        self_res: Res,
+        opaque_ty_node_id: Option<NodeId>,
+        parent_lifetimes: usize,
+    ) -> hir::FnRetTy<'hir> {
+        debug!(
+            "lower_async_fn_ret_assoc_ty(\
+             output={:?}, \
+             assoc_ty_node_id={:?}, \
+             opaque_ty_node_id={:?})",
+            output, assoc_ty_node_id, opaque_ty_node_id,
+        );
+
+        let span = output.span();
+        let assoc_ty_span = self.mark_span_with_reason(DesugaringKind::Async, span, None);
+        let assoc_ty_def_id = self.resolver.local_def_id(assoc_ty_node_id);
+
+        let lifetime_params = self.with_hir_id_owner(assoc_ty_node_id, |this| {
+            // Without a lifetime to elide to, elided lifetimes are an error, like in any other
+            // function signature.
+            let lifetime_mode = match elided_lifetime {
+                Some(name) => AnonymousLifetimeMode::Replace(name),
+                None => AnonymousLifetimeMode::ReportError,
+            };
+            let lower_future_bound = |this: &mut Self| {
+                this.with_anonymous_lifetime_mode(lifetime_mode, |this| {
+                    this.lower_async_fn_output_type_to_future_bound(
+                        output,
+                        ImplTraitContext::disallowed(),
+                        span,
+                    )
+                })
+            };
+            let all_lifetime_params = |this: &Self| -> Vec<(Span, ParamName)> {
+                this.in_scope_lifetimes
+                    .iter()
+                    .cloned()
+                    .map(|name| (name.ident().span, name))
+                    .chain(this.lifetimes_to_define.iter().cloned())
+                    .collect()
+            };
+
+            let assoc_ty_generics = |this: &mut Self, lifetime_params: &[(Span, ParamName)]| {
+                let generic_params = this.arena.alloc_from_iter(
+                    lifetime_params[parent_lifetimes..].iter().map(|&(span, hir_name)| {
+                        this.lifetime_to_generic_param(span, hir_name, assoc_ty_def_id)
+                    }),
+                );
+                hir::Generics {
+                    params: generic_params,
+                    where_clause: hir::WhereClause { predicates: &[], span },
+                    span,
+                }
+            };
+
+            // This item does not exist in the AST, so we have to insert it ourselves.
+            let hir_id = this.lower_node_id(assoc_ty_node_id);
+            match opaque_ty_node_id {
+                // `type {async fn bar}<..>: Future<Output = T>;`
+                None => {
+                    let future_bound = lower_future_bound(this);
+                    let lifetime_params = all_lifetime_params(this);
+                    let item = hir::TraitItem {
+                        ident,
+                        hir_id,
+                        attrs: &[],
+                        generics: assoc_ty_generics(this, &lifetime_params),
+                        kind: hir::TraitItemKind::Type(arena_vec![this; future_bound], None),
+                        span: assoc_ty_span,
+                    };
+                    let id = hir::TraitItemId { hir_id };
+                    this.trait_items.insert(id, item);
+                    this.modules.get_mut(&this.current_module).unwrap().trait_items.insert(id);
+                    lifetime_params
+                }
+                // `type {async fn bar}<..> = impl Future<Output = T>;`
+                Some(opaque_ty_node_id) => {
+                    let opaque_ty_span =
+                        this.mark_span_with_reason(DesugaringKind::OpaqueTy, span, None);
+                    this.allocate_hir_id_counter(opaque_ty_node_id);
+                    let future_bound =
+                        this.with_hir_id_owner(opaque_ty_node_id, lower_future_bound);
+
+                    // The opaque type is nested in the associated type and only uses the
+                    // lifetimes that the associated type declares, so unlike the opaque type of
+                    // a plain `async fn` it doesn't need any generic parameters of its own.
+                    let lifetime_params = all_lifetime_params(this);
+                    let opaque_ty_id = this.with_hir_id_owner(opaque_ty_node_id, |this| {
+                        let opaque_ty_item = hir::OpaqueTy {
+                            generics: hir::Generics {
+                                params: &[],
+                                where_clause: hir::WhereClause { predicates: &[], span },
+                                span,
+                            },
+                            bounds: arena_vec![this; future_bound],
+                            impl_trait_fn: None,
+                            origin: hir::OpaqueTyOrigin::Misc,
+                        };
+                        this.generate_opaque_type(
+                            opaque_ty_node_id,
+                            opaque_ty_item,
+                            span,
+                            opaque_ty_span,
+                        )
+                    });
+
+                    let opaque_ty_ref =
+                        hir::TyKind::OpaqueDef(hir::ItemId { id: opaque_ty_id }, &[]);
+                    let opaque_ty = this.arena.alloc(this.ty(opaque_ty_span, opaque_ty_ref));
+
+                    let item = hir::ImplItem {
+                        ident,
+                        hir_id,
+                        vis: respan(span.shrink_to_lo(), hir::VisibilityKind::Inherited),
+                        defaultness: hir::Defaultness::Final,
+                        attrs: &[],
+                        generics: assoc_ty_generics(this, &lifetime_params),
+                        kind: hir::ImplItemKind::TyAlias(opaque_ty),
+                        span: assoc_ty_span,
+                    };
+                    let id = hir::ImplItemId { hir_id };
+                    this.impl_items.insert(id, item);
+                    this.modules.get_mut(&this.current_module).unwrap().impl_items.insert(id);
+                    lifetime_params
+                }
+            }
+        });
+
+        // `Self::{async fn bar}<'a, '0>`
+        let generic_args: Vec<_> = lifetime_params[parent_lifetimes..]
+            .iter()
+            .map(|&(span, hir_name)| {
+                GenericArg::Lifetime(hir::Lifetime {
+                    hir_id: self.next_id(),
+                    span,
+                    name: hir::LifetimeName::Param(hir_name),
+                })
+            })
+            .collect();
+        let generic_args = self.arena.alloc(hir::GenericArgs {
+            args: self.arena.alloc_from_iter(generic_args),
+            bindings: &[],
+            parenthesized: false,
+        });
+
+        let self_segment = hir::PathSegment::from_ident(Ident::new(kw::SelfUpper, span));
+        let self_path = self.arena.alloc(hir::Path {
+            span,
+            res: self_res,
+            segments: arena_vec![self; self_segment],
+        });
+        let self_ty = self.ty_path(self.next_id(), span, hir::QPath::Resolved(None, self_path));
+        let segment = hir::PathSegment {
+            ident,
+            hir_id: None,
+            res: None,
+            args: Some(generic_args),
+            infer_args: false,
+        };
+        let qpath = hir::QPath::TypeRelative(self.arena.alloc(self_ty), self.arena.alloc(segment));
+        let assoc_ty = self.ty(assoc_ty_span, hir::TyKind::Path(qpath));
+        hir::FnRetTy::Return(self.arena.alloc(assoc_ty))
+    }
+
     /// Transforms `-> T` into `Future<Output = T>`
     fn lower_async_fn_output_type_to_future_bound(
         &mut self,
         output: &FnRetTy,
-        fn_def_id: DefId,
+        itctx: ImplTraitContext<'_, 'hir>,
         span: Span,
     ) -> hir::GenericBound<'hir> {
         // Compute the `T` in `Future<Output = T>` from the return type.
         let output_ty = match output {
-            FnRetTy::Ty(ty) => {
-                // Not `OpaqueTyOrigin::AsyncFn`: that's only used for the
-                // `impl Future` opaque type that `async fn` implicitly
-                // generates.
-                let context = ImplTraitContext::ReturnPositionOpaqueTy {
-                    fn_def_id,
-                    origin: hir::OpaqueTyOrigin::FnReturn,
-                };
-                self.lower_ty(ty, context)
-            }
+            FnRetTy::Ty(ty) => self.lower_ty(ty, itctx),
             FnRetTy::Default(ret_ty_span) => self.arena.alloc(self.ty_tup(*ret_ty_span, &[])),
         };
 
@@ -2115,6 +2378,8 @@ impl<'a, 'hir> LoweringContext<'a, 'hir> {
                 }
 
                 AnonymousLifetimeMode::ReportError => self.new_error_lifetime(Some(l.id), span),
+
+                AnonymousLifetimeMode::Replace(name) => self.new_named_lifetime(l.id, span, name),
             },
             ident => {
                 self.maybe_collect_in_band_lifetime(ident);
@@ -2316,24 +2581,10 @@ impl<'a, 'hir> LoweringContext<'a, 'hir> {
     }
 
     fn lower_block_noalloc(&mut self, b: &Block, targeted_by_break: bool) -> hir::Block<'hir> {
-        let mut stmts = vec![];
-        let mut expr: Option<&'hir _> = None;
-
-        for (index, stmt) in b.stmts.iter().enumerate() {
-            if index == b.stmts.len() - 1 {
-                if let StmtKind::Expr(ref e) = stmt.kind {
-                    expr = Some(self.lower_expr(e));
-                } else {
-                    stmts.extend(self.lower_stmt(stmt));
-                }
-            } else {
-                stmts.extend(self.lower_stmt(stmt));
-            }
-        }
-
+        let (stmts, expr) = self.lower_stmts(&b.stmts);
         hir::Block {
             hir_id: self.lower_node_id(b.id),
-            stmts: self.arena.alloc_from_iter(stmts),
+            stmts,
             expr,
             rules: self.lower_block_check_mode(&b.rules),
             span: b.span,
@@ -2341,6 +2592,29 @@ impl<'a, 'hir> LoweringContext<'a, 'hir> {
         }
     }
 
+    /// Lowers the statements of a block, returning the lowered statements and the trailing
+    /// expression, if any. A `let...else` statement swallows all of the statements following
+    /// it, which end up inside the `match` it is desugared into (see `lower_let_else`).
+    fn lower_stmts(
+        &mut self,
+        mut ast_stmts: &[Stmt],
+    ) -> (&'hir [hir::Stmt<'hir>], Option<&'hir hir::Expr<'hir>>) {
+        let mut stmts = SmallVec::<[hir::Stmt<'hir>; 8]>::new();
+        let mut expr = None;
+        while let [s, tail @ ..] = ast_stmts {
+            match s.kind {
+                StmtKind::Local(ref local) if local.els.is_some() => {
+                    expr = Some(self.lower_let_else(s, local, tail, &mut stmts));
+                    break;
+                }
+                StmtKind::Expr(ref e) if tail.is_empty() => expr = Some(self.lower_expr(e)),
+                _ => stmts.extend(self.lower_stmt(s)),
+            }
+            ast_stmts = tail;
+        }
+        (self.arena.alloc_from_iter(stmts), expr)
+    }
+
     /// Lowers a block directly to an expression, presuming that it
     /// has no attributes and is not targeted by a `break`.
     fn lower_block_expr(&mut self, b: &Block) -> hir::Expr<'hir> {
@@ -2629,6 +2903,10 @@ impl<'a, 'hir> LoweringContext<'a, 'hir> {
             AnonymousLifetimeMode::ReportError => self.new_error_lifetime(None, span),
 
             AnonymousLifetimeMode::PassThrough => self.new_implicit_lifetime(span),
+
+            AnonymousLifetimeMode::Replace(name) => {
+                hir::Lifetime { hir_id: self.next_id(), span, name }
+            }
         }
     }
 
@@ -2682,6 +2960,9 @@ impl<'a, 'hir> LoweringContext<'a, 'hir> {
             AnonymousLifetimeMode::PassThrough | AnonymousLifetimeMode::ReportError => {
                 self.new_implicit_lifetime(span)
             }
+            AnonymousLifetimeMode::Replace(name) => {
+                hir::Lifetime { hir_id: self.next_id(), span, name }
+            }
         }
     }
 
@@ -2712,6 +2993,9 @@ impl<'a, 'hir> LoweringContext<'a, 'hir> {
 
             // This is the normal case.
             AnonymousLifetimeMode::PassThrough => {}
+
+            // Object lifetime defaults still apply, they are not elided lifetimes.
+            AnonymousLifetimeMode::Replace(_) => {}
         }
 
         let r = hir::Lifetime {
@@ -2756,6 +3040,66 @@ fn body_ids(bodies: &BTreeMap<hir::BodyId, hir::Body<'_>>) -> Vec<hir::BodyId> {
     body_ids
 }
 
+/// The lifetime that lifetime elision picks for the return type of `decl`, given its lowered
+/// `inputs`: the lifetime of `&self` or `&mut self` if there is one, otherwise the only lifetime
+/// used in the inputs. Returns `None` if elided lifetimes in the return type are an error.
+fn elided_output_lifetime(decl: &FnDecl, inputs: &[hir::Ty<'_>]) -> Option<hir::LifetimeName> {
+    // Collects the lifetimes of the inputs that elision can pick, skipping `fn()` types and
+    // `Fn()` bounds, which have their own elision scope.
+    struct InputLifetimeCollector {
+        lifetimes: FxHashSet<hir::LifetimeName>,
+    }
+
+    impl<'v> intravisit::Visitor<'v> for InputLifetimeCollector {
+        type Map = intravisit::ErasedMap<'v>;
+
+        fn nested_visit_map(&mut self) -> intravisit::NestedVisitorMap<Self::Map> {
+            intravisit::NestedVisitorMap::None
+        }
+
+        fn visit_generic_args(&mut self, span: Span, parameters: &'v hir::GenericArgs<'v>) {
+            if !parameters.parenthesized {
+                intravisit::walk_generic_args(self, span, parameters);
+            }
+        }
+
+        fn visit_ty(&mut self, t: &'v hir::Ty<'v>) {
+            if let hir::TyKind::BareFn(_) = t.kind {
+                return;
+            }
+            intravisit::walk_ty(self, t);
+        }
+
+        fn visit_lifetime(&mut self, lifetime: &'v hir::Lifetime) {
+            match lifetime.name {
+                hir::LifetimeName::Param(_) | hir::LifetimeName::Static => {
+                    self.lifetimes.insert(lifetime.name);
+                }
+                hir::LifetimeName::Implicit
+                | hir::LifetimeName::ImplicitObjectLifetimeDefault
+                | hir::LifetimeName::Error
+                | hir::LifetimeName::Underscore => {}
+            }
+        }
+    }
+
+    if let (Some(param), Some(input)) = (decl.inputs.first(), inputs.first()) {
+        if let (TyKind::Rptr(_, ref mt), hir::TyKind::Rptr(ref lifetime, _)) =
+            (&param.ty.kind, &input.kind)
+        {
+            if mt.ty.kind.is_implicit_self() {
+                return Some(lifetime.name);
+            }
+        }
+    }
+
+    let mut collector = InputLifetimeCollector { lifetimes: FxHashSet::default() };
+    for input in inputs {
+        intravisit::Visitor::visit_ty(&mut collector, input);
+    }
+    if collector.lifetimes.len() == 1 { collector.lifetimes.into_iter().next() } else { None }
+}
+
 /// Helper struct for delayed construction of GenericArgs.
 struct GenericArgsCtor<'hir> {
     args: SmallVec<[hir::GenericArg<'hir>; 4]>,
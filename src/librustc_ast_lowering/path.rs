@@ -321,7 +321,9 @@ impl<'a, 'hir> LoweringContext<'a, 'hir> {
                         );
                         err.emit();
                     }
-                    AnonymousLifetimeMode::PassThrough | AnonymousLifetimeMode::ReportError => {
+                    AnonymousLifetimeMode::PassThrough
+                    | AnonymousLifetimeMode::ReportError
+                    | AnonymousLifetimeMode::Replace(_) => {
                         self.resolver.lint_buffer().buffer_lint_with_diagnostic(
                             ELIDED_LIFETIMES_IN_PATHS,
                             CRATE_NODE_ID,
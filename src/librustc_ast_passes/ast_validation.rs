@@ -227,16 +227,81 @@ impl<'a> AstValidator<'a> {
 
     fn check_trait_fn_not_async(&self, fn_span: Span, asyncness: Async) {
         if let Async::Yes { span, .. } = asyncness {
-            struct_span_err!(
+            if self.session.features_untracked().async_fn_in_trait {
+                return;
+            }
+            let mut err = struct_span_err!(
                 self.session,
                 fn_span,
                 E0706,
                 "functions in traits cannot be declared `async`"
-            )
-            .span_label(span, "`async` because of this")
-            .note("`async` trait functions are not currently supported")
-            .note("consider using the `async-trait` crate: https://crates.io/crates/async-trait")
-            .emit();
+            );
+            err.span_label(span, "`async` because of this");
+            err.note("`async` trait functions are not currently supported");
+            err.note(
+                "consider using the `async-trait` crate: https://crates.io/crates/async-trait",
+            );
+            if self.session.opts.unstable_features.is_nightly_build() {
+                err.help("add `#![feature(async_fn_in_trait)]` to the crate attributes to enable");
+            }
+            err.emit();
+        }
+    }
+
+    /// An `async fn` in a trait returns an anonymous generic associated type, which for now can
+    /// only be generic over lifetimes and can't be given a default in the trait.
+    fn check_async_trait_fn(
+        &self,
+        ctxt: AssocCtxt,
+        sig: &FnSig,
+        generics: &Generics,
+        body: Option<&Block>,
+    ) {
+        struct ImplTraitVisitor {
+            spans: Vec<Span>,
+        }
+
+        impl<'a> Visitor<'a> for ImplTraitVisitor {
+            fn visit_ty(&mut self, ty: &'a Ty) {
+                if let TyKind::ImplTrait(..) = ty.kind {
+                    self.spans.push(ty.span);
+                }
+                visit::walk_ty(self, ty);
+            }
+        }
+
+        if !sig.header.asyncness.is_async() || !self.session.features_untracked().async_fn_in_trait
+        {
+            return;
+        }
+
+        if let (AssocCtxt::Trait, Some(body)) = (ctxt, body) {
+            self.err_handler()
+                .struct_span_err(body.span, "`async fn` in traits cannot have a default body yet")
+                .note("provide the body in each impl of the trait instead")
+                .emit();
+        }
+
+        let mut visitor = ImplTraitVisitor { spans: vec![] };
+        for param in &generics.params {
+            match param.kind {
+                GenericParamKind::Lifetime => {}
+                GenericParamKind::Type { .. } | GenericParamKind::Const { .. } => {
+                    visitor.spans.push(param.ident.span)
+                }
+            }
+        }
+        for param in &sig.decl.inputs {
+            visitor.visit_ty(&param.ty);
+        }
+        if !visitor.spans.is_empty() {
+            self.err_handler()
+                .struct_span_err(
+                    visitor.spans,
+                    "`async fn` in traits cannot have type or const parameters yet",
+                )
+                .note("this includes `impl Trait` in argument position")
+                .emit();
         }
     }
 
@@ -287,7 +352,7 @@ impl<'a> AstValidator<'a> {
     // ```
     fn check_expr_within_pat(&self, expr: &Expr, allow_paths: bool) {
         match expr.kind {
-            ExprKind::Lit(..) | ExprKind::Err => {}
+            ExprKind::Lit(..) | ExprKind::ConstBlock(..) | ExprKind::Err => {}
             ExprKind::Path(..) if allow_paths => {}
             ExprKind::Unary(UnOp::Neg, ref inner) if matches!(inner.kind, ExprKind::Lit(_)) => {}
             _ => self.err_handler().span_err(
@@ -1329,9 +1394,10 @@ impl<'a> Visitor<'a> for AstValidator<'a> {
 
         if ctxt == AssocCtxt::Trait || self.in_trait_impl {
             self.invalid_visibility(&item.vis, None);
-            if let AssocItemKind::Fn(_, sig, _, _) = &item.kind {
+            if let AssocItemKind::Fn(_, sig, generics, body) = &item.kind {
                 self.check_trait_fn_not_const(sig.header.constness);
                 self.check_trait_fn_not_async(item.span, sig.header.asyncness);
+                self.check_async_trait_fn(ctxt, sig, generics, body.as_deref());
             }
         }
 
@@ -141,6 +141,14 @@ impl<'a> PostExpansionVisitor<'a> {
                     "efiapi ABI is experimental and subject to change"
                 );
             }
+            "C-unwind" | "system-unwind" | "stdcall-unwind" | "thiscall-unwind" => {
+                gate_feature_post!(
+                    &self,
+                    c_unwind,
+                    span,
+                    "unwinding ABIs are experimental and subject to change"
+                );
+            }
             abi => self
                 .sess
                 .parse_sess
@@ -625,6 +633,10 @@ pub fn check_crate(krate: &ast::Crate, sess: &Session) {
     gate_all!(const_trait_bound_opt_out, "`?const` on trait bounds is experimental");
     gate_all!(const_trait_impl, "const trait impls are experimental");
     gate_all!(half_open_range_patterns, "half-open range patterns are unstable");
+    gate_all!(let_else, "`let...else` statements are unstable");
+    gate_all!(if_let_guard, "`if let` guards are experimental");
+    gate_all!(destructuring_assignment, "destructuring assignments are unstable");
+    gate_all!(inline_const, "inline-const is experimental");
 
     // All uses of `gate_all!` below this point were added in #65742,
     // and subsequently disabled (with the non-early gating readded).
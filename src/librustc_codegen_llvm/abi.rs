@@ -1,3 +1,4 @@
+use crate::attributes;
 use crate::builder::Builder;
 use crate::context::CodegenCx;
 use crate::llvm::{self, AttributePlace};
@@ -397,9 +398,7 @@ impl<'tcx> FnAbiLlvmExt<'tcx> for FnAbi<'tcx, Ty<'tcx>> {
         }
 
         // FIXME(eddyb, wesleywiser): apply this to callsites as well?
-        if !self.can_unwind {
-            llvm::Attribute::NoUnwind.apply_llfn(llvm::AttributePlace::Function, llfn);
-        }
+        attributes::unwind(llfn, self.can_unwind);
 
         let mut i = 0;
         let mut apply = |attrs: &ArgAttributes, ty: Option<&Type>| {
@@ -13,6 +13,7 @@ use rustc_middle::ty::query::Providers;
 use rustc_middle::ty::{self, TyCtxt};
 use rustc_session::config::{OptLevel, SanitizerSet};
 use rustc_session::Session;
+use rustc_target::spec::StackProtector;
 
 use crate::attributes;
 use crate::llvm::AttributePlace::Function;
@@ -56,6 +57,12 @@ pub fn sanitize(cx: &CodegenCx<'ll, '_>, no_sanitize: SanitizerSet, llfn: &'ll V
     if enabled.contains(SanitizerSet::THREAD) {
         llvm::Attribute::SanitizeThread.apply_llfn(Function, llfn);
     }
+    if enabled.contains(SanitizerSet::HWADDRESS) {
+        llvm::Attribute::SanitizeHWAddress.apply_llfn(Function, llfn);
+    }
+    if enabled.contains(SanitizerSet::SHADOWCALLSTACK) {
+        llvm::Attribute::ShadowCallStack.apply_llfn(Function, llfn);
+    }
 }
 
 /// Tell LLVM to emit or not emit the information necessary to unwind the stack for the function.
@@ -64,6 +71,15 @@ pub fn emit_uwtable(val: &'ll Value, emit: bool) {
     Attribute::UWTable.toggle_llfn(Function, val, emit);
 }
 
+/// Tell LLVM whether the function can or cannot unwind.
+///
+/// Only functions with a Rust ABI or one of the `-unwind` ABIs (like `"C-unwind"`) may unwind;
+/// every other ABI gets `nounwind`.
+#[inline]
+pub fn unwind(val: &'ll Value, can_unwind: bool) {
+    Attribute::NoUnwind.toggle_llfn(Function, val, !can_unwind);
+}
+
 /// Tell LLVM if this function should be 'naked', i.e., skip the epilogue and prologue.
 #[inline]
 fn naked(val: &'ll Value, is_naked: bool) {
@@ -111,14 +127,14 @@ fn set_probestack(cx: &CodegenCx<'ll, '_>, llfn: &'ll Value) {
     }
 
     // Currently stack probes seem somewhat incompatible with the address
-    // sanitizer and thread sanitizer. With asan we're already protected from
+    // sanitizers and thread sanitizer. With asan we're already protected from
     // stack overflow anyway so we don't really need stack probes regardless.
     if cx
         .sess()
         .opts
         .debugging_opts
         .sanitizer
-        .intersects(SanitizerSet::ADDRESS | SanitizerSet::THREAD)
+        .intersects(SanitizerSet::ADDRESS | SanitizerSet::HWADDRESS | SanitizerSet::THREAD)
     {
         return;
     }
@@ -165,6 +181,16 @@ fn translate_obsolete_target_features(feature: &str) -> &str {
     feature
 }
 
+fn set_stackprotector(cx: &CodegenCx<'ll, '_>, llfn: &'ll Value) {
+    let sspattr = match cx.sess().stack_protector() {
+        StackProtector::None => return,
+        StackProtector::Basic => Attribute::StackProtect,
+        StackProtector::Strong => Attribute::StackProtectStrong,
+        StackProtector::All => Attribute::StackProtectReq,
+    };
+    sspattr.apply_llfn(Function, llfn);
+}
+
 pub fn llvm_target_features(sess: &Session) -> impl Iterator<Item = &str> {
     const RUSTC_SPECIFIC_FEATURES: &[&str] = &["crt-static"];
 
@@ -174,12 +200,31 @@ pub fn llvm_target_features(sess: &Session) -> impl Iterator<Item = &str> {
         .target_feature
         .split(',')
         .filter(|f| !RUSTC_SPECIFIC_FEATURES.iter().any(|s| f.contains(s)));
+    // HWAddressSanitizer tags the addresses of globals, so code referring to them must not
+    // assume that their top byte is zero.
+    let sanitizer = if sess.opts.debugging_opts.sanitizer.contains(SanitizerSet::HWADDRESS)
+        && llvm_util::get_major_version() >= 11
+    {
+        Some("+tagged-globals")
+    } else {
+        None
+    };
+    // ShadowCallStack keeps the shadow stack pointer in x18, so no code may use it for anything
+    // else. Only some AArch64 targets (e.g. Android) reserve it by default.
+    let shadow_call_stack =
+        if sess.opts.debugging_opts.sanitizer.contains(SanitizerSet::SHADOWCALLSTACK) {
+            Some("+reserve-x18")
+        } else {
+            None
+        };
     sess.target
         .target
         .options
         .features
         .split(',')
         .chain(cmdline)
+        .chain(sanitizer)
+        .chain(shadow_call_stack)
         .filter(|l| !l.is_empty())
         .map(translate_obsolete_target_features)
 }
@@ -275,6 +320,7 @@ pub fn from_fn_attrs(cx: &CodegenCx<'ll, 'tcx>, llfn: &'ll Value, instance: ty::
     set_frame_pointer_elimination(cx, llfn);
     set_instrument_function(cx, llfn);
     set_probestack(cx, llfn);
+    set_stackprotector(cx, llfn);
 
     if codegen_fn_attrs.flags.contains(CodegenFnAttrFlags::COLD) {
         Attribute::Cold.apply_llfn(Function, llfn);
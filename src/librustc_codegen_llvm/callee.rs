@@ -14,6 +14,7 @@ use rustc_codegen_ssa::traits::*;
 
 use rustc_middle::ty::layout::{FnAbiExt, HasTyCtxt};
 use rustc_middle::ty::{self, Instance, TypeFoldable};
+use rustc_symbol_mangling::typeid::typeid_for_instance;
 
 /// Codegens a reference to a fn/method item, monomorphizing and
 /// inlining as it goes.
@@ -86,6 +87,12 @@ pub fn get_fn(cx: &CodegenCx<'ll, 'tcx>, instance: Instance<'tcx>) -> &'ll Value
 
         let instance_def_id = instance.def_id();
 
+        // Functions from `extern` blocks are defined by foreign code, so the type identifier
+        // their address is checked against has to be attached to the declaration.
+        if tcx.sess.is_sanitizer_cfi_enabled() && tcx.is_foreign_item(instance_def_id) {
+            cx.add_type_metadata(llfn, typeid_for_instance(tcx, instance, &fn_abi));
+        }
+
         // Apply an appropriate linkage/visibility value to our item that we
         // just declared.
         //
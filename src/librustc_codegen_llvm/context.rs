@@ -188,6 +188,20 @@ pub unsafe fn create_module(
         llvm::LLVMRustAddModuleFlag(llmod, avoid_plt, 1);
     }
 
+    if sess.is_sanitizer_cfi_enabled() {
+        // Make the jump table entries the canonical addresses of the functions, so that function
+        // pointers compare equal no matter whether they were taken in instrumented code or not.
+        let canonical_jump_tables = "CFI Canonical Jump Tables\0".as_ptr().cast();
+        llvm::LLVMRustAddModuleFlag(llmod, canonical_jump_tables, 1);
+
+        // The type tests are lowered by the linker, so the type metadata has to be kept in the
+        // regular LTO part of a split ThinLTO module.
+        if sess.opts.cg.linker_plugin_lto.enabled() {
+            let enable_split_lto_unit = "EnableSplitLTOUnit\0".as_ptr().cast();
+            llvm::LLVMRustAddModuleFlag(llmod, enable_split_lto_unit, 1);
+        }
+    }
+
     // Control Flow Guard is currently only supported by the MSVC linker on Windows.
     if sess.target.target.options.is_like_msvc {
         match sess.opts.cg.control_flow_guard {
@@ -802,6 +816,7 @@ impl CodegenCx<'b, 'tcx> {
         ifn!("llvm.x86.seh.recoverfp", fn(i8p, i8p) -> i8p);
 
         ifn!("llvm.assume", fn(i1) -> void);
+        ifn!("llvm.type.test", fn(i8p, self.type_metadata()) -> i1);
         ifn!("llvm.prefetch", fn(i8p, t_i32, t_i32, t_i32) -> void);
 
         // variadic intrinsics
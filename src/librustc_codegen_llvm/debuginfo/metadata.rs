@@ -999,7 +999,11 @@ pub fn compile_unit_metadata(
     let name_in_debuginfo = name_in_debuginfo.to_string_lossy();
     let work_dir = tcx.sess.working_dir.0.to_string_lossy();
     let flags = "\0";
-    let split_name = "";
+    let split_name = tcx
+        .output_filenames(LOCAL_CRATE)
+        .split_dwarf_path(tcx.sess.split_debuginfo(), Some(codegen_unit_name))
+        .map(|path| path.to_string_lossy().into_owned())
+        .unwrap_or_default();
 
     // FIXME(#60020):
     //
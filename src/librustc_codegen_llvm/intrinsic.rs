@@ -888,6 +888,16 @@ impl IntrinsicCallMethods<'tcx> for Builder<'a, 'll, 'tcx> {
         }
     }
 
+    fn type_test(&mut self, pointer: Self::Value, typeid: Self::Value) -> Self::Value {
+        // The `LowerTypeTests` pass replaces calls to this intrinsic with code that checks whether
+        // the pointer is associated with the type identifier, which is only possible once the
+        // whole program is visible, i.e. during LTO.
+        let i8p_ty = self.type_i8p();
+        let bitcast = self.bitcast(pointer, i8p_ty);
+        let intrinsic = self.get_intrinsic("llvm.type.test");
+        self.call(intrinsic, &[bitcast, typeid], None)
+    }
+
     fn va_start(&mut self, va_list: &'ll Value) -> &'ll Value {
         let intrinsic = self.cx().get_intrinsic("llvm.va_start");
         self.call(intrinsic, &[va_list], None)
@@ -4,7 +4,7 @@ use libc::c_int;
 use rustc_data_structures::fx::FxHashSet;
 use rustc_feature::UnstableFeatures;
 use rustc_middle::bug;
-use rustc_session::config::PrintRequest;
+use rustc_session::config::{PrintRequest, SplitDebuginfo};
 use rustc_session::Session;
 use rustc_span::symbol::sym;
 use rustc_span::symbol::Symbol;
@@ -36,6 +36,15 @@ pub(crate) fn init(sess: &Session) {
             bug!("couldn't enable multi-threaded LLVM");
         }
     }
+
+    // The split DWARF output file is only passed on to the target machine with LLVM 11.
+    if sess.opts.cg.split_debuginfo != SplitDebuginfo::Off && get_version() < (11, 0, 0) {
+        let (major, minor, patch) = get_version();
+        sess.err(&format!(
+            "`-C split-debuginfo` requires LLVM 11 or newer, but rustc uses LLVM {}.{}.{}",
+            major, minor, patch
+        ));
+    }
 }
 
 fn require_inited() {
@@ -331,6 +340,13 @@ pub fn get_major_version() -> u32 {
     unsafe { llvm::LLVMRustVersionMajor() }
 }
 
+pub fn get_version() -> (u32, u32, u32) {
+    // Can be called without initializing LLVM
+    unsafe {
+        (llvm::LLVMRustVersionMajor(), llvm::LLVMRustVersionMinor(), llvm::LLVMRustVersionPatch())
+    }
+}
+
 pub fn print_passes() {
     // Can be called without initializing LLVM
     unsafe {
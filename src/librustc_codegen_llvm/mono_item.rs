@@ -11,6 +11,7 @@ pub use rustc_middle::mir::mono::MonoItem;
 use rustc_middle::mir::mono::{Linkage, Visibility};
 use rustc_middle::ty::layout::FnAbiExt;
 use rustc_middle::ty::{self, Instance, TypeFoldable};
+use rustc_symbol_mangling::typeid::typeid_for_instance;
 use rustc_target::abi::LayoutOf;
 
 impl PreDefineMethods<'tcx> for CodegenCx<'ll, 'tcx> {
@@ -79,6 +80,10 @@ impl PreDefineMethods<'tcx> for CodegenCx<'ll, 'tcx> {
 
         attributes::from_fn_attrs(self, lldecl, instance);
 
+        if self.tcx.sess.is_sanitizer_cfi_enabled() {
+            self.add_type_metadata(lldecl, typeid_for_instance(self.tcx, instance, &fn_abi));
+        }
+
         self.instances.borrow_mut().insert(instance, lldecl);
     }
 }
@@ -3,7 +3,7 @@ use rustc_fs_util::fix_windows_verbatim_for_gcc;
 use rustc_hir::def_id::CrateNum;
 use rustc_middle::middle::cstore::{EncodedMetadata, LibSource, NativeLib};
 use rustc_middle::middle::dependency_format::Linkage;
-use rustc_session::config::{self, CFGuard, CrateType, DebugInfo};
+use rustc_session::config::{self, CFGuard, CrateType, DebugInfo, SplitDebuginfo};
 use rustc_session::config::{OutputFilenames, OutputType, PrintRequest, SanitizerSet};
 use rustc_session::output::{check_file_is_writeable, invalid_output_for_target, out_filename};
 use rustc_session::search_paths::PathKind;
@@ -123,6 +123,11 @@ pub fn link_binary<'a, B: ArchiveBuilder<'a>>(
                     remove(sess, obj);
                 }
             }
+            if sess.opts.output_types.should_codegen() && !preserve_dwarf_objects(sess) {
+                for obj in codegen_results.modules.iter().filter_map(|m| m.dwarf_object.as_ref()) {
+                    remove(sess, obj);
+                }
+            }
             if let Some(ref metadata_module) = codegen_results.metadata_module {
                 if let Some(ref obj) = metadata_module.object {
                     remove(sess, obj);
@@ -775,6 +780,32 @@ fn link_natively<'a, B: ArchiveBuilder<'a>>(
             sess.fatal(&format!("failed to run dsymutil: {}", e))
         }
     }
+
+    // With packed split DWARF, the `.dwo` files referenced from the linked output are combined
+    // into a single `.dwp` package next to it, which debuggers pick up automatically.
+    if sess.split_debuginfo() == SplitDebuginfo::Packed {
+        link_dwarf_object(sess, out_filename);
+    }
+}
+
+fn link_dwarf_object(sess: &Session, out_filename: &Path) {
+    let mut dwp_out_filename = out_filename.as_os_str().to_owned();
+    dwp_out_filename.push(".dwp");
+
+    let mut cmd = Command::new("dwp");
+    cmd.arg("-e").arg(out_filename).arg("-o").arg(&dwp_out_filename);
+    info!("{:?}", &cmd);
+    match sess.time("run_dwp", || cmd.output()) {
+        Ok(prog) if !prog.status.success() => {
+            sess.struct_err(&format!("packaging split DWARF with `dwp` failed: {}", prog.status))
+                .note(&format!("{:?}", &cmd))
+                .note(&String::from_utf8_lossy(&prog.stderr))
+                .emit();
+            sess.abort_if_errors();
+        }
+        Ok(_) => {}
+        Err(e) => sess.fatal(&format!("failed to run dwp: {}", e)),
+    }
 }
 
 fn link_sanitizers(sess: &Session, crate_type: CrateType, linker: &mut dyn Linker) {
@@ -794,6 +825,9 @@ fn link_sanitizers(sess: &Session, crate_type: CrateType, linker: &mut dyn Linke
     if sanitizer.contains(SanitizerSet::THREAD) {
         link_sanitizer_runtime(sess, linker, "tsan");
     }
+    if sanitizer.contains(SanitizerSet::HWADDRESS) {
+        link_sanitizer_runtime(sess, linker, "hwasan");
+    }
 }
 
 fn link_sanitizer_runtime(sess: &Session, linker: &mut dyn Linker, name: &str) {
@@ -964,6 +998,20 @@ fn preserve_objects_for_their_debuginfo(sess: &Session) -> bool {
     false
 }
 
+/// Returns whether the `.dwo` files written with `-C split-debuginfo` have to outlive the
+/// compilation session.
+///
+/// Unpacked split DWARF is only ever read from the `.dwo` files, and rlibs and staticlibs
+/// reference them until they are linked into a final artifact. Only once `dwp` has copied
+/// them into the `.dwp` package of a linked output are they no longer needed.
+fn preserve_dwarf_objects(sess: &Session) -> bool {
+    if sess.split_debuginfo() != SplitDebuginfo::Packed {
+        return true;
+    }
+
+    sess.crate_types().iter().any(|&x| x == CrateType::Rlib || x == CrateType::Staticlib)
+}
+
 pub fn archive_search_paths(sess: &Session) -> Vec<PathBuf> {
     sess.target_filesearch(PathKind::Native).search_path_dirs()
 }
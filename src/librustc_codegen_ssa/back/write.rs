@@ -9,6 +9,7 @@ use crate::{
 
 use crate::traits::*;
 use jobserver::{Acquired, Client};
+use rustc_data_structures::fingerprint::Fingerprint;
 use rustc_data_structures::fx::FxHashMap;
 use rustc_data_structures::profiling::SelfProfilerRef;
 use rustc_data_structures::profiling::TimingGuard;
@@ -21,6 +22,7 @@ use rustc_fs_util::link_or_copy;
 use rustc_hir::def_id::{CrateNum, LOCAL_CRATE};
 use rustc_incremental::{
     copy_cgu_workproduct_to_incr_comp_cache_dir, in_incr_comp_dir, in_incr_comp_dir_sess,
+    publish_shared_work_product,
 };
 use rustc_middle::dep_graph::{WorkProduct, WorkProductId};
 use rustc_middle::middle::cstore::EncodedMetadata;
@@ -28,7 +30,7 @@ use rustc_middle::middle::exported_symbols::SymbolExportLevel;
 use rustc_middle::ty::TyCtxt;
 use rustc_session::cgu_reuse_tracker::CguReuseTracker;
 use rustc_session::config::{self, CrateType, Lto, OutputFilenames, OutputType};
-use rustc_session::config::{Passes, SanitizerSet, SwitchWithOptPath};
+use rustc_session::config::{Passes, SanitizerSet, SplitDebuginfo, SwitchWithOptPath};
 use rustc_session::Session;
 use rustc_span::source_map::SourceMap;
 use rustc_span::symbol::{sym, Symbol};
@@ -275,10 +277,33 @@ impl ModuleConfig {
     }
 }
 
+/// Per-module settings that a backend needs when it creates a target machine.
+pub struct TargetMachineFactoryConfig {
+    /// LLVM only emits split DWARF if the target machine knows the path of the `.dwo` file, so
+    /// that path is needed when the target machine is created. Backends that don't support split
+    /// DWARF can ignore it.
+    pub split_dwarf_file: Option<PathBuf>,
+}
+
+impl TargetMachineFactoryConfig {
+    pub fn new<B: WriteBackendMethods>(
+        cgcx: &CodegenContext<B>,
+        module_name: &str,
+    ) -> TargetMachineFactoryConfig {
+        let split_dwarf_file =
+            cgcx.output_filenames.split_dwarf_path(cgcx.split_debuginfo, Some(module_name));
+        TargetMachineFactoryConfig { split_dwarf_file }
+    }
+}
+
+pub type TargetMachineFactoryFn<B> = Arc<
+    dyn Fn(TargetMachineFactoryConfig) -> Result<<B as WriteBackendMethods>::TargetMachine, String>
+        + Send
+        + Sync,
+>;
+
 // HACK(eddyb) work around `#[derive]` producing wrong bounds for `Clone`.
-pub struct TargetMachineFactory<B: WriteBackendMethods>(
-    pub Arc<dyn Fn() -> Result<B::TargetMachine, String> + Send + Sync>,
-);
+pub struct TargetMachineFactory<B: WriteBackendMethods>(pub TargetMachineFactoryFn<B>);
 
 impl<B: WriteBackendMethods> Clone for TargetMachineFactory<B> {
     fn clone(&self) -> Self {
@@ -311,6 +336,7 @@ pub struct CodegenContext<B: WriteBackendMethods> {
     pub target_pointer_width: String,
     pub target_arch: String,
     pub debuginfo: config::DebugInfo,
+    pub split_debuginfo: SplitDebuginfo,
 
     // Number of cgus excluding the allocator/metadata modules
     pub total_cgus: usize,
@@ -474,12 +500,14 @@ pub fn start_async_codegen<B: ExtraBackendMethods>(
         shared_emitter_main,
         future: coordinator_thread,
         output_filenames: tcx.output_filenames(LOCAL_CRATE),
+        shared_work_product_keys: FxHashMap::default(),
     }
 }
 
 fn copy_all_cgu_workproducts_to_incr_comp_cache_dir(
     sess: &Session,
     compiled_modules: &CompiledModules,
+    shared_work_product_keys: &FxHashMap<WorkProductId, Fingerprint>,
 ) -> FxHashMap<WorkProductId, WorkProduct> {
     let mut work_products = FxHashMap::default();
 
@@ -491,10 +519,14 @@ fn copy_all_cgu_workproducts_to_incr_comp_cache_dir(
 
     for module in compiled_modules.modules.iter().filter(|m| m.kind == ModuleKind::Regular) {
         let path = module.object.as_ref().cloned();
+        let dwarf_path = module.dwarf_object.as_ref().cloned();
 
         if let Some((id, product)) =
-            copy_cgu_workproduct_to_incr_comp_cache_dir(sess, &module.name, &path)
+            copy_cgu_workproduct_to_incr_comp_cache_dir(sess, &module.name, &path, &dwarf_path)
         {
+            if let Some(&key) = shared_work_product_keys.get(&id) {
+                publish_shared_work_product(sess, key, &product);
+            }
             work_products.insert(id, product);
         }
     }
@@ -834,27 +866,37 @@ fn execute_copy_from_cache_work_item<B: ExtraBackendMethods>(
     module_config: &ModuleConfig,
 ) -> Result<WorkItemResult<B>, FatalError> {
     let incr_comp_session_dir = cgcx.incr_comp_session_dir.as_ref().unwrap();
-    let mut object = None;
-    if let Some(saved_file) = module.source.saved_file {
-        let obj_out = cgcx.output_filenames.temp_path(OutputType::Object, Some(&module.name));
-        object = Some(obj_out.clone());
-        let source_file = in_incr_comp_dir(&incr_comp_session_dir, &saved_file);
+    let load_from_incr_comp_dir = |output_path: PathBuf, saved_file: &str| {
+        let source_file = in_incr_comp_dir(&incr_comp_session_dir, saved_file);
         debug!(
             "copying pre-existing module `{}` from {:?} to {}",
             module.name,
             source_file,
-            obj_out.display()
+            output_path.display()
         );
-        if let Err(err) = link_or_copy(&source_file, &obj_out) {
+        if let Err(err) = link_or_copy(&source_file, &output_path) {
             let diag_handler = cgcx.create_diag_handler();
             diag_handler.err(&format!(
                 "unable to copy {} to {}: {}",
                 source_file.display(),
-                obj_out.display(),
+                output_path.display(),
                 err
             ));
         }
-    }
+        output_path
+    };
+
+    let object = module.source.saved_file.as_ref().map(|saved_file| {
+        let obj_out = cgcx.output_filenames.temp_path(OutputType::Object, Some(&module.name));
+        load_from_incr_comp_dir(obj_out, saved_file)
+    });
+    let dwarf_object = module.source.saved_dwarf_file.as_ref().map(|saved_dwarf_file| {
+        let dwarf_obj_out = cgcx
+            .output_filenames
+            .split_dwarf_path(cgcx.split_debuginfo, Some(&module.name))
+            .expect("saved dwarf file in work product but `split_dwarf_path` returned `None`");
+        load_from_incr_comp_dir(dwarf_obj_out, saved_dwarf_file)
+    });
 
     assert_eq!(object.is_some(), module_config.emit_obj != EmitObj::None);
 
@@ -862,6 +904,7 @@ fn execute_copy_from_cache_work_item<B: ExtraBackendMethods>(
         name: module.name,
         kind: ModuleKind::Regular,
         object,
+        dwarf_object,
         bytecode: None,
     }))
 }
@@ -1022,6 +1065,7 @@ fn start_executing_work<B: ExtraBackendMethods>(
         target_pointer_width: tcx.sess.target.target.target_pointer_width.clone(),
         target_arch: tcx.sess.target.target.arch.clone(),
         debuginfo: tcx.sess.opts.debuginfo,
+        split_debuginfo: tcx.sess.split_debuginfo(),
     };
 
     // This is the "main loop" of parallel work happening for parallel codegen.
@@ -1695,6 +1739,9 @@ pub struct OngoingCodegen<B: ExtraBackendMethods> {
     pub shared_emitter_main: SharedEmitterMain,
     pub future: thread::JoinHandle<Result<CompiledModules, ()>>,
     pub output_filenames: Arc<OutputFilenames>,
+    /// The keys under which the object files of codegen units are published
+    /// to the shared incremental cache, see `rustc_incremental::shared_work_product_key`.
+    pub shared_work_product_keys: FxHashMap<WorkProductId, Fingerprint>,
 }
 
 impl<B: ExtraBackendMethods> OngoingCodegen<B> {
@@ -1718,8 +1765,11 @@ impl<B: ExtraBackendMethods> OngoingCodegen<B> {
 
         sess.abort_if_errors();
 
-        let work_products =
-            copy_all_cgu_workproducts_to_incr_comp_cache_dir(sess, &compiled_modules);
+        let work_products = copy_all_cgu_workproducts_to_incr_comp_cache_dir(
+            sess,
+            &compiled_modules,
+            &self.shared_work_product_keys,
+        );
         produce_final_output_artifacts(sess, &compiled_modules, &self.output_filenames);
 
         // FIXME: time_llvm_passes support - does this use a global context or
@@ -26,13 +26,16 @@ use crate::traits::*;
 use crate::{CachedModuleCodegen, CrateInfo, MemFlags, ModuleCodegen, ModuleKind};
 
 use rustc_attr as attr;
+use rustc_data_structures::fingerprint::Fingerprint;
 use rustc_data_structures::fx::FxHashMap;
 use rustc_data_structures::profiling::print_time_passes_entry;
 use rustc_data_structures::sync::{par_iter, Lock, ParallelIterator};
 use rustc_hir as hir;
 use rustc_hir::def_id::{LocalDefId, LOCAL_CRATE};
 use rustc_hir::lang_items::StartFnLangItem;
+use rustc_incremental::{fetch_shared_work_product, shared_work_product_key};
 use rustc_index::vec::Idx;
+use rustc_middle::dep_graph::DepNode;
 use rustc_middle::middle::codegen_fn_attrs::CodegenFnAttrs;
 use rustc_middle::middle::cstore::EncodedMetadata;
 use rustc_middle::middle::cstore::{self, LinkagePreference};
@@ -545,7 +548,7 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
     }
 
     let ongoing_codegen = start_async_codegen(backend.clone(), tcx, metadata, codegen_units.len());
-    let ongoing_codegen = AbortCodegenOnDrop::<B>(Some(ongoing_codegen));
+    let mut ongoing_codegen = AbortCodegenOnDrop::<B>(Some(ongoing_codegen));
 
     // Codegen an allocator shim, if necessary.
     //
@@ -677,13 +680,31 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
                         *time += start_time.elapsed();
                         module
                     };
-                submit_codegened_module_to_llvm(
-                    &backend,
-                    &ongoing_codegen.coordinator_send,
-                    module,
-                    cost,
-                );
-                false
+                let key = record_shared_work_product_key(tcx, &mut ongoing_codegen, cgu);
+                let shared_work_product = key.and_then(|key| {
+                    fetch_shared_work_product(tcx.sess, &cgu.name().as_str(), key)
+                });
+                if let Some(work_product) = shared_work_product {
+                    // Another session already produced the object files for a
+                    // module with the same inputs, so LLVM doesn't have to.
+                    submit_post_lto_module_to_llvm(
+                        &backend,
+                        &ongoing_codegen.coordinator_send,
+                        CachedModuleCodegen {
+                            name: cgu.name().to_string(),
+                            source: work_product,
+                        },
+                    );
+                    true
+                } else {
+                    submit_codegened_module_to_llvm(
+                        &backend,
+                        &ongoing_codegen.coordinator_send,
+                        module,
+                        cost,
+                    );
+                    false
+                }
             }
             CguReuse::PreLto => {
                 submit_pre_lto_module_to_llvm(
@@ -698,6 +719,7 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
                 true
             }
             CguReuse::PostLto => {
+                record_shared_work_product_key(tcx, &mut ongoing_codegen, cgu);
                 submit_post_lto_module_to_llvm(
                     &backend,
                     &ongoing_codegen.coordinator_send,
@@ -915,6 +937,29 @@ pub fn provide_both(providers: &mut Providers) {
         |tcx, def_id| tcx.dllimport_foreign_items(def_id.krate).contains(&def_id);
 }
 
+/// Records the key under which the object files of `cgu` are published to the
+/// shared incremental cache and returns it, if they can be shared at all.
+fn record_shared_work_product_key<B: ExtraBackendMethods>(
+    tcx: TyCtxt<'_>,
+    ongoing_codegen: &mut OngoingCodegen<B>,
+    cgu: &CodegenUnit<'_>,
+) -> Option<Fingerprint> {
+    // After LTO, an object file also depends on the other codegen units.
+    match compute_per_cgu_lto_type(
+        &tcx.sess.lto(),
+        &tcx.sess.opts,
+        &tcx.sess.crate_types(),
+        ModuleKind::Regular,
+    ) {
+        ComputedLtoType::No => {}
+        ComputedLtoType::Thin | ComputedLtoType::Fat => return None,
+    }
+
+    let key = shared_work_product_key(tcx, cgu)?;
+    ongoing_codegen.shared_work_product_keys.insert(cgu.work_product_id(), key);
+    Some(key)
+}
+
 fn determine_cgu_reuse<'tcx>(tcx: TyCtxt<'tcx>, cgu: &CodegenUnit<'tcx>) -> CguReuse {
     if !tcx.dep_graph.is_fully_enabled() {
         return CguReuse::No;
@@ -924,6 +969,9 @@ fn determine_cgu_reuse<'tcx>(tcx: TyCtxt<'tcx>, cgu: &CodegenUnit<'tcx>) -> CguR
     if tcx.dep_graph.previous_work_product(work_product_id).is_none() {
         // We don't have anything cached for this CGU. This can happen
         // if the CGU did not exist in the previous session.
+        if tcx.sess.opts.debugging_opts.incremental_explain {
+            eprintln!("[incremental] CGU `{}` is new in this session", cgu.name());
+        }
         return CguReuse::No;
     }
 
@@ -954,6 +1002,26 @@ fn determine_cgu_reuse<'tcx>(tcx: TyCtxt<'tcx>, cgu: &CodegenUnit<'tcx>) -> CguR
             _ => CguReuse::PreLto,
         }
     } else {
+        if tcx.sess.opts.debugging_opts.incremental_explain {
+            explain_cgu_recompilation(tcx, cgu, &dep_node);
+        }
         CguReuse::No
     }
 }
+
+/// Prints the chain of red dep nodes that kept `cgu` from being reused, e.g.
+/// "`hir_owner(foo::bar)` changed → `typeck(foo::baz)` → CGU `foo.3`".
+fn explain_cgu_recompilation(tcx: TyCtxt<'_>, cgu: &CodegenUnit<'_>, dep_node: &DepNode) {
+    let mut chain: Vec<String> =
+        tcx.dep_graph.red_node_chain(dep_node).iter().map(|node| format!("`{:?}`", node)).collect();
+
+    // The last node is the CGU's own dep node, which is more useful to print by name.
+    chain.pop();
+    match chain.first_mut() {
+        Some(input) => input.push_str(" changed"),
+        None => chain.push("no changed input recorded".to_string()),
+    }
+    chain.push(format!("CGU `{}`", cgu.name()));
+
+    eprintln!("[incremental] {}", chain.join(" → "));
+}
@@ -1,6 +1,7 @@
 use crate::traits::*;
 
 use rustc_middle::ty::{self, Instance, Ty};
+use rustc_symbol_mangling::typeid::typeid_for_trait_ref;
 use rustc_target::abi::call::FnAbi;
 
 #[derive(Copy, Clone, Debug)]
@@ -121,6 +122,10 @@ pub fn get_vtable<'tcx, Cx: CodegenMethods<'tcx>>(
 
     cx.create_vtable_metadata(ty, vtable);
 
+    if tcx.sess.is_sanitizer_cfi_enabled() {
+        cx.add_type_metadata(vtable, typeid_for_trait_ref(tcx, trait_ref));
+    }
+
     cx.vtables().borrow_mut().insert((ty, trait_ref), vtable);
     vtable
 }
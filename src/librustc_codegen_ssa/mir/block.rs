@@ -17,8 +17,10 @@ use rustc_middle::mir::interpret::{AllocId, ConstValue, Pointer, Scalar};
 use rustc_middle::mir::AssertKind;
 use rustc_middle::ty::layout::{FnAbiExt, HasTyCtxt};
 use rustc_middle::ty::{self, Instance, Ty, TypeFoldable};
+use rustc_session::config::SanitizerSet;
 use rustc_span::source_map::Span;
 use rustc_span::{sym, Symbol};
+use rustc_symbol_mangling::typeid::{typeid_for_fn, typeid_for_trait_ref};
 use rustc_target::abi::call::{ArgAbi, FnAbi, PassMode};
 use rustc_target::abi::{self, LayoutOf};
 use rustc_target::spec::abi::Abi;
@@ -334,7 +336,7 @@ impl<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>> FunctionCx<'a, 'tcx, Bx> {
         let (drop_fn, fn_abi) = match ty.kind {
             // FIXME(eddyb) perhaps move some of this logic into
             // `Instance::resolve_drop_in_place`?
-            ty::Dynamic(..) => {
+            ty::Dynamic(data, _) => {
                 let virtual_drop = Instance {
                     def: ty::InstanceDef::Virtual(drop_fn.def_id(), 0),
                     substs: drop_fn.substs,
@@ -342,6 +344,10 @@ impl<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>> FunctionCx<'a, 'tcx, Bx> {
                 let fn_abi = FnAbi::of_instance(&bx, virtual_drop, &[]);
                 let vtable = args[1];
                 args = &args[..1];
+                if self.cfi_checks_enabled() {
+                    let typeid = typeid_for_trait_ref(bx.tcx(), data.principal());
+                    bx = self.codegen_cfi_check(bx, helper.terminator.source_info, vtable, typeid);
+                }
                 (meth::DESTRUCTOR.get_fn(&mut bx, vtable, &fn_abi), fn_abi)
             }
             _ => (bx.get_fn_addr(drop_fn), FnAbi::of_instance(&bx, drop_fn, &[])),
@@ -760,6 +766,12 @@ impl<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>> FunctionCx<'a, 'tcx, Bx> {
                     // the data pointer as the first argument
                     match op.val {
                         Pair(data_ptr, meta) => {
+                            if self.cfi_checks_enabled() {
+                                let principal = self.virtual_call_principal(op.layout.ty);
+                                let typeid = typeid_for_trait_ref(bx.tcx(), principal);
+                                let source_info = terminator.source_info;
+                                bx = self.codegen_cfi_check(bx, source_info, meta, typeid);
+                            }
                             llfn = Some(
                                 meth::VirtualIndex::from_index(idx).get_fn(&mut bx, meta, &fn_abi),
                             );
@@ -770,6 +782,11 @@ impl<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>> FunctionCx<'a, 'tcx, Bx> {
                     }
                 } else if let Ref(data_ptr, Some(meta), _) = op.val {
                     // by-value dynamic dispatch
+                    if self.cfi_checks_enabled() {
+                        let principal = self.virtual_call_principal(op.layout.ty);
+                        let typeid = typeid_for_trait_ref(bx.tcx(), principal);
+                        bx = self.codegen_cfi_check(bx, terminator.source_info, meta, typeid);
+                    }
                     llfn = Some(meth::VirtualIndex::from_index(idx).get_fn(&mut bx, meta, &fn_abi));
                     llargs.push(data_ptr);
                     continue;
@@ -825,6 +842,14 @@ impl<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>> FunctionCx<'a, 'tcx, Bx> {
             _ => span_bug!(span, "no llfn for call"),
         };
 
+        // Functions with a C calling convention have the type identifier Clang gives the
+        // equivalent C function type, so calls into C code built with `-fsanitize=cfi-icall` are
+        // checked as well.
+        if instance.is_none() && self.cfi_checks_enabled() {
+            let typeid = typeid_for_fn(bx.tcx(), sig, &fn_abi);
+            bx = self.codegen_cfi_check(bx, terminator.source_info, fn_ptr, typeid);
+        }
+
         if let Some((_, target)) = destination.as_ref() {
             helper.maybe_sideeffect(self.mir, &mut bx, &[*target]);
         }
@@ -1266,6 +1291,47 @@ impl<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>> FunctionCx<'a, 'tcx, Bx> {
         })
     }
 
+    /// Returns whether indirect calls in this function are checked by the control-flow integrity
+    /// sanitizer.
+    fn cfi_checks_enabled(&self) -> bool {
+        let tcx = self.cx.tcx();
+        tcx.sess.is_sanitizer_cfi_enabled()
+            && !tcx.codegen_fn_attrs(self.instance.def_id()).no_sanitize.contains(SanitizerSet::CFI)
+    }
+
+    /// Returns the principal trait of the trait object a virtual call is dispatched on, given the
+    /// type of the (unwrapped) receiver.
+    fn virtual_call_principal(&self, ty: Ty<'tcx>) -> Option<ty::PolyExistentialTraitRef<'tcx>> {
+        let tcx = self.cx.tcx();
+        let pointee = ty.builtin_deref(true).map_or(ty, |tm| tm.ty);
+        let tail = tcx.struct_tail_erasing_lifetimes(pointee, ty::ParamEnv::reveal_all());
+        match tail.kind {
+            ty::Dynamic(data, _) => data.principal(),
+            _ => bug!("virtual call on non-trait-object receiver {:?}", ty),
+        }
+    }
+
+    /// Emits a check that `pointer` is associated with the type identifier `typeid`, aborting if
+    /// it isn't, and returns a builder positioned after the check.
+    fn codegen_cfi_check(
+        &self,
+        mut bx: Bx,
+        source_info: mir::SourceInfo,
+        pointer: Bx::Value,
+        typeid: String,
+    ) -> Bx {
+        let typeid = bx.typeid_metadata(typeid);
+        let cond = bx.type_test(pointer, typeid);
+        let mut ok = self.new_block("cfi_ok");
+        let mut fail = self.new_block("cfi_fail");
+        bx.cond_br(cond, ok.llbb(), fail.llbb());
+        self.set_debug_loc(&mut fail, source_info);
+        fail.abort();
+        fail.unreachable();
+        self.set_debug_loc(&mut ok, source_info);
+        ok
+    }
+
     pub fn new_block(&self, name: &str) -> Bx {
         Bx::new_block(self.cx, self.llfn, name)
     }
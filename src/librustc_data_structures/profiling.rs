@@ -85,6 +85,7 @@ use crate::cold_path;
 use crate::fx::FxHashMap;
 
 use std::borrow::Borrow;
+use std::cell::RefCell;
 use std::collections::hash_map::Entry;
 use std::convert::Into;
 use std::error::Error;
@@ -95,7 +96,7 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use measureme::{EventId, EventIdBuilder, SerializableString, StringId};
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 
 cfg_if! {
     if #[cfg(any(windows, target_os = "wasi"))] {
@@ -180,6 +181,10 @@ pub struct SelfProfilerRef {
 
     // Print extra verbose generic activities to stdout
     print_extra_verbose_generic_activities: bool,
+
+    // This field is `None` unless per-query statistics are collected for
+    // `-Z query-stats`. This works independently of `-Z self-profile`.
+    query_stats: Option<Arc<QueryStatsRecorder>>,
 }
 
 impl SelfProfilerRef {
@@ -187,17 +192,22 @@ impl SelfProfilerRef {
         profiler: Option<Arc<SelfProfiler>>,
         print_verbose_generic_activities: bool,
         print_extra_verbose_generic_activities: bool,
+        record_query_stats: bool,
     ) -> SelfProfilerRef {
         // If there is no SelfProfiler then the filter mask is set to NONE,
         // ensuring that nothing ever tries to actually access it.
         let event_filter_mask =
             profiler.as_ref().map(|p| p.event_filter_mask).unwrap_or(EventFilter::empty());
 
+        let query_stats =
+            if record_query_stats { Some(Arc::new(QueryStatsRecorder::default())) } else { None };
+
         SelfProfilerRef {
             profiler,
             event_filter_mask,
             print_verbose_generic_activities,
             print_extra_verbose_generic_activities,
+            query_stats,
         }
     }
 
@@ -361,6 +371,44 @@ impl SelfProfilerRef {
         }));
     }
 
+    /// Record an invocation of the query `query_name` for `-Z query-stats`.
+    /// `cache_hit` is `true` if the result was already available.
+    #[inline(always)]
+    pub fn query_stats_invocation(&self, query_name: &'static str, cache_hit: bool) {
+        if unlikely!(self.query_stats.is_some()) {
+            cold_path(|| {
+                self.query_stats.as_ref().unwrap().record_invocation(query_name, cache_hit)
+            })
+        }
+    }
+
+    /// Record a call to `ensure` for the query `query_name` for `-Z query-stats`.
+    /// The invocation it results in is recorded separately.
+    #[inline(always)]
+    pub fn query_stats_ensure(&self, query_name: &'static str) {
+        if unlikely!(self.query_stats.is_some()) {
+            cold_path(|| self.query_stats.as_ref().unwrap().record_ensure(query_name))
+        }
+    }
+
+    /// Start measuring the execution of the provider of the query
+    /// `query_name` for `-Z query-stats`. Measuring continues until the
+    /// QueryStatsGuard returned from this call is dropped.
+    #[inline(always)]
+    pub fn query_stats_provider(&self, query_name: &'static str) -> QueryStatsGuard<'_> {
+        if unlikely!(self.query_stats.is_some()) {
+            QueryStatsGuard::start(self.query_stats.as_ref().unwrap(), query_name)
+        } else {
+            QueryStatsGuard::none()
+        }
+    }
+
+    /// Returns the per-query statistics recorded so far, or `None` if they
+    /// are not being recorded.
+    pub fn query_stats(&self) -> Option<FxHashMap<&'static str, QueryStatsCounters>> {
+        self.query_stats.as_ref().map(|recorder| recorder.counters.lock().clone())
+    }
+
     pub fn with_profiler(&self, f: impl FnOnce(&SelfProfiler)) {
         if let Some(profiler) = &self.profiler {
             f(&profiler)
@@ -582,6 +630,92 @@ impl Drop for VerboseTimingGuard<'_> {
     }
 }
 
+/// The statistics recorded for a single query for `-Z query-stats`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct QueryStatsCounters {
+    /// How often the query was invoked, including cache hits.
+    pub invocations: u64,
+    /// How many of the invocations were answered by the in-memory cache, or
+    /// for `ensure`, by marking the query green.
+    pub cache_hits: u64,
+    /// How many of the invocations came from `ensure` rather than from a
+    /// call that needs the result.
+    pub ensures: u64,
+    /// The time spent in the query's provider, including nested queries.
+    pub total_time: Duration,
+    /// The time spent in the query's provider, excluding nested queries.
+    pub self_time: Duration,
+}
+
+#[derive(Default)]
+struct QueryStatsRecorder {
+    counters: Mutex<FxHashMap<&'static str, QueryStatsCounters>>,
+}
+
+impl QueryStatsRecorder {
+    fn record_invocation(&self, query_name: &'static str, cache_hit: bool) {
+        let mut counters = self.counters.lock();
+        let counters = counters.entry(query_name).or_default();
+        counters.invocations += 1;
+        if cache_hit {
+            counters.cache_hits += 1;
+        }
+    }
+
+    fn record_ensure(&self, query_name: &'static str) {
+        self.counters.lock().entry(query_name).or_default().ensures += 1;
+    }
+
+    fn record_provider(&self, query_name: &'static str, total_time: Duration, self_time: Duration) {
+        let mut counters = self.counters.lock();
+        let counters = counters.entry(query_name).or_default();
+        counters.total_time += total_time;
+        counters.self_time += self_time;
+    }
+}
+
+thread_local! {
+    // For each query provider that is being measured on this thread, the time
+    // spent in the providers nested in it, innermost provider last.
+    static NESTED_PROVIDER_TIMES: RefCell<Vec<Duration>> = RefCell::new(Vec::new());
+}
+
+#[must_use]
+pub struct QueryStatsGuard<'a> {
+    recorder_and_start: Option<(&'a QueryStatsRecorder, &'static str, Instant)>,
+}
+
+impl<'a> QueryStatsGuard<'a> {
+    fn start(recorder: &'a QueryStatsRecorder, query_name: &'static str) -> Self {
+        NESTED_PROVIDER_TIMES.with(|times| times.borrow_mut().push(Duration::default()));
+        QueryStatsGuard { recorder_and_start: Some((recorder, query_name, Instant::now())) }
+    }
+
+    #[inline]
+    fn none() -> Self {
+        QueryStatsGuard { recorder_and_start: None }
+    }
+}
+
+impl Drop for QueryStatsGuard<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some((recorder, query_name, start)) = self.recorder_and_start.take() {
+            let total_time = start.elapsed();
+            let nested_time = NESTED_PROVIDER_TIMES.with(|times| {
+                let mut times = times.borrow_mut();
+                let nested_time = times.pop().unwrap();
+                if let Some(parent_nested_time) = times.last_mut() {
+                    *parent_nested_time += total_time;
+                }
+                nested_time
+            });
+            let self_time = total_time.checked_sub(nested_time).unwrap_or_default();
+            recorder.record_provider(query_name, total_time, self_time);
+        }
+    }
+}
+
 pub fn print_time_passes_entry(do_it: bool, what: &str, dur: Duration) {
     if !do_it {
         return;
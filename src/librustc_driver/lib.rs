@@ -160,11 +160,13 @@ pub fn run_compiler(
 
     let sopts = config::build_session_options(&matches);
     let cfg = interface::parse_cfgspecs(matches.opt_strs("cfg"));
+    let check_cfg = interface::parse_check_cfg(matches.opt_strs("check-cfg"));
 
-    let mut dummy_config = |sopts, cfg, diagnostic_output| {
+    let mut dummy_config = |sopts, cfg, check_cfg, diagnostic_output| {
         let mut config = interface::Config {
             opts: sopts,
             crate_cfg: cfg,
+            crate_check_cfg: check_cfg,
             input: Input::File(PathBuf::new()),
             input_path: None,
             output_file: None,
@@ -192,7 +194,7 @@ pub fn run_compiler(
         Some(v) => v,
         None => match matches.free.len() {
             0 => {
-                let config = dummy_config(sopts, cfg, diagnostic_output);
+                let config = dummy_config(sopts, cfg, check_cfg, diagnostic_output);
                 interface::run_compiler(config, |compiler| {
                     let sopts = &compiler.session().opts;
                     if sopts.describe_lints {
@@ -232,7 +234,7 @@ pub fn run_compiler(
     if let Some(err) = input_err {
         // Immediately stop compilation if there was an issue reading
         // the input (for example if the input stream is not UTF-8).
-        interface::run_compiler(dummy_config(sopts, cfg, diagnostic_output), |compiler| {
+        interface::run_compiler(dummy_config(sopts, cfg, check_cfg, diagnostic_output), |compiler| {
             compiler.session().err(&err.to_string());
         });
         return Err(ErrorReported);
@@ -241,6 +243,7 @@ pub fn run_compiler(
     let mut config = interface::Config {
         opts: sopts,
         crate_cfg: cfg,
+        crate_check_cfg: check_cfg,
         input,
         input_path: input_file_path,
         output_file: ofile,
@@ -71,7 +71,7 @@ fn annotation_type_for_level(level: Level) -> AnnotationType {
         Level::Note => AnnotationType::Note,
         Level::Help => AnnotationType::Help,
         // FIXME(#59346): Not sure how to map these two levels
-        Level::Cancelled | Level::FailureNote => AnnotationType::Error,
+        Level::Cancelled | Level::FailureNote | Level::Expect(_) => AnnotationType::Error,
     }
 }
 
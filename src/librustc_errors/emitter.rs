@@ -192,6 +192,10 @@ pub trait Emitter {
     /// other formats can, and will, simply ignore it.
     fn emit_artifact_notification(&mut self, _path: &Path, _artifact_type: &str) {}
 
+    /// Records the names and descriptions of the lints known to the compiler, for emitters that
+    /// describe the lints they report.
+    fn register_lints(&mut self, _lints: &[(String, &'static str)]) {}
+
     /// Checks if should show explanations about "rustc --explain"
     fn should_show_explain(&self) -> bool {
         true
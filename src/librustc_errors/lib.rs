@@ -17,6 +17,7 @@ use rustc_data_structures::fx::{FxHashSet, FxIndexMap};
 use rustc_data_structures::stable_hasher::StableHasher;
 use rustc_data_structures::sync::{self, Lock, Lrc};
 use rustc_data_structures::AtomicRef;
+use rustc_span::def_id::DefPathHash;
 use rustc_span::source_map::SourceMap;
 use rustc_span::{Loc, MultiSpan, Span};
 
@@ -34,6 +35,7 @@ pub mod emitter;
 pub mod json;
 mod lock;
 pub mod registry;
+pub mod sarif;
 mod snippet;
 mod styled_buffer;
 pub use snippet::Style;
@@ -318,6 +320,10 @@ struct HandlerInner {
 
     /// The warning count, used for a recap upon finishing
     deduplicated_warn_count: usize,
+
+    /// The `#[expect]` attributes whose lint has been emitted, as recorded by diagnostics at the
+    /// `Expect` level.
+    fulfilled_expectations: FxHashSet<LintExpectationId>,
 }
 
 /// A key denoting where from a diagnostic was stashed.
@@ -427,6 +433,7 @@ impl Handler {
                 emitted_diagnostic_codes: Default::default(),
                 emitted_diagnostics: Default::default(),
                 stashed_diagnostics: Default::default(),
+                fulfilled_expectations: Default::default(),
             }),
         }
     }
@@ -479,6 +486,11 @@ impl Handler {
         self.inner.borrow_mut().emit_stashed_diagnostics();
     }
 
+    /// Takes the IDs of all lint expectations fulfilled so far.
+    pub fn steal_fulfilled_expectation_ids(&self) -> FxHashSet<LintExpectationId> {
+        std::mem::take(&mut self.inner.borrow_mut().fulfilled_expectations)
+    }
+
     /// Construct a dummy builder with `Level::Cancelled`.
     ///
     /// Using this will neither report anything to the user (e.g. a warning),
@@ -507,6 +519,14 @@ impl Handler {
         result
     }
 
+    /// Construct a builder at the `Expect` level with the `msg`.
+    ///
+    /// Emitting it doesn't report anything to the user, it only records that the lint
+    /// expectation `id` is fulfilled.
+    pub fn struct_expect(&self, msg: &str, id: LintExpectationId) -> DiagnosticBuilder<'_> {
+        DiagnosticBuilder::new(self, Level::Expect(id), msg)
+    }
+
     /// Construct a builder at the `Warning` level with the `msg`.
     pub fn struct_warn(&self, msg: &str) -> DiagnosticBuilder<'_> {
         let mut result = DiagnosticBuilder::new(self, Level::Warning, msg);
@@ -710,6 +730,11 @@ impl Handler {
         self.inner.borrow_mut().emit_artifact_notification(path, artifact_type)
     }
 
+    /// See `Emitter::register_lints`.
+    pub fn register_lints(&self, lints: &[(String, &'static str)]) {
+        self.inner.borrow_mut().emitter.register_lints(lints)
+    }
+
     pub fn delay_as_bug(&self, diagnostic: Diagnostic) {
         self.inner.borrow_mut().delay_as_bug(diagnostic)
     }
@@ -735,6 +760,14 @@ impl HandlerInner {
             return;
         }
 
+        if let Expect(id) = diagnostic.level {
+            // Tracked like any other diagnostic, so that the expectation is fulfilled again when
+            // the query that emitted it is replayed from the incremental cache.
+            (*TRACK_DIAGNOSTICS)(diagnostic);
+            self.fulfilled_expectations.insert(id);
+            return;
+        }
+
         if diagnostic.level == Warning && !self.flags.can_emit_warnings {
             return;
         }
@@ -953,8 +986,23 @@ pub enum Level {
     Help,
     Cancelled,
     FailureNote,
+    /// Records that a lint expectation is fulfilled instead of reporting anything.
+    Expect(LintExpectationId),
 }
 
+/// Identifies a lint named in an `#[expect]` attribute.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, RustcEncodable, RustcDecodable)]
+pub enum LintExpectationId {
+    /// Identifies the attribute by its `AttrId`, which is only valid within a session. Lints
+    /// emitted before the HIR is built use these, and they are never cached.
+    Unstable { attr_id: u32, lint_index: u16 },
+    /// Identifies the attribute by the HIR node it is on and its position among the node's
+    /// attributes, which stays valid when a diagnostic is replayed in a later session.
+    Stable { owner: DefPathHash, local_id: u32, attr_index: u16, lint_index: u16 },
+}
+
+rustc_data_structures::impl_stable_hash_via_hash!(LintExpectationId);
+
 impl fmt::Display for Level {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.to_str().fmt(f)
@@ -978,7 +1026,7 @@ impl Level {
                 spec.set_fg(Some(Color::Cyan)).set_intense(true);
             }
             FailureNote => {}
-            Cancelled => unreachable!(),
+            Cancelled | Expect(_) => unreachable!(),
         }
         spec
     }
@@ -992,6 +1040,7 @@ impl Level {
             Help => "help",
             FailureNote => "failure-note",
             Cancelled => panic!("Shouldn't call on cancelled error"),
+            Expect(_) => "expect",
         }
     }
 
@@ -0,0 +1,366 @@
+//! A SARIF emitter for errors.
+//!
+//! Unlike the JSON emitter, which prints every diagnostic as soon as it is emitted, this collects
+//! the diagnostics and writes a single [SARIF 2.1.0] log when it is dropped, at the end of the
+//! compilation. Diagnostics become SARIF `results`, error codes and lints become the `rules` of
+//! the tool, and suggestions become `fixes`. Diagnostics without a span or a code, such as the
+//! final error count, are reported as tool execution notifications instead.
+//!
+//! [SARIF 2.1.0]: https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+
+use rustc_span::source_map::{FilePathMapping, SourceMap};
+
+use crate::emitter::Emitter;
+use crate::registry::Registry;
+use crate::{CodeSuggestion, DiagnosticId, Level, SubDiagnostic};
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::sync::Lrc;
+use rustc_serialize::json::{as_pretty_json, Json, Object};
+use rustc_span::{MultiSpan, Span, SpanLabel};
+use std::io::{self, Write};
+
+#[cfg(test)]
+mod tests;
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const ERROR_INDEX: &str = "https://doc.rust-lang.org/error-index.html";
+
+pub struct SarifEmitter {
+    dst: Box<dyn Write + Send>,
+    registry: Option<Registry>,
+    sm: Lrc<SourceMap>,
+    /// The descriptions of the known lints, by name.
+    lints: FxHashMap<String, &'static str>,
+    /// The error codes and lints reported so far, in the order they were first reported.
+    rules: Vec<DiagnosticId>,
+    rule_indices: FxHashMap<DiagnosticId, usize>,
+    results: Vec<Json>,
+    notifications: Vec<Json>,
+    execution_successful: bool,
+}
+
+impl SarifEmitter {
+    pub fn stderr(registry: Option<Registry>, source_map: Lrc<SourceMap>) -> SarifEmitter {
+        SarifEmitter::new(Box::new(io::BufWriter::new(io::stderr())), registry, source_map)
+    }
+
+    pub fn basic() -> SarifEmitter {
+        let file_path_mapping = FilePathMapping::empty();
+        SarifEmitter::stderr(None, Lrc::new(SourceMap::new(file_path_mapping)))
+    }
+
+    pub fn new(
+        dst: Box<dyn Write + Send>,
+        registry: Option<Registry>,
+        source_map: Lrc<SourceMap>,
+    ) -> SarifEmitter {
+        SarifEmitter {
+            dst,
+            registry,
+            sm: source_map,
+            lints: Default::default(),
+            rules: Vec::new(),
+            rule_indices: Default::default(),
+            results: Vec::new(),
+            notifications: Vec::new(),
+            execution_successful: true,
+        }
+    }
+
+    fn rule_index(&mut self, code: &DiagnosticId) -> usize {
+        let rules = &mut self.rules;
+        *self.rule_indices.entry(code.clone()).or_insert_with(|| {
+            rules.push(code.clone());
+            rules.len() - 1
+        })
+    }
+
+    fn rule(&self, code: &DiagnosticId) -> Json {
+        let mut rule = Vec::new();
+        match code {
+            DiagnosticId::Error(code) => {
+                rule.push(("id", Json::String(code.clone())));
+                let explanation = self
+                    .registry
+                    .as_ref()
+                    .and_then(|registry| registry.try_find_description(code).ok().flatten());
+                if let Some(explanation) = explanation {
+                    rule.push(("fullDescription", message(explanation.to_string())));
+                }
+                rule.push(("helpUri", Json::String(format!("{}#{}", ERROR_INDEX, code))));
+            }
+            DiagnosticId::Lint(name) => {
+                rule.push(("id", Json::String(name.clone())));
+                if let Some(desc) = self.lints.get(name) {
+                    rule.push(("shortDescription", message(desc.to_string())));
+                }
+            }
+        }
+        object(rule)
+    }
+
+    fn result(&mut self, diag: &crate::Diagnostic) -> Json {
+        let (primary, secondary): (Vec<_>, Vec<_>) = diag
+            .span
+            .span_labels()
+            .into_iter()
+            .filter(|label| !label.span.is_dummy())
+            .partition(|label| label.is_primary);
+
+        let mut result = vec![
+            ("level", Json::String(sarif_level(diag.level).to_string())),
+            ("message", message(diag.message())),
+            ("locations", Json::Array(primary.iter().map(|l| self.span_label(l)).collect())),
+        ];
+
+        if let Some(code) = &diag.code {
+            let index = self.rule_index(code);
+            let id = match code {
+                DiagnosticId::Error(id) | DiagnosticId::Lint(id) => id.clone(),
+            };
+            result.push(("ruleId", Json::String(id)));
+            result.push(("ruleIndex", Json::U64(index as u64)));
+        }
+
+        let related: Vec<_> = secondary
+            .iter()
+            .map(|label| self.span_label(label))
+            .chain(diag.children.iter().flat_map(|child| self.sub_diagnostic(child)))
+            .collect();
+        if !related.is_empty() {
+            result.push(("relatedLocations", Json::Array(related)));
+        }
+
+        let stacks: Vec<_> =
+            primary.iter().filter_map(|label| self.macro_backtrace(label.span)).collect();
+        if !stacks.is_empty() {
+            result.push(("stacks", Json::Array(stacks)));
+        }
+
+        let fixes: Vec<_> = diag.suggestions.iter().flat_map(|sugg| self.fixes(sugg)).collect();
+        if !fixes.is_empty() {
+            result.push(("fixes", Json::Array(fixes)));
+        }
+
+        object(result)
+    }
+
+    /// Child diagnostics become related locations, with the level prepended to their message.
+    /// Those without a span are still reported, as a location that only has a message.
+    fn sub_diagnostic(&self, diag: &SubDiagnostic) -> Vec<Json> {
+        let text = format!("{}: {}", diag.level.to_str(), diag.message());
+        let span = diag.render_span.as_ref().unwrap_or(&diag.span);
+        let spans: Vec<_> = span.primary_spans().iter().filter(|sp| !sp.is_dummy()).collect();
+        if spans.is_empty() {
+            return vec![object(vec![("message", message(text))])];
+        }
+        spans
+            .into_iter()
+            .map(|&span| {
+                object(vec![
+                    ("physicalLocation", self.physical_location(span)),
+                    ("message", message(text.clone())),
+                ])
+            })
+            .collect()
+    }
+
+    fn span_label(&self, label: &SpanLabel) -> Json {
+        let mut location = vec![("physicalLocation", self.physical_location(label.span))];
+        if let Some(label) = &label.label {
+            location.push(("message", message(label.clone())));
+        }
+        object(location)
+    }
+
+    /// The macro invocations that created the code at `span`, innermost first.
+    fn macro_backtrace(&self, span: Span) -> Option<Json> {
+        let frames: Vec<_> = span
+            .macro_backtrace()
+            .map(|expn_data| {
+                let text = format!("in this expansion of `{}`", expn_data.kind.descr());
+                let location = object(vec![
+                    ("physicalLocation", self.physical_location(expn_data.call_site)),
+                    ("message", message(text)),
+                ]);
+                object(vec![("location", location)])
+            })
+            .collect();
+        if frames.is_empty() {
+            return None;
+        }
+        Some(object(vec![
+            ("message", message("macro backtrace".to_string())),
+            ("frames", Json::Array(frames)),
+        ]))
+    }
+
+    /// Every substitution of a suggestion is an alternative fix.
+    fn fixes(&self, suggestion: &CodeSuggestion) -> Vec<Json> {
+        suggestion
+            .substitutions
+            .iter()
+            .map(|substitution| {
+                // Group the replacements by file, keeping the order of the parts.
+                let mut changes: Vec<(String, Vec<Json>)> = Vec::new();
+                for part in &substitution.parts {
+                    let uri = self.uri(part.span);
+                    let inserted = object(vec![("text", Json::String(part.snippet.clone()))]);
+                    let replacement = object(vec![
+                        ("deletedRegion", self.region(part.span)),
+                        ("insertedContent", inserted),
+                    ]);
+                    match changes.iter_mut().find(|(change_uri, _)| *change_uri == uri) {
+                        Some((_, replacements)) => replacements.push(replacement),
+                        None => changes.push((uri, vec![replacement])),
+                    }
+                }
+                let changes = changes
+                    .into_iter()
+                    .map(|(uri, replacements)| {
+                        object(vec![
+                            ("artifactLocation", object(vec![("uri", Json::String(uri))])),
+                            ("replacements", Json::Array(replacements)),
+                        ])
+                    })
+                    .collect();
+                object(vec![
+                    ("description", message(suggestion.msg.clone())),
+                    ("artifactChanges", Json::Array(changes)),
+                    (
+                        "properties",
+                        object(vec![(
+                            "applicability",
+                            Json::String(format!("{:?}", suggestion.applicability)),
+                        )]),
+                    ),
+                ])
+            })
+            .collect()
+    }
+
+    fn physical_location(&self, span: Span) -> Json {
+        object(vec![
+            ("artifactLocation", object(vec![("uri", Json::String(self.uri(span)))])),
+            ("region", self.region(span)),
+        ])
+    }
+
+    fn uri(&self, span: Span) -> String {
+        // SARIF expects URIs, which always use forward slashes.
+        self.sm.span_to_filename(span).to_string().replace('\\', "/")
+    }
+
+    /// The region covered by `span`. Lines and columns are 1-based, columns count characters.
+    fn region(&self, span: Span) -> Json {
+        let start = self.sm.lookup_char_pos(span.lo());
+        let end = self.sm.lookup_char_pos(span.hi());
+        let byte_start = start.file.original_relative_byte_pos(span.lo()).0;
+        let byte_end = start.file.original_relative_byte_pos(span.hi()).0;
+        let mut region = vec![
+            ("startLine", Json::U64(start.line as u64)),
+            ("startColumn", Json::U64(start.col.0 as u64 + 1)),
+            ("endLine", Json::U64(end.line as u64)),
+            ("endColumn", Json::U64(end.col.0 as u64 + 1)),
+            ("byteOffset", Json::U64(byte_start as u64)),
+            ("byteLength", Json::U64(byte_end.saturating_sub(byte_start) as u64)),
+        ];
+        if let Ok(snippet) = self.sm.span_to_snippet(span) {
+            region.push(("snippet", object(vec![("text", Json::String(snippet))])));
+        }
+        object(region)
+    }
+
+    fn log(&self) -> Json {
+        let rules = self.rules.iter().map(|code| self.rule(code)).collect();
+        let driver = object(vec![
+            ("name", Json::String("rustc".to_string())),
+            ("informationUri", Json::String("https://www.rust-lang.org/".to_string())),
+            ("rules", Json::Array(rules)),
+        ]);
+        let invocation = object(vec![
+            ("executionSuccessful", Json::Boolean(self.execution_successful)),
+            ("toolExecutionNotifications", Json::Array(self.notifications.clone())),
+        ]);
+        let run = object(vec![
+            ("tool", object(vec![("driver", driver)])),
+            ("invocations", Json::Array(vec![invocation])),
+            ("results", Json::Array(self.results.clone())),
+            ("columnKind", Json::String("unicodeCodePoints".to_string())),
+        ]);
+        object(vec![
+            ("$schema", Json::String(SCHEMA.to_string())),
+            ("version", Json::String("2.1.0".to_string())),
+            ("runs", Json::Array(vec![run])),
+        ])
+    }
+}
+
+impl Emitter for SarifEmitter {
+    fn emit_diagnostic(&mut self, diag: &crate::Diagnostic) {
+        if let Level::Bug | Level::Fatal | Level::Error = diag.level {
+            self.execution_successful = false;
+        }
+
+        if diag.code.is_none() && !has_span(&diag.span) {
+            let notification = object(vec![
+                ("level", Json::String(sarif_level(diag.level).to_string())),
+                ("message", message(diag.message())),
+            ]);
+            self.notifications.push(notification);
+        } else {
+            let result = self.result(diag);
+            self.results.push(result);
+        }
+    }
+
+    fn register_lints(&mut self, lints: &[(String, &'static str)]) {
+        self.lints.extend(lints.iter().cloned());
+    }
+
+    fn source_map(&self) -> Option<&Lrc<SourceMap>> {
+        Some(&self.sm)
+    }
+
+    fn should_show_explain(&self) -> bool {
+        // The explanations of the error codes are part of the rules already.
+        false
+    }
+}
+
+impl Drop for SarifEmitter {
+    fn drop(&mut self) {
+        let log = self.log();
+        let result =
+            writeln!(&mut self.dst, "{}", as_pretty_json(&log)).and_then(|_| self.dst.flush());
+        // Don't turn a panic into an abort, the log can't be written properly anyway.
+        if let Err(e) = result {
+            if !std::thread::panicking() {
+                panic!("failed to print diagnostics: {:?}", e);
+            }
+        }
+    }
+}
+
+fn has_span(span: &MultiSpan) -> bool {
+    span.primary_spans().iter().any(|sp| !sp.is_dummy())
+}
+
+fn sarif_level(level: Level) -> &'static str {
+    match level {
+        Level::Bug | Level::Fatal | Level::Error => "error",
+        Level::Warning => "warning",
+        Level::Note | Level::Help | Level::FailureNote => "note",
+        Level::Cancelled | Level::Expect(_) => "none",
+    }
+}
+
+/// A SARIF message object.
+fn message(text: String) -> Json {
+    object(vec![("text", Json::String(text))])
+}
+
+fn object(fields: Vec<(&str, Json)>) -> Json {
+    Json::Object(fields.into_iter().map(|(k, v)| (k.to_string(), v)).collect::<Object>())
+}
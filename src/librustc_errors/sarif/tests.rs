@@ -0,0 +1,156 @@
+use super::*;
+
+use crate::registry::Registry;
+use crate::{Applicability, Handler};
+use rustc_serialize::json;
+use rustc_span::source_map::{FilePathMapping, SourceMap};
+use rustc_span::{BytePos, Span};
+
+use std::path::Path;
+use std::str;
+use std::sync::{Arc, Mutex};
+
+struct Shared<T> {
+    data: Arc<Mutex<T>>,
+}
+
+impl<T: Write> Write for Shared<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.data.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.data.lock().unwrap().flush()
+    }
+}
+
+fn with_default_session_globals(f: impl FnOnce()) {
+    let session_globals = rustc_span::SessionGlobals::new(rustc_span::edition::DEFAULT_EDITION);
+    rustc_span::SESSION_GLOBALS.set(&session_globals, f);
+}
+
+/// Emits diagnostics about `code` with `f` and returns the SARIF log written by the emitter.
+fn test_log(code: &str, f: impl FnOnce(&Handler)) -> Json {
+    let mut log = None;
+    with_default_session_globals(|| {
+        let sm = Lrc::new(SourceMap::new(FilePathMapping::empty()));
+        sm.new_source_file(Path::new("test.rs").to_owned().into(), code.to_owned());
+
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let registry = Registry::new(&[("E0001", Some("An explanation."))]);
+        let mut emitter =
+            SarifEmitter::new(Box::new(Shared { data: output.clone() }), Some(registry), sm);
+        emitter.register_lints(&[("unused_foo".to_string(), "detects unused foos")]);
+
+        let handler = Handler::with_emitter(true, None, Box::new(emitter));
+        f(&handler);
+        // The log is only written once the emitter is dropped.
+        drop(handler);
+
+        let bytes = output.lock().unwrap();
+        log = Some(json::from_str(str::from_utf8(&bytes).unwrap()).unwrap());
+    });
+    log.unwrap()
+}
+
+fn result(log: &Json, index: usize) -> &Json {
+    &log.find_path(&["runs"]).unwrap().as_array().unwrap()[0]
+        .find("results")
+        .unwrap()
+        .as_array()
+        .unwrap()[index]
+}
+
+#[test]
+fn error_with_code() {
+    let log = test_log("let x = 1;", |handler| {
+        let span = Span::with_root_ctxt(BytePos(4), BytePos(5));
+        handler
+            .struct_span_err_with_code(span, "foo", DiagnosticId::Error("E0001".to_string()))
+            .span_label(span, "bar")
+            .note("baz")
+            .emit();
+    });
+
+    assert_eq!(log.find("version").unwrap().as_string(), Some("2.1.0"));
+    let run = &log.find("runs").unwrap().as_array().unwrap()[0];
+    let rule = &run.find_path(&["tool", "driver", "rules"]).unwrap().as_array().unwrap()[0];
+    assert_eq!(rule.find("id").unwrap().as_string(), Some("E0001"));
+    assert_eq!(
+        rule.find_path(&["fullDescription", "text"]).unwrap().as_string(),
+        Some("An explanation.")
+    );
+    let invocation = &run.find("invocations").unwrap().as_array().unwrap()[0];
+    assert_eq!(invocation.find("executionSuccessful").unwrap().as_boolean(), Some(false));
+
+    let result = result(&log, 0);
+    assert_eq!(result.find("ruleId").unwrap().as_string(), Some("E0001"));
+    assert_eq!(result.find("ruleIndex").unwrap().as_u64(), Some(0));
+    assert_eq!(result.find("level").unwrap().as_string(), Some("error"));
+    assert_eq!(result.find_path(&["message", "text"]).unwrap().as_string(), Some("foo"));
+
+    let location = &result.find("locations").unwrap().as_array().unwrap()[0];
+    assert_eq!(location.find_path(&["message", "text"]).unwrap().as_string(), Some("bar"));
+    let region = location.find_path(&["physicalLocation", "region"]).unwrap();
+    assert_eq!(region.find("startLine").unwrap().as_u64(), Some(1));
+    assert_eq!(region.find("startColumn").unwrap().as_u64(), Some(5));
+    assert_eq!(region.find("endColumn").unwrap().as_u64(), Some(6));
+    assert_eq!(region.find_path(&["snippet", "text"]).unwrap().as_string(), Some("x"));
+
+    let related = &result.find("relatedLocations").unwrap().as_array().unwrap()[0];
+    assert_eq!(related.find_path(&["message", "text"]).unwrap().as_string(), Some("note: baz"));
+}
+
+#[test]
+fn lint_with_suggestion() {
+    let log = test_log("let x = 1;", |handler| {
+        let span = Span::with_root_ctxt(BytePos(4), BytePos(5));
+        let mut diag = handler.struct_span_warn(span, "unused foo");
+        diag.code(DiagnosticId::Lint("unused_foo".to_string()));
+        diag.span_suggestion(span, "rename it", "_x".to_string(), Applicability::MaybeIncorrect);
+        diag.emit();
+    });
+
+    let run = &log.find("runs").unwrap().as_array().unwrap()[0];
+    let rule = &run.find_path(&["tool", "driver", "rules"]).unwrap().as_array().unwrap()[0];
+    assert_eq!(rule.find("id").unwrap().as_string(), Some("unused_foo"));
+    assert_eq!(
+        rule.find_path(&["shortDescription", "text"]).unwrap().as_string(),
+        Some("detects unused foos")
+    );
+
+    let result = result(&log, 0);
+    assert_eq!(result.find("level").unwrap().as_string(), Some("warning"));
+    let fix = &result.find("fixes").unwrap().as_array().unwrap()[0];
+    assert_eq!(fix.find_path(&["description", "text"]).unwrap().as_string(), Some("rename it"));
+    let change = &fix.find("artifactChanges").unwrap().as_array().unwrap()[0];
+    let uri = change.find_path(&["artifactLocation", "uri"]).unwrap();
+    assert_eq!(uri.as_string(), Some("test.rs"));
+    let replacement = &change.find("replacements").unwrap().as_array().unwrap()[0];
+    assert_eq!(
+        replacement.find_path(&["insertedContent", "text"]).unwrap().as_string(),
+        Some("_x")
+    );
+    assert_eq!(
+        replacement.find_path(&["deletedRegion", "byteOffset"]).unwrap().as_u64(),
+        Some(4)
+    );
+}
+
+#[test]
+fn notification_without_span() {
+    let log = test_log("", |handler| {
+        handler.struct_warn("1 warning emitted").emit();
+    });
+
+    let run = &log.find("runs").unwrap().as_array().unwrap()[0];
+    assert!(run.find("results").unwrap().as_array().unwrap().is_empty());
+    let invocation = &run.find("invocations").unwrap().as_array().unwrap()[0];
+    assert_eq!(invocation.find("executionSuccessful").unwrap().as_boolean(), Some(true));
+    let notification =
+        &invocation.find("toolExecutionNotifications").unwrap().as_array().unwrap()[0];
+    assert_eq!(
+        notification.find_path(&["message", "text"]).unwrap().as_string(),
+        Some("1 warning emitted")
+    );
+}
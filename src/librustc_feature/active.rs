@@ -582,6 +582,32 @@ declare_features! (
     /// The smallest useful subset of `const_generics`.
     (active, min_const_generics, "1.46.0", Some(74878), None),
 
+    /// Allows `let PAT = EXPR else { DIVERGING_BLOCK };` statements.
+    (active, let_else, "1.47.0", None, None),
+
+    /// Allows `if let` guards in match arms.
+    (active, if_let_guard, "1.47.0", None, None),
+
+    /// Allows closures to capture disjoint fields of a variable rather than the whole variable.
+    (active, capture_disjoint_fields, "1.47.0", None, None),
+
+    /// Allows destructuring assignments, e.g. `(a, b) = (b, a)`.
+    (active, destructuring_assignment, "1.47.0", None, None),
+
+    /// Allows `const { ... }` as an inline constant expression or pattern.
+    (active, inline_const, "1.47.0", None, None),
+
+    /// Allows `async fn` in trait definitions and trait impls.
+    (active, async_fn_in_trait, "1.47.0", None, None),
+
+    /// Allows the `"C-unwind"` ABI and its `-unwind` siblings, which permit unwinding
+    /// across the FFI boundary, and makes other non-Rust ABIs abort on unwind.
+    (active, c_unwind, "1.47.0", Some(74990), None),
+
+    /// Allows the `#[must_not_suspend]` attribute, which warns about values of the
+    /// marked type that are held across a suspend point.
+    (active, must_not_suspend, "1.47.0", None, None),
+
     // -------------------------------------------------------------------------
     // feature-group-end: actual feature gates
     // -------------------------------------------------------------------------
@@ -600,4 +626,8 @@ pub const INCOMPLETE_FEATURES: &[Symbol] = &[
     sym::const_trait_bound_opt_out,
     sym::lazy_normalization_consts,
     sym::specialization,
+    sym::if_let_guard,
+    sym::capture_disjoint_fields,
+    sym::inline_const,
+    sym::async_fn_in_trait,
 ];
@@ -342,6 +342,15 @@ pub const BUILTIN_ATTRIBUTES: &[BuiltinAttribute] = &[
         register_tool, CrateLevel, template!(List: "tool1, tool2, ..."),
         experimental!(register_tool),
     ),
+    // RFC 2383
+    gated!(
+        expect, Normal, template!(List: r#"lint1, lint2, ..., /*opt*/ reason = "...""#),
+        lint_reasons, experimental!(expect)
+    ),
+    gated!(
+        must_not_suspend, AssumedUsed, template!(Word, NameValueStr: "reason"),
+        experimental!(must_not_suspend)
+    ),
 
     // ==========================================================================
     // Internal attributes: Stability, deprecation, and unsafe:
@@ -68,6 +68,14 @@ impl DefPathTable {
     pub fn size(&self) -> usize {
         self.index_to_key.len()
     }
+
+    pub fn enumerated_keys_and_path_hashes(
+        &self,
+    ) -> impl Iterator<Item = (DefIndex, &DefKey, &DefPathHash)> + '_ {
+        self.index_to_key
+            .iter_enumerated()
+            .map(move |(index, key)| (index, key, &self.def_path_hashes[index]))
+    }
 }
 
 /// The definition table containing node definitions.
@@ -27,4 +27,7 @@ pub use persist::prepare_session_directory;
 pub use persist::save_dep_graph;
 pub use persist::save_work_product_index;
 pub use persist::LoadResult;
+pub use persist::{
+    fetch_shared_work_product, publish_shared_work_product, shared_work_product_key,
+};
 pub use persist::{load_dep_graph, DepGraphFuture};
@@ -39,6 +39,11 @@
 //!    at the beginning of the session has become obsolete because we have just
 //!    published a more current version. Thus the compiler will delete it.
 //!
+//! With `-Z incremental-shared-cache`, step 3 falls back to seeding the new
+//! session directory from a cache shared with other incremental directories
+//! if there is no finalized session directory, and step 5 also publishes the
+//! finalized directory to that cache. See the `shared_cache` module.
+//!
 //! ## Garbage Collection
 //!
 //! Naively following the above protocol might lead to old session directories
@@ -117,6 +122,8 @@ use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use rand::{thread_rng, RngCore};
 
+use super::shared_cache;
+
 #[cfg(test)]
 mod tests;
 
@@ -246,13 +253,16 @@ pub fn prepare_session_directory(
         let source_directory = if let Some(dir) = source_directory {
             dir
         } else {
-            // There's nowhere to copy from, we're done
+            // There's nowhere to copy from locally. Start out with whatever
+            // another checkout of this crate published to the shared cache, if
+            // anything.
             debug!(
-                "no source directory found. Continuing with empty session \
-                    directory."
+                "no source directory found. Continuing with empty or shared \
+                    session directory."
             );
 
-            sess.init_incr_comp_session(session_dir, directory_lock, false);
+            let seeded = shared_cache::seed_session_directory(sess, &session_dir);
+            sess.init_incr_comp_session(session_dir, directory_lock, seeded);
             return;
         };
 
@@ -365,6 +375,8 @@ pub fn finalize_session_directory(sess: &Session, svh: Svh) {
         Ok(_) => {
             debug!("finalize_session_directory() - directory renamed successfully");
 
+            shared_cache::publish_session_directory(sess, &new_path);
+
             // This unlocks the directory
             sess.finalize_incr_comp_session(new_path);
         }
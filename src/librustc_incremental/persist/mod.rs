@@ -8,6 +8,7 @@ mod file_format;
 mod fs;
 mod load;
 mod save;
+mod shared_cache;
 mod work_product;
 
 pub use fs::finalize_session_directory;
@@ -21,5 +22,8 @@ pub use load::LoadResult;
 pub use load::{load_dep_graph, DepGraphFuture};
 pub use save::save_dep_graph;
 pub use save::save_work_product_index;
+pub use shared_cache::{
+    fetch_shared_work_product, publish_shared_work_product, shared_work_product_key,
+};
 pub use work_product::copy_cgu_workproduct_to_incr_comp_cache_dir;
 pub use work_product::delete_workproduct_files;
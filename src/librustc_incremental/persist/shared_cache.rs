@@ -0,0 +1,363 @@
+//! A content-addressed incremental compilation cache that can be shared by
+//! several incremental directories, e.g. by multiple checkouts of the same
+//! repository on one machine, enabled with `-Z incremental-shared-cache=DIR`.
+//!
+//! The shared cache directory has the following layout:
+//!
+//! ```text
+//! DIR/blobs/{fingerprint}          contents of a single file, named by its fingerprint
+//! DIR/sessions/{fingerprint}       manifest of the last session published for a crate
+//! DIR/work-products/{fingerprint}  manifest of the object files of a single codegen unit
+//! ```
+//!
+//! Whenever a session directory is finalized, every file in it (the
+//! dependency graph, the query result cache, the work product index and the
+//! object files it refers to) is stored as a blob and a manifest listing the
+//! file names and blob fingerprints is written for the crate. Files with the
+//! same contents are only stored once, no matter how many checkouts produced
+//! them.
+//!
+//! When a crate has no finalized session directory of its own yet, its new
+//! session directory is seeded from that manifest instead of starting out
+//! empty. From then on the usual red/green algorithm decides what can be
+//! reused, exactly as if the session had been produced locally, since all of
+//! the involved fingerprints are stable across checkouts.
+//!
+//! In addition, the object files of every codegen unit are published on
+//! their own, keyed by a fingerprint of the codegen unit's name and of
+//! everything that was read while translating it to LLVM IR (see
+//! `DepGraph::input_fingerprint_of`). When a codegen unit has to be
+//! translated again, e.g. because the session it was seeded from is out of
+//! date, its key is computed afterwards and, if another session already
+//! produced object files for the same key, those are used instead of running
+//! LLVM. This is only done when not performing LTO.
+//!
+//! Manifests and work product keys include a fingerprint of everything that
+//! has to match for one session to be usable by another: the crate name and
+//! disambiguator, the tracked command-line options, the compiler version and,
+//! if debuginfo is emitted, the (remapped) working directory that ends up in
+//! the object files. Pass `--remap-path-prefix` to share debuginfo-enabled
+//! builds between checkouts in different directories.
+//!
+//! Blobs and manifests are first written to a temporary file and then renamed
+//! into place, so readers only ever see complete files and concurrent
+//! compiler processes never need to lock the shared directory.
+//!
+//! ## Cleaning up
+//!
+//! The compiler never deletes anything from the shared cache, so it grows
+//! with every distinct file that is published to it. Any file in it can be
+//! deleted at any time, also while compilers are using the cache, for example
+//! by a periodic job that removes files older than some number of days. A
+//! session whose dependency graph blob is gone is not seeded from at all.
+//! Missing object file blobs only cause the affected codegen units to be
+//! compiled again. Leftover `*.tmp` files belong to compilers that were
+//! killed while publishing and can be deleted as well.
+
+use crate::persist::fs::*;
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_data_structures::stable_hasher::StableHasher;
+use rustc_fs_util::link_or_copy;
+use rustc_middle::dep_graph::WorkProduct;
+use rustc_middle::mir::mono::CodegenUnit;
+use rustc_middle::ty::TyCtxt;
+use rustc_session::config::DebugInfo;
+use rustc_session::Session;
+
+use std::fs as std_fs;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use rand::{thread_rng, RngCore};
+
+const BLOBS_DIR: &str = "blobs";
+const SESSIONS_DIR: &str = "sessions";
+const WORK_PRODUCTS_DIR: &str = "work-products";
+
+/// Fills the empty session directory `session_dir` with the files of the last
+/// session published to the shared cache for the same crate. Returns `true` if
+/// the directory was seeded, in which case the dependency graph should be
+/// loaded from it.
+pub fn seed_session_directory(sess: &Session, session_dir: &Path) -> bool {
+    let shared_dir = match sess.opts.debugging_opts.incremental_shared_cache {
+        Some(ref dir) => dir,
+        None => return false,
+    };
+
+    let manifest_path = shared_dir.join(SESSIONS_DIR).join(crate_key(sess, session_dir).to_hex());
+    let manifest = match std_fs::read_to_string(&manifest_path) {
+        Ok(manifest) => manifest,
+        Err(_) => {
+            debug!("no shared session found at `{}`", manifest_path.display());
+            return false;
+        }
+    };
+
+    match link_session_files(shared_dir, &manifest, session_dir) {
+        Ok(file_count) => {
+            if sess.opts.debugging_opts.incremental_info {
+                println!(
+                    "[incremental] seeded session directory with {} files from shared cache `{}`",
+                    file_count,
+                    manifest_path.display()
+                );
+            }
+            true
+        }
+        Err(err) => {
+            // Start out cold rather than with a partial session.
+            debug!("failed to seed from `{}`: {}", manifest_path.display(), err);
+            for entry in std_fs::read_dir(session_dir).into_iter().flatten().flatten() {
+                let _ = std_fs::remove_file(entry.path());
+            }
+            false
+        }
+    }
+}
+
+/// Publishes the contents of the finalized session directory `session_dir` to
+/// the shared cache, making it the session that new checkouts of the crate
+/// are seeded with.
+pub fn publish_session_directory(sess: &Session, session_dir: &Path) {
+    let shared_dir = match sess.opts.debugging_opts.incremental_shared_cache {
+        Some(ref dir) => dir,
+        None => return,
+    };
+
+    let _timer = sess.timer("incr_comp_publish_to_shared_cache");
+
+    if let Err(err) = try_publish_session_directory(sess, shared_dir, session_dir) {
+        sess.warn(&format!(
+            "failed to publish incremental compilation session to shared cache `{}`: {}",
+            shared_dir.display(),
+            err
+        ));
+    }
+}
+
+fn try_publish_session_directory(
+    sess: &Session,
+    shared_dir: &Path,
+    session_dir: &Path,
+) -> io::Result<()> {
+    std_fs::create_dir_all(shared_dir.join(BLOBS_DIR))?;
+    std_fs::create_dir_all(shared_dir.join(SESSIONS_DIR))?;
+
+    let mut paths: Vec<_> = std_fs::read_dir(session_dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<_>>()?;
+    paths.sort();
+
+    let mut manifest = String::new();
+    for path in &paths {
+        let file_name = match path.file_name().and_then(|name| name.to_str()) {
+            Some(file_name) => file_name,
+            None => continue,
+        };
+        let fingerprint = store_blob(shared_dir, path)?;
+        manifest.push_str(&format!("{} {}\n", fingerprint.to_hex(), file_name));
+    }
+
+    let manifest_path = shared_dir.join(SESSIONS_DIR).join(crate_key(sess, session_dir).to_hex());
+    write_atomically(&manifest_path, |tmp_path| std_fs::write(tmp_path, &manifest))
+}
+
+/// Returns the key under which the object files of `cgu` are published to
+/// the shared cache, or `None` if no shared cache is used or the inputs of
+/// `cgu` cannot be fingerprinted. Must only be called once `cgu` has been
+/// translated to LLVM IR or marked green.
+pub fn shared_work_product_key(tcx: TyCtxt<'_>, cgu: &CodegenUnit<'_>) -> Option<Fingerprint> {
+    tcx.sess.opts.debugging_opts.incremental_shared_cache.as_ref()?;
+    if !tcx.dep_graph.is_fully_enabled() {
+        return None;
+    }
+
+    let dep_node_index = tcx.dep_graph.dep_node_index_of(&cgu.codegen_dep_node(tcx));
+    let input_fingerprint = tcx.dep_graph.input_fingerprint_of(dep_node_index)?;
+
+    let mut hasher = StableHasher::new();
+    crate_key(tcx.sess, &tcx.sess.incr_comp_session_dir()).hash(&mut hasher);
+    (*cgu.name().as_str()).hash(&mut hasher);
+    input_fingerprint.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+/// Links the object files that some session published under `key` into the
+/// session directory and returns the work product for them, if there are any.
+pub fn fetch_shared_work_product(
+    sess: &Session,
+    cgu_name: &str,
+    key: Fingerprint,
+) -> Option<WorkProduct> {
+    let shared_dir = sess.opts.debugging_opts.incremental_shared_cache.as_ref()?;
+
+    let manifest_path = shared_dir.join(WORK_PRODUCTS_DIR).join(key.to_hex());
+    let manifest = std_fs::read_to_string(&manifest_path).ok()?;
+
+    let mut work_product =
+        WorkProduct { cgu_name: cgu_name.to_string(), saved_file: None, saved_dwarf_file: None };
+    let mut result = Ok(());
+    for (fingerprint, file_name) in manifest_entries(&manifest) {
+        let saved_file = match Path::new(file_name).extension().and_then(|ext| ext.to_str()) {
+            Some("o") => &mut work_product.saved_file,
+            Some("dwo") => &mut work_product.saved_dwarf_file,
+            _ => {
+                result = Err(malformed_manifest());
+                break;
+            }
+        };
+        result = link_or_copy(
+            blob_path(shared_dir, fingerprint),
+            in_incr_comp_dir_sess(sess, file_name),
+        )
+        .map(|_| ());
+        if result.is_err() {
+            break;
+        }
+        *saved_file = Some(file_name.to_string());
+    }
+
+    match result {
+        Ok(()) => {
+            if sess.opts.debugging_opts.incremental_info {
+                println!(
+                    "[incremental] reused object files of `{}` from shared cache `{}`",
+                    cgu_name,
+                    manifest_path.display()
+                );
+            }
+            Some(work_product)
+        }
+        Err(err) => {
+            debug!("failed to fetch `{}`: {}", manifest_path.display(), err);
+            for file_name in work_product.saved_files() {
+                let _ = std_fs::remove_file(in_incr_comp_dir_sess(sess, file_name));
+            }
+            None
+        }
+    }
+}
+
+/// Publishes the files of `work_product`, which have just been saved to the
+/// session directory, to the shared cache under `key`.
+pub fn publish_shared_work_product(sess: &Session, key: Fingerprint, work_product: &WorkProduct) {
+    let shared_dir = match sess.opts.debugging_opts.incremental_shared_cache {
+        Some(ref dir) => dir,
+        None => return,
+    };
+
+    if let Err(err) = try_publish_work_product(sess, shared_dir, key, work_product) {
+        sess.warn(&format!(
+            "failed to publish object files of `{}` to shared cache `{}`: {}",
+            work_product.cgu_name,
+            shared_dir.display(),
+            err
+        ));
+    }
+}
+
+fn try_publish_work_product(
+    sess: &Session,
+    shared_dir: &Path,
+    key: Fingerprint,
+    work_product: &WorkProduct,
+) -> io::Result<()> {
+    std_fs::create_dir_all(shared_dir.join(BLOBS_DIR))?;
+    std_fs::create_dir_all(shared_dir.join(WORK_PRODUCTS_DIR))?;
+
+    let mut manifest = String::new();
+    for file_name in work_product.saved_files() {
+        let fingerprint = store_blob(shared_dir, &in_incr_comp_dir_sess(sess, file_name))?;
+        manifest.push_str(&format!("{} {}\n", fingerprint.to_hex(), file_name));
+    }
+
+    let manifest_path = shared_dir.join(WORK_PRODUCTS_DIR).join(key.to_hex());
+    write_atomically(&manifest_path, |tmp_path| std_fs::write(tmp_path, &manifest))
+}
+
+/// Stores the file at `path` as a blob and returns the blob's fingerprint.
+fn store_blob(shared_dir: &Path, path: &Path) -> io::Result<Fingerprint> {
+    let contents = std_fs::read(path)?;
+    let mut hasher = StableHasher::new();
+    hasher.write(&contents);
+    let fingerprint: Fingerprint = hasher.finish();
+
+    let blob_path = blob_path(shared_dir, &fingerprint.to_hex());
+    if !blob_path.exists() {
+        write_atomically(&blob_path, |tmp_path| link_or_copy(path, tmp_path).map(|_| ()))?;
+    }
+    Ok(fingerprint)
+}
+
+/// Links the blobs listed in the session `manifest` into `session_dir` under
+/// their file names and returns the number of files. Blobs that have been
+/// deleted from the shared cache are skipped, except for the dependency
+/// graph: loading the session drops work products with missing files.
+fn link_session_files(shared_dir: &Path, manifest: &str, session_dir: &Path) -> io::Result<usize> {
+    let mut file_count = 0;
+    for (fingerprint, file_name) in manifest_entries(manifest) {
+        if file_name.is_empty() || file_name.contains(|c| c == '/' || c == '\\') {
+            return Err(malformed_manifest());
+        }
+        match link_or_copy(blob_path(shared_dir, fingerprint), session_dir.join(file_name)) {
+            Ok(_) => file_count += 1,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                debug!("blob `{}` for `{}` has been deleted", fingerprint, file_name);
+            }
+            Err(err) => return Err(err),
+        }
+    }
+
+    if !dep_graph_path_from(session_dir).exists() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, "dependency graph not found"));
+    }
+    Ok(file_count)
+}
+
+/// Iterates over the `(fingerprint, file name)` pairs of a manifest. A
+/// malformed line is returned with an empty file name.
+fn manifest_entries(manifest: &str) -> impl Iterator<Item = (&str, &str)> {
+    manifest.lines().map(|line| match line.find(' ') {
+        Some(i) => (&line[..i], &line[i + 1..]),
+        None => (line, ""),
+    })
+}
+
+fn malformed_manifest() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "malformed manifest")
+}
+
+/// Returns a fingerprint of everything that has to match between the crate
+/// that `session_dir` belongs to and another one for their sessions to be
+/// interchangeable.
+fn crate_key(sess: &Session, session_dir: &Path) -> Fingerprint {
+    let mut hasher = StableHasher::new();
+
+    // The crate directory is named after the crate name and disambiguator.
+    session_dir.parent().and_then(|dir| dir.file_name()).hash(&mut hasher);
+    sess.opts.dep_tracking_hash().hash(&mut hasher);
+    option_env!("CFG_VERSION").hash(&mut hasher);
+    if sess.opts.debuginfo != DebugInfo::None {
+        sess.working_dir.0.hash(&mut hasher);
+    }
+
+    hasher.finish()
+}
+
+fn blob_path(shared_dir: &Path, fingerprint: &str) -> PathBuf {
+    shared_dir.join(BLOBS_DIR).join(fingerprint)
+}
+
+/// Creates `path` by letting `write` create a temporary file next to it and
+/// renaming that into place.
+fn write_atomically(path: &Path, write: impl FnOnce(&Path) -> io::Result<()>) -> io::Result<()> {
+    let file_name = path.file_name().unwrap().to_string_lossy();
+    let tmp_path = path.with_file_name(format!("{}.{:x}.tmp", file_name, thread_rng().next_u64()));
+
+    let result = write(&tmp_path).and_then(|()| std_fs::rename(&tmp_path, path));
+    if result.is_err() {
+        let _ = std_fs::remove_file(&tmp_path);
+    }
+    result
+}
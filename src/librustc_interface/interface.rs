@@ -1,7 +1,7 @@
 pub use crate::passes::BoxedResolver;
 use crate::util;
 
-use rustc_ast::ast::{self, MetaItemKind};
+use rustc_ast::ast::{self, LitKind, MetaItemKind};
 use rustc_ast::token;
 use rustc_codegen_ssa::traits::CodegenBackend;
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
@@ -12,12 +12,13 @@ use rustc_errors::ErrorReported;
 use rustc_lint::LintStore;
 use rustc_middle::ty;
 use rustc_parse::new_parser_from_source_str;
-use rustc_session::config::{self, ErrorOutputType, Input, OutputFilenames};
+use rustc_session::config::{self, CheckCfg, ErrorOutputType, Input, OutputFilenames};
 use rustc_session::early_error;
 use rustc_session::lint;
 use rustc_session::parse::{CrateConfig, ParseSess};
 use rustc_session::{DiagnosticOutput, Session};
 use rustc_span::source_map::{FileLoader, FileName};
+use rustc_span::symbol::sym;
 use std::path::PathBuf;
 use std::result;
 use std::sync::{Arc, Mutex};
@@ -119,6 +120,79 @@ pub fn parse_cfgspecs(cfgspecs: Vec<String>) -> FxHashSet<(String, Option<String
     })
 }
 
+/// Converts strings provided as `--check-cfg [specs]` into a `CheckCfg`.
+pub fn parse_check_cfg(specs: Vec<String>) -> CheckCfg {
+    rustc_span::with_default_session_globals(move || {
+        let mut cfg = CheckCfg::default();
+
+        'specs: for s in specs {
+            let sess = ParseSess::with_silent_emitter();
+            let filename = FileName::cfg_spec_source_code(&s);
+            let mut parser = new_parser_from_source_str(&sess, filename, s.to_string());
+
+            macro_rules! error {
+                ($reason: expr) => {
+                    early_error(
+                        ErrorOutputType::default(),
+                        &format!(
+                            concat!("invalid `--check-cfg` argument: `{}` (", $reason, ")"),
+                            s
+                        ),
+                    )
+                };
+            }
+
+            match &mut parser.parse_meta_item() {
+                Ok(meta_item) if parser.token == token::Eof => {
+                    if let Some(args) = meta_item.meta_item_list() {
+                        if meta_item.has_name(sym::names) {
+                            let names_valid = cfg.names_valid.get_or_insert_with(Default::default);
+                            for arg in args {
+                                match arg.ident() {
+                                    Some(ident) if arg.is_word() => {
+                                        names_valid.insert(ident.name.to_string());
+                                    }
+                                    _ => error!("`names()` arguments must be simple identifiers"),
+                                }
+                            }
+                            continue 'specs;
+                        } else if meta_item.has_name(sym::values) {
+                            if let Some((name, values)) = args.split_first() {
+                                let ident = match name.ident() {
+                                    Some(ident) if name.is_word() => ident,
+                                    _ => error!(
+                                        "`values()` first argument must be a simple identifier"
+                                    ),
+                                };
+                                let values_valid =
+                                    cfg.values_valid.entry(ident.name.to_string()).or_default();
+                                for value in values {
+                                    match value.literal().map(|lit| &lit.kind) {
+                                        Some(LitKind::Str(s, _)) => {
+                                            values_valid.insert(s.to_string());
+                                        }
+                                        _ => error!("`values()` arguments must be string literals"),
+                                    }
+                                }
+                                continue 'specs;
+                            }
+                        }
+                    }
+                }
+                Ok(..) => {}
+                Err(err) => err.cancel(),
+            }
+
+            error!(
+                "expected `names(name1, name2, ... nameN)` or \
+                 `values(name, \"value1\", \"value2\", ... \"valueN\")`"
+            );
+        }
+
+        cfg
+    })
+}
+
 /// The compiler configuration
 pub struct Config {
     /// Command line options
@@ -126,6 +200,8 @@ pub struct Config {
 
     /// cfg! configuration in addition to the default ones
     pub crate_cfg: FxHashSet<(String, Option<String>)>,
+    /// The `cfg` names and values declared with `--check-cfg`
+    pub crate_check_cfg: CheckCfg,
 
     pub input: Input,
     pub input_path: Option<PathBuf>,
@@ -163,6 +239,7 @@ pub fn create_compiler_and_run<R>(config: Config, f: impl FnOnce(&Compiler) -> R
     let (sess, codegen_backend) = util::create_session(
         config.opts,
         config.crate_cfg,
+        config.crate_check_cfg,
         config.diagnostic_output,
         config.file_loader,
         config.input_path.clone(),
@@ -29,7 +29,8 @@ use rustc_parse::{parse_crate_from_file, parse_crate_from_source_str};
 use rustc_passes::{self, hir_stats, layout_test};
 use rustc_plugin_impl as plugin;
 use rustc_resolve::{Resolver, ResolverArenas};
-use rustc_session::config::{CrateType, Input, OutputFilenames, OutputType, PpMode, PpSourceMode};
+use rustc_session::config::{CrateType, ErrorOutputType, Input, OutputFilenames, OutputType};
+use rustc_session::config::{PpMode, PpSourceMode};
 use rustc_session::lint;
 use rustc_session::output::{filename_for_input, filename_for_metadata};
 use rustc_session::search_paths::PathKind;
@@ -204,6 +205,12 @@ pub fn register_plugins<'a>(
         }
     });
 
+    if let ErrorOutputType::Sarif = sess.opts.error_format {
+        let lints: Vec<_> =
+            lint_store.get_lints().iter().map(|lint| (lint.name_lower(), lint.desc)).collect();
+        sess.diagnostic().register_lints(&lints);
+    }
+
     Ok((krate, Lrc::new(lint_store)))
 }
 
@@ -1008,6 +1015,10 @@ pub fn start_codegen<'tcx>(
 
     info!("Post-codegen\n{:?}", tcx.debug_stats());
 
+    // Lints are still emitted while building the MIR that codegen needs, e.g. by const
+    // propagation, so expectations can only be checked once that is done.
+    tcx.sess.time("lint_expectation_checking", || rustc_lint::check_expectations(tcx));
+
     if tcx.sess.opts.output_types.contains_key(&OutputType::Mir) {
         if let Err(e) = mir::transform::dump_mir::emit_mir(tcx, outputs) {
             tcx.sess.err(&format!("could not emit MIR: {}", e));
@@ -384,7 +384,7 @@ impl Compiler {
         let queries = Queries::new(&self);
         let ret = f(&queries);
 
-        if self.session().opts.debugging_opts.query_stats {
+        if self.session().opts.debugging_opts.query_stats.is_some() {
             if let Ok(gcx) = queries.global_ctxt() {
                 gcx.peek_mut().print_stats();
             }
@@ -308,6 +308,7 @@ impl LintStore {
                 "requested on the command line with `{} {}`",
                 match level {
                     Level::Allow => "-A",
+                    Level::Expect(_) => unreachable!("`expect` is not a command line lint level"),
                     Level::Warn => "-W",
                     Level::Deny => "-D",
                     Level::Forbid => "-F",
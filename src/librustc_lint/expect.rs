@@ -0,0 +1,59 @@
+use rustc_hir::def_id::LOCAL_CRATE;
+use rustc_middle::lint::struct_lint_level;
+use rustc_middle::ty::TyCtxt;
+use rustc_session::lint::builtin::UNFULFILLED_LINT_EXPECTATIONS;
+use rustc_session::lint::Level;
+
+/// Emits `unfulfilled_lint_expectations` for every `#[expect]` attribute whose lint was never
+/// emitted in its scope.
+///
+/// This has to run after all other lints of the crate have been emitted, including the ones
+/// emitted during codegen. Lints replayed from the incremental cache fulfill expectations too,
+/// since fulfilling one is recorded by a diagnostic like any other.
+pub fn check_expectations(tcx: TyCtxt<'_>) {
+    let sess = tcx.sess;
+    let lint_levels = tcx.lint_levels(LOCAL_CRATE);
+    let mut fulfilled = sess.diagnostic().steal_fulfilled_expectation_ids();
+
+    // Nested expectations are recorded after the ones enclosing them, so walking backwards lets
+    // an unfulfilled inner expectation fulfill an outer
+    // `#[expect(unfulfilled_lint_expectations)]` before that one is looked at.
+    let mut unfulfilled = Vec::new();
+    for expectation in lint_levels.expectations.iter().rev() {
+        if fulfilled.contains(&expectation.id) || fulfilled.contains(&expectation.unstable_id) {
+            continue;
+        }
+        let (level, src) = lint_levels.sets.get_lint_level(
+            UNFULFILLED_LINT_EXPECTATIONS,
+            expectation.set,
+            None,
+            sess,
+        );
+        if let Level::Expect(id) = level {
+            // Doesn't emit anything, it only fulfills the enclosing expectation.
+            fulfilled.insert(id);
+        } else {
+            unfulfilled.push((expectation, level, src));
+        }
+    }
+
+    for (expectation, level, src) in unfulfilled.into_iter().rev() {
+        struct_lint_level(
+            sess,
+            UNFULFILLED_LINT_EXPECTATIONS,
+            level,
+            src,
+            Some(expectation.span.into()),
+            |lint| {
+                let mut err = lint.build(&format!(
+                    "this lint expectation is unfulfilled: `{}` was never emitted",
+                    expectation.lint_name
+                ));
+                if let Some(rationale) = expectation.reason {
+                    err.note(&rationale.as_str());
+                }
+                err.emit();
+            },
+        );
+    }
+}
@@ -5,19 +5,21 @@ use rustc_ast::attr;
 use rustc_ast::unwrap_or;
 use rustc_ast_pretty::pprust;
 use rustc_data_structures::fx::FxHashMap;
-use rustc_errors::{struct_span_err, Applicability};
+use rustc_errors::{struct_span_err, Applicability, LintExpectationId};
 use rustc_hir as hir;
 use rustc_hir::def_id::{CrateNum, LOCAL_CRATE};
 use rustc_hir::{intravisit, HirId};
 use rustc_middle::hir::map::Map;
 use rustc_middle::lint::LintDiagnosticBuilder;
-use rustc_middle::lint::{struct_lint_level, LintLevelMap, LintLevelSets, LintSet, LintSource};
+use rustc_middle::lint::{struct_lint_level, LintExpectation, LintLevelMap};
+use rustc_middle::lint::{LintLevelSets, LintSet, LintSource};
 use rustc_middle::ty::query::Providers;
 use rustc_middle::ty::TyCtxt;
 use rustc_session::lint::{builtin, Level, Lint, LintId};
 use rustc_session::parse::feature_err;
 use rustc_session::Session;
 use rustc_span::symbol::{sym, Symbol};
+use rustc_span::def_id::DefPathHash;
 use rustc_span::{source_map::MultiSpan, Span, DUMMY_SP};
 
 use std::cmp;
@@ -29,7 +31,8 @@ fn lint_levels(tcx: TyCtxt<'_>, cnum: CrateNum) -> LintLevelMap {
     let mut builder = LintLevelMapBuilder { levels, tcx, store };
     let krate = tcx.hir().krate();
 
-    let push = builder.levels.push(&krate.item.attrs, &store, true);
+    let crate_id = builder.stable_id(hir::CRATE_HIR_ID);
+    let push = builder.levels.push(&krate.item.attrs, &store, true, Some(crate_id));
     builder.levels.register_id(hir::CRATE_HIR_ID);
     for macro_def in krate.exported_macros {
         builder.levels.register_id(macro_def.hir_id);
@@ -44,6 +47,7 @@ pub struct LintLevelsBuilder<'s> {
     sess: &'s Session,
     sets: LintLevelSets,
     id_to_set: FxHashMap<HirId, u32>,
+    expectations: Vec<LintExpectation>,
     cur: u32,
     warn_about_weird_lints: bool,
 }
@@ -60,6 +64,7 @@ impl<'s> LintLevelsBuilder<'s> {
             sets: LintLevelSets::new(),
             cur: 0,
             id_to_set: Default::default(),
+            expectations: Vec::new(),
             warn_about_weird_lints,
         };
         builder.process_command_line(sess, store);
@@ -108,18 +113,22 @@ impl<'s> LintLevelsBuilder<'s> {
     /// * Lint attributes are validated, e.g., a `#[forbid]` can't be switched to
     ///   `#[allow]`
     ///
+    /// `hir_id` is the HIR node the attributes are on, if they are on one, as a `DefPathHash` of
+    /// its owner and its local id. It gives expectations IDs that are stable across sessions.
+    ///
     /// Don't forget to call `pop`!
     pub fn push(
         &mut self,
         attrs: &[ast::Attribute],
         store: &LintStore,
         is_crate_node: bool,
+        hir_id: Option<(DefPathHash, u32)>,
     ) -> BuilderPush {
         let mut specs = FxHashMap::default();
         let sess = self.sess;
         let bad_attr = |span| struct_span_err!(sess, span, E0452, "malformed lint attribute input");
-        for attr in attrs {
-            let level = match Level::from_symbol(attr.name_or_empty()) {
+        for (attr_index, attr) in attrs.iter().enumerate() {
+            let attr_level = match Level::from_attr(attr) {
                 None => continue,
                 Some(lvl) => lvl,
             };
@@ -175,7 +184,22 @@ impl<'s> LintLevelsBuilder<'s> {
                 }
             }
 
-            for li in metas {
+            for (lint_index, li) in metas.iter().enumerate() {
+                let level = match attr_level {
+                    Level::Expect(_) => Level::Expect(match hir_id {
+                        Some((owner, local_id)) => LintExpectationId::Stable {
+                            owner,
+                            local_id,
+                            attr_index: attr_index as u16,
+                            lint_index: lint_index as u16,
+                        },
+                        None => LintExpectationId::Unstable {
+                            attr_id: attr.id.as_u32(),
+                            lint_index: lint_index as u16,
+                        },
+                    }),
+                    level => level,
+                };
                 let meta_item = match li.meta_item() {
                     Some(meta_item) if meta_item.is_word() => meta_item,
                     _ => {
@@ -419,6 +443,38 @@ impl<'s> LintLevelsBuilder<'s> {
         let prev = self.cur;
         if !specs.is_empty() {
             self.cur = self.sets.list.len() as u32;
+
+            // A lint group expands to several specs sharing one source, but it is still a single
+            // expectation that any lint of the group fulfills.
+            let mut expectations: Vec<_> = specs
+                .values()
+                .filter_map(|&(level, src)| match (level, src) {
+                    (Level::Expect(id), LintSource::Node(lint_name, span, reason)) => {
+                        let unstable_id = match id {
+                            LintExpectationId::Stable { attr_index, lint_index, .. } => {
+                                LintExpectationId::Unstable {
+                                    attr_id: attrs[attr_index as usize].id.as_u32(),
+                                    lint_index,
+                                }
+                            }
+                            LintExpectationId::Unstable { .. } => id,
+                        };
+                        Some(LintExpectation {
+                            id,
+                            unstable_id,
+                            span,
+                            lint_name,
+                            reason,
+                            set: self.cur,
+                        })
+                    }
+                    _ => None,
+                })
+                .collect();
+            expectations.sort_by_key(|expectation| expectation.span);
+            expectations.dedup_by_key(|expectation| expectation.id);
+            self.expectations.extend(expectations);
+
             self.sets.list.push(LintSet::Node { specs, parent: prev });
         }
 
@@ -473,7 +529,11 @@ impl<'s> LintLevelsBuilder<'s> {
     }
 
     pub fn build_map(self) -> LintLevelMap {
-        LintLevelMap { sets: self.sets, id_to_set: self.id_to_set }
+        LintLevelMap {
+            sets: self.sets,
+            id_to_set: self.id_to_set,
+            expectations: self.expectations,
+        }
     }
 }
 
@@ -484,12 +544,17 @@ struct LintLevelMapBuilder<'a, 'tcx> {
 }
 
 impl LintLevelMapBuilder<'_, '_> {
+    /// Identifies `id` as the `DefPathHash` of its owner and its local id.
+    fn stable_id(&self, id: hir::HirId) -> (DefPathHash, u32) {
+        (self.tcx.def_path_hash(id.owner.to_def_id()), id.local_id.as_u32())
+    }
+
     fn with_lint_attrs<F>(&mut self, id: hir::HirId, attrs: &[ast::Attribute], f: F)
     where
         F: FnOnce(&mut Self),
     {
         let is_crate_hir = id == hir::CRATE_HIR_ID;
-        let push = self.levels.push(attrs, self.store, is_crate_hir);
+        let push = self.levels.push(attrs, self.store, is_crate_hir, Some(self.stable_id(id)));
         if push.changed {
             self.levels.register_id(id);
         }
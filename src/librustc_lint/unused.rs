@@ -811,7 +811,14 @@ declare_lint! {
     "unnecessary braces around an expression"
 }
 
-declare_lint_pass!(UnusedBraces => [UNUSED_BRACES]);
+#[derive(Default)]
+pub struct UnusedBraces {
+    /// If `Some(_)`, the `NodeId` of the anonymous constant of an inline `const` block,
+    /// whose braces are always necessary.
+    inline_const: Option<ast::NodeId>,
+}
+
+impl_lint_pass!(UnusedBraces => [UNUSED_BRACES]);
 
 impl UnusedDelimLint for UnusedBraces {
     const DELIM_STR: &'static str = "braces";
@@ -895,10 +902,16 @@ impl UnusedDelimLint for UnusedBraces {
 
 impl EarlyLintPass for UnusedBraces {
     fn check_expr(&mut self, cx: &EarlyContext<'_>, e: &ast::Expr) {
+        if let ExprKind::ConstBlock(ref anon_const) = e.kind {
+            self.inline_const = Some(anon_const.id);
+        }
         <Self as UnusedDelimLint>::check_expr(self, cx, e)
     }
 
     fn check_anon_const(&mut self, cx: &EarlyContext<'_>, c: &ast::AnonConst) {
+        if self.inline_const.take() == Some(c.id) {
+            return;
+        }
         self.check_unused_delims_expr(cx, &c.value, UnusedDelimsCtx::AnonConst, false, None, None);
     }
 
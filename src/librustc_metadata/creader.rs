@@ -10,8 +10,8 @@ use rustc_data_structures::fx::FxHashSet;
 use rustc_data_structures::svh::Svh;
 use rustc_data_structures::sync::Lrc;
 use rustc_expand::base::SyntaxExtension;
-use rustc_hir::def_id::{CrateNum, LocalDefId, LOCAL_CRATE};
-use rustc_hir::definitions::Definitions;
+use rustc_hir::def_id::{CrateNum, DefId, LocalDefId, LOCAL_CRATE};
+use rustc_hir::definitions::{DefPathHash, Definitions};
 use rustc_index::vec::IndexVec;
 use rustc_middle::middle::cstore::{CrateDepKind, CrateSource, ExternCrate};
 use rustc_middle::middle::cstore::{ExternCrateSource, MetadataLoaderDyn};
@@ -131,6 +131,14 @@ impl CStore {
         CrateMetadataRef { cdata, cstore: self }
     }
 
+    /// Finds the upstream item with the given `DefPathHash`, if any.
+    crate fn def_path_hash_to_def_id(&self, hash: DefPathHash) -> Option<DefId> {
+        self.metas.iter_enumerated().find_map(|(cnum, data)| {
+            let index = data.as_ref()?.def_path_hash_to_def_index(hash)?;
+            Some(DefId { krate: cnum, index })
+        })
+    }
+
     fn set_crate_data(&mut self, cnum: CrateNum, data: CrateMetadata) {
         assert!(self.metas[cnum].is_none(), "Overwriting crate metadata entry");
         self.metas[cnum] = Some(Lrc::new(data));
@@ -357,7 +365,6 @@ impl<'a> CrateLoader<'a> {
         };
 
         let crate_metadata = CrateMetadata::new(
-            self.sess,
             metadata,
             crate_root,
             raw_proc_macros,
@@ -1,7 +1,7 @@
 // Decoding metadata from a single crate's metadata
 
 use crate::creader::CrateMetadataRef;
-use crate::rmeta::table::{FixedSizeEncoding, Table};
+use crate::rmeta::table::{FixedSizeEncoding, SortedTable, Table};
 use crate::rmeta::*;
 
 use rustc_ast::ast;
@@ -16,7 +16,6 @@ use rustc_expand::proc_macro::{AttrProcMacro, BangProcMacro, ProcMacroDerive};
 use rustc_hir as hir;
 use rustc_hir::def::{CtorKind, CtorOf, DefKind, Res};
 use rustc_hir::def_id::{CrateNum, DefId, DefIndex, LocalDefId, CRATE_DEF_INDEX, LOCAL_CRATE};
-use rustc_hir::definitions::DefPathTable;
 use rustc_hir::definitions::{DefKey, DefPath, DefPathData, DefPathHash};
 use rustc_hir::lang_items;
 use rustc_index::vec::{Idx, IndexVec};
@@ -29,7 +28,6 @@ use rustc_middle::mir::interpret::{AllocDecodingSession, AllocDecodingState};
 use rustc_middle::mir::{self, interpret, Body, Promoted};
 use rustc_middle::ty::codec::TyDecoder;
 use rustc_middle::ty::{self, Ty, TyCtxt};
-use rustc_middle::util::common::record_time;
 use rustc_serialize::{opaque, Decodable, Decoder, SpecializedDecoder, UseSpecializedDecodable};
 use rustc_session::Session;
 use rustc_span::hygiene::ExpnDataDecodeMode;
@@ -69,17 +67,6 @@ crate struct CrateMetadata {
     /// universal (`for<'tcx>`), that is paired up with whichever `TyCtxt`
     /// is being used to decode those values.
     root: CrateRoot<'static>,
-    /// For each definition in this crate, we encode a key. When the
-    /// crate is loaded, we read all the keys and put them in this
-    /// hashmap, which gives the reverse mapping. This allows us to
-    /// quickly retrace a `DefPath`, which is needed for incremental
-    /// compilation support.
-    def_path_table: DefPathTable,
-    /// Trait impl data.
-    /// FIXME: Used only from queries and can use query cache,
-    /// so pre-decoding can probably be avoided.
-    trait_impls:
-        FxHashMap<(u32, DefIndex), Lazy<[(DefIndex, Option<ty::fast_reject::SimplifiedType>)]>>,
     /// Proc macro descriptions for this crate, if it's a proc macro crate.
     raw_proc_macros: Option<&'static [ProcMacro]>,
     /// Source maps for code from the crate.
@@ -91,6 +78,16 @@ crate struct CrateMetadata {
     /// Do not access the value directly, as it might not have been initialized yet.
     /// The field must always be initialized to `DepNodeIndex::INVALID`.
     dep_node_index: AtomicCell<DepNodeIndex>,
+    /// Def keys decoded so far. `def_path` and friends look up the same parents over and
+    /// over again, so they are decoded at most once.
+    def_key_cache: Lock<IndexVec<DefIndex, Option<DefKey>>>,
+    /// Same as `def_key_cache`, for the `DefPathHash` of each item.
+    def_path_hash_cache: Lock<IndexVec<DefIndex, Option<DefPathHash>>>,
+    /// Maps the `DefPathHash` of every item of this crate back to its `DefIndex`.
+    /// This is only needed by incremental compilation, and only for the crates
+    /// that are actually referenced from the query result cache, so it is built
+    /// on the first call to `def_path_hash_to_def_index()`.
+    def_path_hash_map: OnceCell<FxHashMap<DefPathHash, DefIndex>>,
 
     // --- Other significant crate properties ---
     /// ID of this crate, from the current compilation session's point of view.
@@ -376,6 +373,17 @@ where
     }
 }
 
+impl<'a, 'tcx, K, T> SpecializedDecoder<Lazy<SortedTable<K, T>, usize>> for DecodeContext<'a, 'tcx>
+where
+    K: FixedSizeEncoding + Ord,
+    Option<T>: FixedSizeEncoding,
+{
+    fn specialized_decode(&mut self) -> Result<Lazy<SortedTable<K, T>>, Self::Error> {
+        let len = self.read_usize()?;
+        self.read_lazy_with_meta(len)
+    }
+}
+
 impl<'a, 'tcx> SpecializedDecoder<DefId> for DecodeContext<'a, 'tcx> {
     #[inline]
     fn specialized_decode(&mut self) -> Result<DefId, Self::Error> {
@@ -763,7 +771,7 @@ impl<'a, 'tcx> CrateMetadataRef<'a> {
                     data.has_auto_impl,
                     data.is_marker,
                     data.specialization_kind,
-                    self.def_path_table.def_path_hash(item_id),
+                    self.def_path_hash(item_id),
                 )
             }
             EntryKind::TraitAlias => ty::TraitDef::new(
@@ -773,7 +781,7 @@ impl<'a, 'tcx> CrateMetadataRef<'a> {
                 false,
                 false,
                 ty::trait_def::TraitSpecializationKind::None,
-                self.def_path_table.def_path_hash(item_id),
+                self.def_path_hash(item_id),
             ),
             _ => bug!("def-index does not refer to trait or trait alias"),
         }
@@ -1299,13 +1307,13 @@ impl<'a, 'tcx> CrateMetadataRef<'a> {
         // Do a reverse lookup beforehand to avoid touching the crate_num
         // hash map in the loop below.
         let filter = match filter.map(|def_id| self.reverse_translate_def_id(def_id)) {
-            Some(Some(def_id)) => Some((def_id.krate.as_u32(), def_id.index)),
+            Some(Some(def_id)) => Some(trait_impls_key(def_id.krate.as_u32(), def_id.index)),
             Some(None) => return &[],
             None => None,
         };
 
         if let Some(filter) = filter {
-            if let Some(impls) = self.trait_impls.get(&filter) {
+            if let Some(impls) = self.root.impls.get(self, &filter) {
                 tcx.arena.alloc_from_iter(
                     impls.decode(self).map(|(idx, simplified_self_ty)| {
                         (self.local_def_id(idx), simplified_self_ty)
@@ -1315,7 +1323,7 @@ impl<'a, 'tcx> CrateMetadataRef<'a> {
                 &[]
             }
         } else {
-            tcx.arena.alloc_from_iter(self.trait_impls.values().flat_map(|impls| {
+            tcx.arena.alloc_from_iter(self.root.impls.values(self).flat_map(|impls| {
                 impls
                     .decode(self)
                     .map(|(idx, simplified_self_ty)| (self.local_def_id(idx), simplified_self_ty))
@@ -1465,11 +1473,18 @@ impl<'a, 'tcx> CrateMetadataRef<'a> {
 
     #[inline]
     fn def_key(&self, index: DefIndex) -> DefKey {
-        let mut key = self.def_path_table.def_key(index);
+        if let Some(&Some(key)) = self.def_key_cache.lock().get(index) {
+            return key;
+        }
+
+        let mut key = self.root.tables.def_keys.get(self, index).unwrap().decode(self);
         if self.is_proc_macro(index) {
             let name = self.raw_proc_macro(index).name();
             key.disambiguated_data.data = DefPathData::MacroNs(Symbol::intern(name));
         }
+        let mut cache = self.def_key_cache.lock();
+        cache.ensure_contains_elem(index, || None);
+        cache[index] = Some(key);
         key
     }
 
@@ -1668,7 +1683,6 @@ impl<'a, 'tcx> CrateMetadataRef<'a> {
 
 impl CrateMetadata {
     crate fn new(
-        sess: &Session,
         blob: MetadataBlob,
         root: CrateRoot<'static>,
         raw_proc_macros: Option<&'static [ProcMacro]>,
@@ -1679,26 +1693,19 @@ impl CrateMetadata {
         private_dep: bool,
         host_hash: Option<Svh>,
     ) -> CrateMetadata {
-        let def_path_table = record_time(&sess.perf_stats.decode_def_path_tables_time, || {
-            root.def_path_table.decode((&blob, sess))
-        });
-        let trait_impls = root
-            .impls
-            .decode((&blob, sess))
-            .map(|trait_impls| (trait_impls.trait_id, trait_impls.impls))
-            .collect();
         let alloc_decoding_state =
             AllocDecodingState::new(root.interpret_alloc_index.decode(&blob).collect());
         let dependencies = Lock::new(cnum_map.iter().cloned().collect());
         CrateMetadata {
             blob,
             root,
-            def_path_table,
-            trait_impls,
             raw_proc_macros,
             source_map_import_info: OnceCell::new(),
             alloc_decoding_state,
             dep_node_index: AtomicCell::new(DepNodeIndex::INVALID),
+            def_key_cache: Default::default(),
+            def_path_hash_cache: Default::default(),
+            def_path_hash_map: OnceCell::new(),
             cnum,
             cnum_map,
             dependencies,
@@ -1711,6 +1718,26 @@ impl CrateMetadata {
         }
     }
 
+    crate fn decode_def_path_hash(&self, index: DefIndex) -> DefPathHash {
+        self.root.tables.def_path_hashes.get(&self.blob, index).unwrap().decode(&self.blob)
+    }
+
+    /// Finds the item of this crate with the given `DefPathHash`. The reverse map is
+    /// built the first time this is called for the crate.
+    crate fn def_path_hash_to_def_index(&self, hash: DefPathHash) -> Option<DefIndex> {
+        let map = self.def_path_hash_map.get_or_init(|| {
+            // Decode directly instead of going through `def_path_hash()`, so that building
+            // the map does not fill the per-item cache with every hash of the crate.
+            (0..self.root.tables.def_keys.size())
+                .map(|index| {
+                    let index = DefIndex::from_usize(index);
+                    (self.decode_def_path_hash(index), index)
+                })
+                .collect()
+        });
+        map.get(&hash).copied()
+    }
+
     crate fn dependencies(&self) -> LockGuard<'_, Vec<CrateNum>> {
         self.dependencies.borrow()
     }
@@ -1802,7 +1829,19 @@ impl CrateMetadata {
 
     #[inline]
     fn def_path_hash(&self, index: DefIndex) -> DefPathHash {
-        self.def_path_table.def_path_hash(index)
+        if let Some(&Some(hash)) = self.def_path_hash_cache.lock().get(index) {
+            return hash;
+        }
+
+        let hash = self.decode_def_path_hash(index);
+        let mut cache = self.def_path_hash_cache.lock();
+        cache.ensure_contains_elem(index, || None);
+        cache[index] = Some(hash);
+        hash
+    }
+
+    fn num_def_ids(&self) -> usize {
+        self.root.tables.def_keys.size()
     }
 
     /// Get the `DepNodeIndex` corresponding this crate. The result of this
@@ -1,5 +1,5 @@
 use decoder::Metadata;
-use table::{Table, TableBuilder};
+use table::{SortedTable, Table, TableBuilder};
 
 use rustc_ast::ast::{self, MacroDef};
 use rustc_attr as attr;
@@ -8,6 +8,7 @@ use rustc_data_structures::sync::MetadataRef;
 use rustc_hir as hir;
 use rustc_hir::def::CtorKind;
 use rustc_hir::def_id::{DefId, DefIndex};
+use rustc_hir::definitions::{DefKey, DefPathHash};
 use rustc_hir::lang_items;
 use rustc_index::{bit_set::FiniteBitSet, vec::IndexVec};
 use rustc_middle::hir::exports::Export;
@@ -41,7 +42,7 @@ crate fn rustc_version() -> String {
 /// Metadata encoding version.
 /// N.B., increment this if you change the format of metadata such that
 /// the rustc version can't be found to compare with `rustc_version()`.
-const METADATA_VERSION: u8 = 5;
+const METADATA_VERSION: u8 = 6;
 
 /// Metadata header which includes `METADATA_VERSION`.
 ///
@@ -196,8 +197,7 @@ crate struct CrateRoot<'tcx> {
     diagnostic_items: Lazy<[(Symbol, DefIndex)]>,
     native_libraries: Lazy<[NativeLib]>,
     foreign_modules: Lazy<[ForeignModule]>,
-    def_path_table: Lazy<rustc_hir::definitions::DefPathTable>,
-    impls: Lazy<[TraitImpls]>,
+    impls: Lazy<SortedTable<u64, Lazy<[(DefIndex, Option<ty::fast_reject::SimplifiedType>)]>>>,
     interpret_alloc_index: Lazy<[u32]>,
 
     tables: LazyTables<'tcx>,
@@ -230,10 +230,11 @@ crate struct CrateDep {
     pub extra_filename: String,
 }
 
-#[derive(RustcEncodable, RustcDecodable)]
-crate struct TraitImpls {
-    trait_id: (u32, DefIndex),
-    impls: Lazy<[(DefIndex, Option<ty::fast_reject::SimplifiedType>)]>,
+/// The key of a trait in `CrateRoot::impls`: the `CrateNum` of the trait (as
+/// seen from the crate being encoded) in the high 32 bits and its `DefIndex`
+/// in the low 32 bits.
+fn trait_impls_key(krate: u32, index: DefIndex) -> u64 {
+    (krate as u64) << 32 | index.as_u32() as u64
 }
 
 /// Define `LazyTables` and `TableBuilders` at the same time.
@@ -260,6 +261,8 @@ macro_rules! define_tables {
 }
 
 define_tables! {
+    def_keys: Table<DefIndex, Lazy<DefKey>>,
+    def_path_hashes: Table<DefIndex, Lazy<DefPathHash>>,
     kind: Table<DefIndex, Lazy<EntryKind>>,
     visibility: Table<DefIndex, Lazy<ty::Visibility>>,
     span: Table<DefIndex, Lazy<Span>>,
@@ -3,6 +3,7 @@ use crate::rmeta::*;
 use log::debug;
 use rustc_index::vec::Idx;
 use rustc_serialize::{opaque::Encoder, Encodable};
+use std::cmp::Ordering;
 use std::convert::TryInto;
 use std::marker::PhantomData;
 use std::num::NonZeroUsize;
@@ -75,6 +76,20 @@ impl FixedSizeEncoding for u32 {
     }
 }
 
+impl FixedSizeEncoding for u64 {
+    fixed_size_encoding_byte_len_and_defaults!(8);
+
+    fn from_bytes(b: &[u8]) -> Self {
+        let mut bytes = [0; Self::BYTE_LEN];
+        bytes.copy_from_slice(&b[..Self::BYTE_LEN]);
+        Self::from_le_bytes(bytes)
+    }
+
+    fn write_to_bytes(self, b: &mut [u8]) {
+        b[..Self::BYTE_LEN].copy_from_slice(&self.to_le_bytes());
+    }
+}
+
 // NOTE(eddyb) there could be an impl for `usize`, which would enable a more
 // generic `Lazy<T>` impl, but in the general case we might not need / want to
 // fit every `usize` in `u32`.
@@ -201,4 +216,130 @@ where
         let bytes = &metadata.raw_bytes()[start..start + self.meta];
         <Option<T>>::maybe_read_from_bytes_at(bytes, i.index())?
     }
+
+    /// Size of the table in entries, including possible gaps.
+    pub(super) fn size(&self) -> usize {
+        self.meta / <Option<T>>::BYTE_LEN
+    }
 }
+
+/// Lookup table for keys that are too sparse for a `Table`, similar to a
+/// `Vec<(K, T)>` sorted by key. Every entry is encoded with the same size,
+/// so a key can be found by binary search directly in the metadata blob,
+/// without decoding the entries before it.
+/// A total of `len * (K::BYTE_LEN + <Option<T> as FixedSizeEncoding>::BYTE_LEN)`
+/// bytes are used for a table with `len` entries.
+pub(super) struct SortedTable<K: FixedSizeEncoding + Ord, T>
+where
+    Option<T>: FixedSizeEncoding,
+{
+    _marker: PhantomData<(fn(&K), T)>,
+    // NOTE(eddyb) this makes `SortedTable` not implement `Sized`, but no
+    // value of `SortedTable` is ever created (it's always behind `Lazy`).
+    _bytes: [u8],
+}
+
+/// Helper for constructing a sorted table's serialization (also see `SortedTable`).
+pub(super) struct SortedTableBuilder<K: FixedSizeEncoding + Ord, T>
+where
+    Option<T>: FixedSizeEncoding,
+{
+    entries: Vec<(K, T)>,
+}
+
+impl<K: FixedSizeEncoding + Ord, T> Default for SortedTableBuilder<K, T>
+where
+    Option<T>: FixedSizeEncoding,
+{
+    fn default() -> Self {
+        SortedTableBuilder { entries: vec![] }
+    }
+}
+
+impl<K: FixedSizeEncoding + Ord, T> SortedTableBuilder<K, T>
+where
+    Option<T>: FixedSizeEncoding,
+{
+    const ENTRY_LEN: usize = K::BYTE_LEN + <Option<T>>::BYTE_LEN;
+
+    pub(crate) fn insert(&mut self, key: K, value: T) {
+        self.entries.push((key, value));
+    }
+
+    pub(crate) fn encode(self, buf: &mut Encoder) -> Lazy<SortedTable<K, T>> {
+        let mut entries = self.entries;
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        debug_assert!(entries.windows(2).all(|w| w[0].0 != w[1].0), "duplicate key");
+
+        let mut bytes = vec![0; entries.len() * Self::ENTRY_LEN];
+        for (entry, (key, value)) in bytes.chunks_exact_mut(Self::ENTRY_LEN).zip(entries) {
+            key.write_to_bytes(entry);
+            Some(value).write_to_bytes(&mut entry[K::BYTE_LEN..]);
+        }
+
+        let pos = buf.position();
+        buf.emit_raw_bytes(&bytes);
+        Lazy::from_position_and_meta(NonZeroUsize::new(pos as usize).unwrap(), bytes.len())
+    }
+}
+
+impl<K: FixedSizeEncoding + Ord, T> LazyMeta for SortedTable<K, T>
+where
+    Option<T>: FixedSizeEncoding,
+{
+    type Meta = usize;
+
+    fn min_size(len: usize) -> usize {
+        len
+    }
+}
+
+impl<K: FixedSizeEncoding + Ord, T> Lazy<SortedTable<K, T>>
+where
+    Option<T>: FixedSizeEncoding,
+{
+    const ENTRY_LEN: usize = K::BYTE_LEN + <Option<T>>::BYTE_LEN;
+
+    fn raw_entries<'a, 'tcx, M: Metadata<'a, 'tcx>>(&self, metadata: M) -> &'a [u8] {
+        let start = self.position.get();
+        &metadata.raw_bytes()[start..start + self.meta]
+    }
+
+    /// Given the metadata, binary search for the value of `key` (if any).
+    #[inline(never)]
+    pub(super) fn get<'a, 'tcx, M: Metadata<'a, 'tcx>>(&self, metadata: M, key: &K) -> Option<T> {
+        debug!("SortedTable::lookup: len={:?}", self.meta);
+
+        let bytes = self.raw_entries(metadata);
+        let (mut lo, mut hi) = (0, bytes.len() / Self::ENTRY_LEN);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            let entry = &bytes[mid * Self::ENTRY_LEN..];
+            match K::from_bytes(entry).cmp(key) {
+                Ordering::Less => lo = mid + 1,
+                Ordering::Greater => hi = mid,
+                Ordering::Equal => return Self::entry_value(entry),
+            }
+        }
+        None
+    }
+
+    /// Given the metadata, decode the values of all entries, in key order.
+    pub(super) fn values<'a, 'tcx, M: Metadata<'a, 'tcx>>(
+        &self,
+        metadata: M,
+    ) -> impl Iterator<Item = T> + 'a
+    where
+        K: 'a,
+        T: 'a,
+    {
+        self.raw_entries(metadata).chunks_exact(Self::ENTRY_LEN).filter_map(Self::entry_value)
+    }
+
+    fn entry_value(entry: &[u8]) -> Option<T> {
+        <Option<T>>::from_bytes(&entry[K::BYTE_LEN..])
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,54 @@
+use super::*;
+
+use rustc_data_structures::owning_ref::OwningRef;
+
+/// Encodes a `SortedTable` mapping each key to a `Lazy` at the given position,
+/// and returns it along with the metadata blob it was written to.
+fn encode(entries: &[(u64, usize)]) -> (MetadataBlob, Lazy<SortedTable<u64, Lazy<u32>>>) {
+    let mut encoder = Encoder::new(Vec::new());
+    // Nothing is ever encoded at position 0, where the metadata header goes.
+    encoder.emit_raw_bytes(b"header");
+
+    let mut builder = SortedTableBuilder::default();
+    for &(key, position) in entries {
+        builder.insert(key, Lazy::from_position(NonZeroUsize::new(position).unwrap()));
+    }
+    let table = builder.encode(&mut encoder);
+
+    let bytes = rustc_erase_owner!(OwningRef::new(encoder.into_inner()).map_owner_box());
+    (MetadataBlob::new(bytes), table)
+}
+
+fn positions(values: impl Iterator<Item = Lazy<u32>>) -> Vec<usize> {
+    values.map(|lazy| lazy.position.get()).collect()
+}
+
+#[test]
+fn empty() {
+    let (blob, table) = encode(&[]);
+    assert!(table.get(&blob, &0).is_none());
+    assert!(table.get(&blob, &u64::MAX).is_none());
+    assert_eq!(table.values(&blob).count(), 0);
+}
+
+#[test]
+fn get() {
+    let (blob, table) = encode(&[(30, 3), (10, 1), (u64::MAX, 4), (20, 2)]);
+    for &(key, position) in &[(10, 1), (20, 2), (30, 3), (u64::MAX, 4)] {
+        assert_eq!(table.get(&blob, &key).map(|lazy| lazy.position.get()), Some(position));
+    }
+}
+
+#[test]
+fn get_missing_key() {
+    let (blob, table) = encode(&[(10, 1), (30, 3)]);
+    for key in &[0, 20, 40, u64::MAX] {
+        assert!(table.get(&blob, key).is_none());
+    }
+}
+
+#[test]
+fn values_in_key_order() {
+    let (blob, table) = encode(&[(30, 3), (10, 1), (20, 2)]);
+    assert_eq!(positions(table.values(&blob)), [1, 2, 3]);
+}
@@ -245,7 +245,7 @@ macro_rules! define_dep_nodes {
             fn extract_def_id(&self, tcx: TyCtxt<'tcx>) -> Option<DefId> {
                 if self.kind.can_reconstruct_query_key() {
                     let def_path_hash = DefPathHash(self.hash);
-                    tcx.def_path_hash_to_def_id.as_ref()?.get(&def_path_hash).cloned()
+                    tcx.def_path_hash_to_def_id(def_path_hash)
                 } else {
                     None
                 }
@@ -30,6 +30,10 @@ impl rustc_query_system::dep_graph::DepKind for DepKind {
         DepKind::is_eval_always(self)
     }
 
+    fn is_anon(&self) -> bool {
+        DepKind::is_anon(self)
+    }
+
     fn has_params(&self) -> bool {
         DepKind::has_params(self)
     }
@@ -100,8 +104,12 @@ impl<'tcx> DepContext for TyCtxt<'tcx> {
     }
     fn debug_dep_node(&self) -> bool {
         self.sess.opts.debugging_opts.incremental_info
+            || self.sess.opts.debugging_opts.incremental_explain
             || self.sess.opts.debugging_opts.query_dep_graph
     }
+    fn explain_red_nodes(&self) -> bool {
+        self.sess.opts.debugging_opts.incremental_explain
+    }
 
     fn try_force_from_dep_node(&self, dep_node: &DepNode) -> bool {
         // FIXME: This match is just a workaround for incremental bugs and should
@@ -3,7 +3,7 @@ use std::cmp;
 use crate::ich::StableHashingContext;
 use rustc_data_structures::fx::FxHashMap;
 use rustc_data_structures::stable_hasher::{HashStable, StableHasher};
-use rustc_errors::{DiagnosticBuilder, DiagnosticId};
+use rustc_errors::{DiagnosticBuilder, DiagnosticId, LintExpectationId};
 use rustc_hir::HirId;
 use rustc_session::lint::{builtin, Level, Lint, LintId};
 use rustc_session::{DiagnosticMessageId, Session};
@@ -121,6 +121,26 @@ impl LintLevelSets {
 pub struct LintLevelMap {
     pub sets: LintLevelSets,
     pub id_to_set: FxHashMap<HirId, u32>,
+    /// Every `#[expect]` attribute in the crate, in source order.
+    pub expectations: Vec<LintExpectation>,
+}
+
+/// A lint expectation created by an `#[expect(lint)]` attribute.
+#[derive(Clone, Copy, Debug, HashStable)]
+pub struct LintExpectation {
+    /// Identifies the expectation in the diagnostics that fulfill it.
+    pub id: LintExpectationId,
+    /// The ID lints emitted before the HIR is built use for the expectation, which is only
+    /// valid within the current session.
+    #[stable_hasher(ignore)]
+    pub unstable_id: LintExpectationId,
+    /// The span of the lint name inside the attribute.
+    pub span: Span,
+    pub lint_name: Symbol,
+    pub reason: Option<Symbol>,
+    /// The lint set the attribute belongs to, used to compute the level of
+    /// `unfulfilled_lint_expectations` at the attribute.
+    pub set: u32,
 }
 
 impl LintLevelMap {
@@ -144,7 +164,7 @@ impl LintLevelMap {
 impl<'a> HashStable<StableHashingContext<'a>> for LintLevelMap {
     #[inline]
     fn hash_stable(&self, hcx: &mut StableHashingContext<'a>, hasher: &mut StableHasher) {
-        let LintLevelMap { ref sets, ref id_to_set } = *self;
+        let LintLevelMap { ref sets, ref id_to_set, ref expectations } = *self;
 
         id_to_set.hash_stable(hcx, hasher);
 
@@ -170,6 +190,8 @@ impl<'a> HashStable<StableHashingContext<'a>> for LintLevelMap {
                     }
                 }
             }
+
+            expectations.hash_stable(hcx, hasher);
         })
     }
 }
@@ -211,6 +233,13 @@ pub fn struct_lint_level<'s, 'd>(
             (Level::Allow, _) => {
                 return;
             }
+            (Level::Expect(id), _) => {
+                // The lint is suppressed, but the expectation that asked for it is now fulfilled.
+                // This is recorded by a diagnostic that isn't shown, so that it is replayed along
+                // with the other diagnostics of the query emitting the lint.
+                sess.struct_expect("", id).emit();
+                return;
+            }
             (Level::Warn, Some(span)) => sess.struct_span_warn(span, ""),
             (Level::Warn, None) => sess.struct_warn(""),
             (Level::Deny | Level::Forbid, Some(span)) => sess.struct_span_err(span, ""),
@@ -255,7 +284,7 @@ pub fn struct_lint_level<'s, 'd>(
                     Level::Warn => "-W",
                     Level::Deny => "-D",
                     Level::Forbid => "-F",
-                    Level::Allow => panic!(),
+                    Level::Allow | Level::Expect(_) => panic!(),
                 };
                 let hyphen_case_lint_name = name.replace("_", "-");
                 if lint_flag_val.as_str() == name {
@@ -2415,6 +2415,7 @@ fn fn_can_unwind(
     panic_strategy: PanicStrategy,
     codegen_fn_attr_flags: CodegenFnAttrFlags,
     call_conv: Conv,
+    abi: SpecAbi,
 ) -> bool {
     if panic_strategy != PanicStrategy::Unwind {
         // In panic=abort mode we assume nothing can unwind anywhere, so
@@ -2432,6 +2433,10 @@ fn fn_can_unwind(
             // "rust-call" fn`) is explicitly allowed to unwind
             // (unless it has no-unwind attribute, handled above).
             true
+        } else if abi.is_unwind() {
+            // The `-unwind` ABIs (like `extern "C-unwind" fn`) exist precisely
+            // so that foreign exceptions and Rust panics may cross them.
+            true
         } else {
             // Anything else is either:
             //
@@ -2444,9 +2449,9 @@ fn fn_can_unwind(
             // rust-lang/rust#63909 and Rust RFC 2753.)
             //
             // Items defined in Rust with non-Rust ABIs (case 2) are also
-            // not supposed to unwind. Whether this should be enforced
-            // (versus stating it is UB) and *how* it would be enforced
-            // is currently under discussion; see rust-lang/rust#58794.
+            // not supposed to unwind. With `#![feature(c_unwind)]` this is
+            // enforced by aborting on unwind in MIR building; see
+            // rust-lang/rust#58794.
             //
             // In either case, we mark item as explicitly nounwind.
             false
@@ -2550,14 +2555,14 @@ where
             RustIntrinsic | PlatformIntrinsic | Rust | RustCall => Conv::Rust,
 
             // It's the ABI's job to select this, not ours.
-            System => bug!("system abi should be selected elsewhere"),
+            System | SystemUnwind => bug!("system abi should be selected elsewhere"),
             EfiApi => bug!("eficall abi should be selected elsewhere"),
 
-            Stdcall => Conv::X86Stdcall,
+            Stdcall | StdcallUnwind => Conv::X86Stdcall,
             Fastcall => Conv::X86Fastcall,
             Vectorcall => Conv::X86VectorCall,
-            Thiscall => Conv::X86ThisCall,
-            C => Conv::C,
+            Thiscall | ThiscallUnwind => Conv::X86ThisCall,
+            C | CUnwind => Conv::C,
             Unadjusted => Conv::C,
             Win64 => Conv::X86_64Win64,
             SysV64 => Conv::X86_64SysV,
@@ -2730,7 +2735,12 @@ where
             c_variadic: sig.c_variadic,
             fixed_count: inputs.len(),
             conv,
-            can_unwind: fn_can_unwind(cx.tcx().sess.panic_strategy(), codegen_fn_attr_flags, conv),
+            can_unwind: fn_can_unwind(
+                cx.tcx().sess.panic_strategy(),
+                codegen_fn_attr_flags,
+                conv,
+                sig.abi,
+            ),
         };
         fn_abi.adjust_for_abi(cx, sig.abi);
         fn_abi
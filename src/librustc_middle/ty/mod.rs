@@ -6,6 +6,9 @@ pub use self::IntVarValue::*;
 pub use self::Variance::*;
 
 use crate::hir::exports::ExportMap;
+use crate::hir::place::{
+    Place as HirPlace, PlaceBase as HirPlaceBase, ProjectionKind as HirProjectionKind,
+};
 use crate::ich::StableHashingContext;
 use crate::infer::canonical::Canonical;
 use crate::middle::cstore::CrateStoreDyn;
@@ -749,6 +752,69 @@ pub struct UpvarBorrow<'tcx> {
 pub type UpvarListMap = FxHashMap<DefId, FxIndexMap<hir::HirId, UpvarId>>;
 pub type UpvarCaptureMap<'tcx> = FxHashMap<UpvarId, UpvarCapture<'tcx>>;
 
+/// A place captured by a closure, along with how it is captured. With
+/// `capture_disjoint_fields`, closures capture places like `x.a` or `(*self).b`
+/// instead of whole variables.
+#[derive(PartialEq, Clone, Debug, RustcEncodable, RustcDecodable, HashStable)]
+pub struct CapturedPlace<'tcx> {
+    /// The captured place, whose base is always a `PlaceBase::Upvar`.
+    pub place: HirPlace<'tcx>,
+
+    /// Whether the place is captured by value or by reference.
+    pub capture_kind: UpvarCapture<'tcx>,
+
+    /// Whether the captured place may be mutated from within the closure: the root
+    /// variable has to be a `mut` binding, or the place has to go through a `&mut`,
+    /// and no shared reference may be dereferenced along the way.
+    pub mutability: hir::Mutability,
+}
+
+impl<'tcx> CapturedPlace<'tcx> {
+    /// Returns the `HirId` of the variable the captured place is rooted at.
+    pub fn get_root_variable(&self) -> hir::HirId {
+        match self.place.base {
+            HirPlaceBase::Upvar(upvar_id) => upvar_id.var_path.hir_id,
+            base => bug!("expected upvar, found={:?}", base),
+        }
+    }
+
+    /// Returns the captured place as it would be written in the source, e.g. `(*self).a`.
+    pub fn to_string(&self, tcx: TyCtxt<'tcx>) -> String {
+        let mut place = tcx.hir().name(self.get_root_variable()).to_string();
+        for (i, proj) in self.place.projections.iter().enumerate() {
+            match proj.kind {
+                HirProjectionKind::Deref => place = format!("*{}", place),
+                HirProjectionKind::Field(idx, variant) => {
+                    if place.starts_with('*') {
+                        place = format!("({})", place);
+                    }
+                    match self.place.ty_before_projection(i).kind {
+                        ty::Adt(def, _) => {
+                            let field = &def.variants[variant].fields[idx as usize];
+                            place = format!("{}.{}", place, field.ident);
+                        }
+                        _ => place = format!("{}.{}", place, idx),
+                    }
+                }
+                HirProjectionKind::Index | HirProjectionKind::Subslice => {
+                    bug!("unexpected projection {:?} in captured place", proj.kind)
+                }
+            }
+        }
+        place
+    }
+}
+
+/// The places captured for a single root variable. No place in the list is an
+/// ancestor of another one.
+pub type MinCaptureList<'tcx> = Vec<CapturedPlace<'tcx>>;
+
+/// Maps the root variables captured by a closure to the places captured from them.
+pub type RootVariableMinCaptureList<'tcx> = FxIndexMap<hir::HirId, MinCaptureList<'tcx>>;
+
+/// Maps closures to the places they capture, see `TypeckResults::closure_min_captures`.
+pub type MinCaptureInformationMap<'tcx> = FxHashMap<DefId, RootVariableMinCaptureList<'tcx>>;
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum IntVarValue {
     IntType(ast::IntTy),
@@ -664,19 +664,30 @@ pub trait PrettyPrinter<'tcx>:
                         p!(write("@{}", self.tcx().sess.source_map().span_to_string(span)));
                     }
 
-                    if substs.as_closure().is_valid() {
+                    // With `capture_disjoint_fields`, the types of the captures are only known
+                    // after upvar analysis, and each captured place gets its own type.
+                    let captures_known = substs.as_closure().is_valid()
+                        && matches!(substs.as_closure().tupled_upvars_ty().kind, ty::Tuple(_));
+                    if captures_known {
+                        let upvar_count = substs.as_closure().upvar_tys().count();
                         let upvar_tys = substs.as_closure().upvar_tys();
+                        let upvars = self.tcx().upvars_mentioned(did);
                         let mut sep = " ";
-                        for (&var_id, upvar_ty) in self
-                            .tcx()
-                            .upvars_mentioned(did)
-                            .as_ref()
-                            .iter()
-                            .flat_map(|v| v.keys())
-                            .zip(upvar_tys)
-                        {
-                            p!(write("{}{}:", sep, self.tcx().hir().name(var_id)), print(upvar_ty));
-                            sep = ", ";
+                        if upvars.map_or(0, |upvars| upvars.len()) == upvar_count {
+                            for (&var_id, upvar_ty) in
+                                upvars.iter().flat_map(|v| v.keys()).zip(upvar_tys)
+                            {
+                                p!(
+                                    write("{}{}:", sep, self.tcx().hir().name(var_id)),
+                                    print(upvar_ty)
+                                );
+                                sep = ", ";
+                            }
+                        } else {
+                            for (index, upvar_ty) in upvar_tys.enumerate() {
+                                p!(write("{}{}:", sep, index), print(upvar_ty));
+                                sep = ", ";
+                            }
                         }
                     }
                 } else {
@@ -4,6 +4,8 @@ use rustc_hir::def_id::{DefId, LOCAL_CRATE};
 use rustc_query_system::query::QueryCache;
 use rustc_query_system::query::QueryState;
 use rustc_query_system::query::{QueryAccessors, QueryContext};
+use rustc_serialize::json::as_json;
+use rustc_session::config::QueryStatsFormat;
 
 use std::any::type_name;
 use std::mem;
@@ -64,6 +66,57 @@ fn stats<CTX: QueryContext, C: QueryCache>(
 }
 
 pub fn print_stats(tcx: TyCtxt<'_>) {
+    match tcx.sess.opts.debugging_opts.query_stats {
+        Some(QueryStatsFormat::Json) => print_stats_json(tcx),
+        _ => print_stats_text(tcx),
+    }
+}
+
+/// The entry for a single query in the output of `-Z query-stats=json`.
+#[derive(RustcEncodable)]
+struct QueryStatsJson {
+    name: &'static str,
+    invocations: u64,
+    cache_hits: u64,
+    ensures: u64,
+    total_time_ns: u64,
+    self_time_ns: u64,
+    result_count: usize,
+}
+
+#[derive(RustcEncodable)]
+struct QueryStatsReportJson {
+    crate_name: String,
+    queries: Vec<QueryStatsJson>,
+}
+
+/// Prints a single JSON object with an entry for every query that was
+/// invoked. Invocation counts and timings are collected by the self-profiler,
+/// result counts are taken from the query caches.
+fn print_stats_json(tcx: TyCtxt<'_>) {
+    let counters = tcx.sess.prof.query_stats().unwrap_or_default();
+
+    let queries = query_stats(tcx)
+        .into_iter()
+        .filter_map(|q| {
+            let counters = counters.get(q.name)?;
+            Some(QueryStatsJson {
+                name: q.name,
+                invocations: counters.invocations,
+                cache_hits: counters.cache_hits,
+                ensures: counters.ensures,
+                total_time_ns: counters.total_time.as_nanos() as u64,
+                self_time_ns: counters.self_time.as_nanos() as u64,
+                result_count: q.entry_count,
+            })
+        })
+        .collect();
+
+    let report = QueryStatsReportJson { crate_name: tcx.crate_name.to_string(), queries };
+    println!("{}", as_json(&report));
+}
+
+fn print_stats_text(tcx: TyCtxt<'_>) {
     let queries = query_stats(tcx);
 
     if cfg!(debug_assertions) {
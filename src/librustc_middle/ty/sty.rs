@@ -368,7 +368,17 @@ impl<'tcx> ClosureSubsts<'tcx> {
 
     #[inline]
     pub fn upvar_tys(self) -> impl Iterator<Item = Ty<'tcx>> + 'tcx {
-        self.tupled_upvars_ty().tuple_fields()
+        // With `capture_disjoint_fields`, the tuple is a single inference variable
+        // until upvar analysis has determined which places the closure captures.
+        let tupled_upvars_ty = self.tupled_upvars_ty();
+        match tupled_upvars_ty.kind {
+            Tuple(..) => Some(tupled_upvars_ty.tuple_fields()),
+            Error(_) => None,
+            Infer(_) => bug!("upvar_tys called before capture types are inferred"),
+            ref ty => bug!("Unexpected representation of upvar types tuple {:?}", ty),
+        }
+        .into_iter()
+        .flatten()
     }
 
     /// Returns the tuple type representing the upvars for this closure.
@@ -618,7 +628,15 @@ impl<'tcx> UpvarSubsts<'tcx> {
             UpvarSubsts::Closure(substs) => substs.as_closure().split().tupled_upvars_ty,
             UpvarSubsts::Generator(substs) => substs.as_generator().split().tupled_upvars_ty,
         };
-        tupled_upvars_ty.expect_ty().tuple_fields()
+        let tupled_upvars_ty = tupled_upvars_ty.expect_ty();
+        match tupled_upvars_ty.kind {
+            Tuple(..) => Some(tupled_upvars_ty.tuple_fields()),
+            Error(_) => None,
+            Infer(_) => bug!("upvar_tys called before capture types are inferred"),
+            ref ty => bug!("Unexpected representation of upvar types tuple {:?}", ty),
+        }
+        .into_iter()
+        .flatten()
     }
 }
 
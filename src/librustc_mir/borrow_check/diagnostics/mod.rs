@@ -386,6 +386,16 @@ impl<'cx, 'tcx> MirBorrowckCtxt<'cx, 'tcx> {
                     // `tcx.upvars_mentioned(def_id)` returns an `Option`, which is `None` in case
                     // the closure comes from another crate. But in that case we wouldn't
                     // be borrowck'ing it, so we can just unwrap:
+                    let typeck_results = self.infcx.tcx.typeck(def_id.expect_local());
+                    // The fields of a closure that captures places rather than whole
+                    // variables are the captured places.
+                    if typeck_results.closure_min_captures.contains_key(&def_id) {
+                        return typeck_results
+                            .closure_min_captures_flattened(def_id)
+                            .nth(field.index())
+                            .unwrap()
+                            .to_string(self.infcx.tcx);
+                    }
                     let (&var_id, _) = self
                         .infcx
                         .tcx
@@ -941,7 +951,20 @@ impl<'cx, 'tcx> MirBorrowckCtxt<'cx, 'tcx> {
         let expr = &self.infcx.tcx.hir().expect_expr(hir_id).kind;
         debug!("closure_span: hir_id={:?} expr={:?}", hir_id, expr);
         if let hir::ExprKind::Closure(.., body_id, args_span, _) = expr {
-            for (upvar, place) in self.infcx.tcx.upvars_mentioned(def_id)?.values().zip(places) {
+            let upvars = self.infcx.tcx.upvars_mentioned(def_id)?;
+            let typeck_results = self.infcx.tcx.typeck(def_id.expect_local());
+            // Closures that capture places rather than whole variables have an operand for each
+            // captured place, which can share a root variable with other operands.
+            let captures_places = typeck_results.closure_min_captures.contains_key(&def_id);
+            let upvar_spans: Vec<Span> = if captures_places {
+                typeck_results
+                    .closure_min_captures_flattened(def_id)
+                    .map(|captured| upvars[&captured.get_root_variable()].span)
+                    .collect()
+            } else {
+                upvars.values().map(|upvar| upvar.span).collect()
+            };
+            for (upvar_span, place) in upvar_spans.into_iter().zip(places) {
                 match place {
                     Operand::Copy(place) | Operand::Move(place)
                         if target_place == place.as_ref() =>
@@ -949,7 +972,7 @@ impl<'cx, 'tcx> MirBorrowckCtxt<'cx, 'tcx> {
                         debug!("closure_span: found captured local {:?}", place);
                         let body = self.infcx.tcx.hir().body(*body_id);
                         let generator_kind = body.generator_kind();
-                        return Some((*args_span, generator_kind, upvar.span));
+                        return Some((*args_span, generator_kind, upvar_span));
                     }
                     _ => {}
                 }
@@ -154,31 +154,46 @@ fn do_mir_borrowck<'a, 'tcx>(
     if let Some(ErrorReported) = tables.tainted_by_errors {
         infcx.set_tainted_by_errors();
     }
-    let upvars: Vec<_> = tables
-        .closure_captures
-        .get(&def.did.to_def_id())
-        .into_iter()
-        .flat_map(|v| v.values())
-        .map(|upvar_id| {
-            let var_hir_id = upvar_id.var_path.hir_id;
-            let capture = tables.upvar_capture(*upvar_id);
-            let by_ref = match capture {
-                ty::UpvarCapture::ByValue => false,
-                ty::UpvarCapture::ByRef(..) => true,
-            };
-            let mut upvar = Upvar {
-                name: tcx.hir().name(var_hir_id),
-                var_hir_id,
-                by_ref,
-                mutability: Mutability::Not,
-            };
-            let bm = *tables.pat_binding_modes().get(var_hir_id).expect("missing binding mode");
-            if bm == ty::BindByValue(hir::Mutability::Mut) {
-                upvar.mutability = Mutability::Mut;
-            }
-            upvar
-        })
-        .collect();
+    let upvars: Vec<_> = if tables.closure_min_captures.contains_key(&def.did.to_def_id()) {
+        tables
+            .closure_min_captures_flattened(def.did.to_def_id())
+            .map(|captured| Upvar {
+                name: Symbol::intern(&captured.to_string(tcx)),
+                var_hir_id: captured.get_root_variable(),
+                by_ref: match captured.capture_kind {
+                    ty::UpvarCapture::ByValue => false,
+                    ty::UpvarCapture::ByRef(..) => true,
+                },
+                mutability: captured.mutability,
+            })
+            .collect()
+    } else {
+        tables
+            .closure_captures
+            .get(&def.did.to_def_id())
+            .into_iter()
+            .flat_map(|v| v.values())
+            .map(|upvar_id| {
+                let var_hir_id = upvar_id.var_path.hir_id;
+                let capture = tables.upvar_capture(*upvar_id);
+                let by_ref = match capture {
+                    ty::UpvarCapture::ByValue => false,
+                    ty::UpvarCapture::ByRef(..) => true,
+                };
+                let mut upvar = Upvar {
+                    name: tcx.hir().name(var_hir_id),
+                    var_hir_id,
+                    by_ref,
+                    mutability: Mutability::Not,
+                };
+                let bm = *tables.pat_binding_modes().get(var_hir_id).expect("missing binding mode");
+                if bm == ty::BindByValue(hir::Mutability::Mut) {
+                    upvar.mutability = Mutability::Mut;
+                }
+                upvar
+            })
+            .collect()
+    };
 
     // Replace all regions with fresh inference variables. This
     // requires first making our own copy of the MIR. This copy will
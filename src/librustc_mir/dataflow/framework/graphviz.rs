@@ -1,17 +1,35 @@
 //! A helpful diagram for debugging dataflow problems.
 
 use std::cell::RefCell;
-use std::{io, ops, str};
+use std::{io, ops};
 
 use rustc_graphviz as dot;
 use rustc_hir::def_id::DefId;
-use rustc_index::bit_set::{BitSet, HybridBitSet};
-use rustc_index::vec::{Idx, IndexVec};
 use rustc_middle::mir::{self, BasicBlock, Body, Location};
 
-use super::{Analysis, Direction, GenKillSet, Results, ResultsRefCursor};
+use super::fmt::{DebugDiffWithAdapter, DebugWithAdapter, DebugWithContext};
+use super::{Analysis, Direction, Results, ResultsRefCursor};
 use crate::util::graphviz_safe_def_name;
 
+/// What to print under the `STATE` header for each statement.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputStyle {
+    /// A single column with the change caused by each statement.
+    AfterOnly,
+    /// Two columns, one with the change caused by the "before" effect of each statement and one
+    /// with the change caused by its primary effect.
+    BeforeAndAfter,
+}
+
+impl OutputStyle {
+    fn num_state_columns(&self) -> usize {
+        match self {
+            Self::AfterOnly => 1,
+            Self::BeforeAndAfter => 2,
+        }
+    }
+}
+
 pub struct Formatter<'a, 'tcx, A>
 where
     A: Analysis<'tcx>,
@@ -31,12 +49,12 @@ where
         body: &'a Body<'tcx>,
         def_id: DefId,
         results: &'a Results<'tcx, A>,
-        state_formatter: &'a mut dyn StateFormatter<'tcx, A>,
+        style: OutputStyle,
     ) -> Self {
         let block_formatter = BlockFormatter {
             bg: Background::Light,
             results: ResultsRefCursor::new(body, results),
-            state_formatter,
+            style,
         };
 
         Formatter { body, def_id, block_formatter: RefCell::new(block_formatter) }
@@ -62,6 +80,7 @@ fn dataflow_successors(body: &Body<'tcx>, bb: BasicBlock) -> Vec<CfgEdge> {
 impl<A> dot::Labeller<'_> for Formatter<'a, 'tcx, A>
 where
     A: Analysis<'tcx>,
+    A::Domain: DebugWithContext<A>,
 {
     type Node = BasicBlock;
     type Edge = CfgEdge;
@@ -126,19 +145,16 @@ where
 {
     results: ResultsRefCursor<'a, 'a, 'tcx, A>,
     bg: Background,
-    state_formatter: &'a mut dyn StateFormatter<'tcx, A>,
+    style: OutputStyle,
 }
 
 impl<A> BlockFormatter<'a, 'tcx, A>
 where
     A: Analysis<'tcx>,
+    A::Domain: DebugWithContext<A>,
 {
     const HEADER_COLOR: &'static str = "#a0a0a0";
 
-    fn num_state_columns(&self) -> usize {
-        std::cmp::max(1, self.state_formatter.column_names().len())
-    }
-
     fn toggle_background(&mut self) -> Background {
         let bg = self.bg;
         self.bg = !bg;
@@ -187,16 +203,17 @@ where
         write!(w, r#"<table{fmt}>"#, fmt = table_fmt)?;
 
         // A + B: Block header
-        if self.state_formatter.column_names().is_empty() {
-            self.write_block_header_simple(w, block)?;
-        } else {
-            self.write_block_header_with_state_columns(w, block)?;
+        match self.style {
+            OutputStyle::AfterOnly => self.write_block_header_simple(w, block)?,
+            OutputStyle::BeforeAndAfter => {
+                self.write_block_header_with_state_columns(w, block, &["BEFORE", "AFTER"])?
+            }
         }
 
         // C: State at start of block
         self.bg = Background::Light;
         self.results.seek_to_block_start(block);
-        let block_entry_state = self.results.get().clone();
+        let block_start_state = self.results.get().clone();
 
         self.write_row_with_full_state(w, "", "(on start)")?;
 
@@ -218,9 +235,9 @@ where
         // F: State at end of block
 
         // Write the full dataflow state immediately after the terminator if it differs from the
-        // state at block entry.
+        // state at block start.
         self.results.seek_to_block_end(block);
-        if self.results.get() != &block_entry_state || A::Direction::is_backward() {
+        if self.results.get() != &block_start_state || A::Direction::is_backward() {
             let after_terminator_name = match terminator.kind {
                 mir::TerminatorKind::Call { destination: Some(_), .. } => "(on unwind)",
                 _ => "(on end)",
@@ -230,7 +247,7 @@ where
         }
 
         // Write any changes caused by terminator-specific effects
-        let num_state_columns = self.num_state_columns();
+        let num_state_columns = self.style.num_state_columns();
         match terminator.kind {
             mir::TerminatorKind::Call {
                 destination: Some((return_place, _)),
@@ -322,6 +339,7 @@ where
         &mut self,
         w: &mut impl io::Write,
         block: BasicBlock,
+        state_column_names: &[&str],
     ) -> io::Result<()> {
         //   +------------------------------------+-------------+
         // A |                bb4                 |    STATE    |
@@ -330,8 +348,6 @@ where
         //   +-+----------------------------------+------+------+
         //   | |              ...                 |      |      |
 
-        let state_column_names = self.state_formatter.column_names();
-
         // A
         write!(
             w,
@@ -399,12 +415,13 @@ where
 
             write!(
                 w,
-                r#"<td colspan="{colspan}" {fmt} align="left">{{"#,
-                colspan = this.num_state_columns(),
+                r#"<td colspan="{colspan}" {fmt} balign="left" align="left">"#,
+                colspan = this.style.num_state_columns(),
                 fmt = fmt,
             )?;
-            pretty_print_state_elems(w, analysis, state.iter(), ", ", LIMIT_30_ALIGN_1)?;
-            write!(w, "}}</td>")
+            let state = format!("{:?}", DebugWithAdapter { this: state, ctxt: analysis });
+            write_html_lines(w, &state)?;
+            write!(w, "</td>")
         })
     }
 
@@ -416,300 +433,82 @@ where
         location: Location,
     ) -> io::Result<()> {
         self.write_row(w, i, mir, |this, w, fmt| {
-            this.state_formatter.write_state_for_location(w, fmt, &mut this.results, location)
-        })
-    }
-}
-
-/// Controls what gets printed under the `STATE` header.
-pub trait StateFormatter<'tcx, A>
-where
-    A: Analysis<'tcx>,
-{
-    /// The columns that will get printed under `STATE`.
-    fn column_names(&self) -> &[&str];
-
-    fn write_state_for_location(
-        &mut self,
-        w: &mut dyn io::Write,
-        fmt: &str,
-        results: &mut ResultsRefCursor<'_, '_, 'tcx, A>,
-        location: Location,
-    ) -> io::Result<()>;
-}
-
-/// Prints a single column containing the state vector immediately *after* each statement.
-pub struct SimpleDiff<'a, 'tcx, A>
-where
-    A: Analysis<'tcx>,
-{
-    prev_state: ResultsRefCursor<'a, 'a, 'tcx, A>,
-}
-
-impl<A> SimpleDiff<'a, 'tcx, A>
-where
-    A: Analysis<'tcx>,
-{
-    pub fn new(body: &'a Body<'tcx>, results: &'a Results<'tcx, A>) -> Self {
-        SimpleDiff { prev_state: ResultsRefCursor::new(body, results) }
-    }
-}
+            this.seek_to_state_preceding(location);
+            let mut prev_state = this.results.get().clone();
+
+            if this.style == OutputStyle::BeforeAndAfter {
+                write!(w, r#"<td {fmt} balign="left" align="left">"#, fmt = fmt)?;
+                this.results.seek_before_primary_effect(location);
+                let curr_state = this.results.get();
+                write_diff(w, this.results.analysis(), &prev_state, curr_state)?;
+                prev_state.clone_from(curr_state);
+                write!(w, "</td>")?;
+            }
 
-impl<A> StateFormatter<'tcx, A> for SimpleDiff<'_, 'tcx, A>
-where
-    A: Analysis<'tcx>,
-{
-    fn column_names(&self) -> &[&str] {
-        &[]
+            write!(w, r#"<td {fmt} balign="left" align="left">"#, fmt = fmt)?;
+            this.results.seek_after_primary_effect(location);
+            write_diff(w, this.results.analysis(), &prev_state, this.results.get())?;
+            write!(w, "</td>")
+        })
     }
 
-    fn write_state_for_location(
-        &mut self,
-        mut w: &mut dyn io::Write,
-        fmt: &str,
-        results: &mut ResultsRefCursor<'_, '_, 'tcx, A>,
-        location: Location,
-    ) -> io::Result<()> {
+    /// Moves the cursor to the state right before the effects at `location` are applied, in the
+    /// order the analysis visits locations.
+    fn seek_to_state_preceding(&mut self, location: Location) {
         if A::Direction::is_forward() {
             if location.statement_index == 0 {
-                self.prev_state.seek_to_block_start(location.block);
+                self.results.seek_to_block_start(location.block);
             } else {
-                self.prev_state.seek_after_primary_effect(Location {
+                self.results.seek_after_primary_effect(Location {
                     statement_index: location.statement_index - 1,
                     ..location
                 });
             }
+        } else if location == self.results.body().terminator_loc(location.block) {
+            self.results.seek_to_block_end(location.block);
         } else {
-            if location == results.body().terminator_loc(location.block) {
-                self.prev_state.seek_to_block_end(location.block);
-            } else {
-                self.prev_state.seek_after_primary_effect(location.successor_within_block());
-            }
-        }
-
-        write!(w, r#"<td {fmt} balign="left" align="left">"#, fmt = fmt)?;
-        results.seek_after_primary_effect(location);
-        let curr_state = results.get();
-        write_diff(&mut w, results.analysis(), self.prev_state.get(), curr_state)?;
-        write!(w, "</td>")
-    }
-}
-
-/// Prints two state columns, one containing only the "before" effect of each statement and one
-/// containing the full effect.
-pub struct TwoPhaseDiff<T: Idx> {
-    prev_state: BitSet<T>,
-    prev_loc: Location,
-}
-
-impl<T: Idx> TwoPhaseDiff<T> {
-    pub fn new(bits_per_block: usize) -> Self {
-        TwoPhaseDiff { prev_state: BitSet::new_empty(bits_per_block), prev_loc: Location::START }
-    }
-}
-
-impl<A> StateFormatter<'tcx, A> for TwoPhaseDiff<A::Idx>
-where
-    A: Analysis<'tcx>,
-{
-    fn column_names(&self) -> &[&str] {
-        &["BEFORE", " AFTER"]
-    }
-
-    fn write_state_for_location(
-        &mut self,
-        mut w: &mut dyn io::Write,
-        fmt: &str,
-        results: &mut ResultsRefCursor<'_, '_, 'tcx, A>,
-        location: Location,
-    ) -> io::Result<()> {
-        if location.statement_index == 0 {
-            results.seek_to_block_entry(location.block);
-            self.prev_state.overwrite(results.get());
-        } else {
-            // Ensure that we are visiting statements in order, so `prev_state` is correct.
-            assert_eq!(self.prev_loc.successor_within_block(), location);
-        }
-
-        self.prev_loc = location;
-
-        // Before
-
-        write!(w, r#"<td {fmt} align="left">"#, fmt = fmt)?;
-        results.seek_before_primary_effect(location);
-        let curr_state = results.get();
-        write_diff(&mut w, results.analysis(), &self.prev_state, curr_state)?;
-        self.prev_state.overwrite(curr_state);
-        write!(w, "</td>")?;
-
-        // After
-
-        write!(w, r#"<td {fmt} align="left">"#, fmt = fmt)?;
-        results.seek_after_primary_effect(location);
-        let curr_state = results.get();
-        write_diff(&mut w, results.analysis(), &self.prev_state, curr_state)?;
-        self.prev_state.overwrite(curr_state);
-        write!(w, "</td>")
-    }
-}
-
-/// Prints the gen/kill set for the entire block.
-pub struct BlockTransferFunc<'a, 'tcx, T: Idx> {
-    body: &'a mir::Body<'tcx>,
-    trans_for_block: IndexVec<BasicBlock, GenKillSet<T>>,
-}
-
-impl<T: Idx> BlockTransferFunc<'mir, 'tcx, T> {
-    pub fn new(
-        body: &'mir mir::Body<'tcx>,
-        trans_for_block: IndexVec<BasicBlock, GenKillSet<T>>,
-    ) -> Self {
-        BlockTransferFunc { body, trans_for_block }
-    }
-}
-
-impl<A> StateFormatter<'tcx, A> for BlockTransferFunc<'mir, 'tcx, A::Idx>
-where
-    A: Analysis<'tcx>,
-{
-    fn column_names(&self) -> &[&str] {
-        &["GEN", "KILL"]
-    }
-
-    fn write_state_for_location(
-        &mut self,
-        mut w: &mut dyn io::Write,
-        fmt: &str,
-        results: &mut ResultsRefCursor<'_, '_, 'tcx, A>,
-        location: Location,
-    ) -> io::Result<()> {
-        // Only print a single row.
-        if location.statement_index != 0 {
-            return Ok(());
-        }
-
-        let block_trans = &self.trans_for_block[location.block];
-        let rowspan = self.body.basic_blocks()[location.block].statements.len();
-
-        for set in &[&block_trans.gen, &block_trans.kill] {
-            write!(
-                w,
-                r#"<td {fmt} rowspan="{rowspan}" balign="left" align="left">"#,
-                fmt = fmt,
-                rowspan = rowspan
-            )?;
-
-            pretty_print_state_elems(&mut w, results.analysis(), set.iter(), BR_LEFT, None)?;
-            write!(w, "</td>")?;
+            self.results.seek_after_primary_effect(location.successor_within_block());
         }
-
-        Ok(())
     }
 }
 
-/// Writes two lines, one containing the added bits and one the removed bits.
-fn write_diff<A: Analysis<'tcx>>(
+/// Writes the difference between two states, using the format described in
+/// `DebugWithContext::fmt_diff_with`.
+fn write_diff<A>(
     w: &mut impl io::Write,
     analysis: &A,
-    from: &BitSet<A::Idx>,
-    to: &BitSet<A::Idx>,
-) -> io::Result<()> {
-    assert_eq!(from.domain_size(), to.domain_size());
-    let len = from.domain_size();
-
-    let mut set = HybridBitSet::new_empty(len);
-    let mut clear = HybridBitSet::new_empty(len);
-
-    // FIXME: Implement a lazy iterator over the symmetric difference of two bitsets.
-    for i in (0..len).map(A::Idx::new) {
-        match (from.contains(i), to.contains(i)) {
-            (false, true) => set.insert(i),
-            (true, false) => clear.insert(i),
-            _ => continue,
-        };
-    }
-
-    if !set.is_empty() {
-        write!(w, r#"<font color="darkgreen">+"#)?;
-        pretty_print_state_elems(w, analysis, set.iter(), ", ", LIMIT_30_ALIGN_1)?;
-        write!(w, r#"</font>"#)?;
-    }
-
-    if !set.is_empty() && !clear.is_empty() {
-        write!(w, "{}", BR_LEFT)?;
-    }
-
-    if !clear.is_empty() {
-        write!(w, r#"<font color="red">-"#)?;
-        pretty_print_state_elems(w, analysis, clear.iter(), ", ", LIMIT_30_ALIGN_1)?;
-        write!(w, r#"</font>"#)?;
-    }
-
-    Ok(())
-}
-
-const BR_LEFT: &str = r#"<br align="left"/>"#;
-const BR_LEFT_SPACE: &str = r#"<br align="left"/> "#;
-
-/// Line break policy that breaks at 40 characters and starts the next line with a single space.
-const LIMIT_30_ALIGN_1: Option<LineBreak> = Some(LineBreak { sequence: BR_LEFT_SPACE, limit: 30 });
-
-struct LineBreak {
-    sequence: &'static str,
-    limit: usize,
-}
-
-/// Formats each `elem` using the pretty printer provided by `analysis` into a list with the given
-/// separator (`sep`).
-///
-/// Optionally, it will break lines using the given character sequence (usually `<br/>`) and
-/// character limit.
-fn pretty_print_state_elems<A>(
-    w: &mut impl io::Write,
-    analysis: &A,
-    elems: impl Iterator<Item = A::Idx>,
-    sep: &str,
-    line_break: Option<LineBreak>,
-) -> io::Result<bool>
+    old: &A::Domain,
+    new: &A::Domain,
+) -> io::Result<()>
 where
     A: Analysis<'tcx>,
+    A::Domain: DebugWithContext<A>,
 {
-    let sep_width = sep.chars().count();
-
-    let mut buf = Vec::new();
+    let diff = format!("{:?}", DebugDiffWithAdapter { new, old, ctxt: analysis });
+    write_html_lines(w, &diff)
+}
 
-    let mut first = true;
-    let mut curr_line_width = 0;
-    let mut line_break_inserted = false;
+const BR_LEFT: &str = r#"<br align="left"/>"#;
 
-    for idx in elems {
-        buf.clear();
-        analysis.pretty_print_idx(&mut buf, idx)?;
-        let idx_str =
-            str::from_utf8(&buf).expect("Output of `pretty_print_idx` must be valid UTF-8");
-        let escaped = dot::escape_html(idx_str);
-        let escaped_width = escaped.chars().count();
+/// Writes each line of `text`, escaped for HTML, on its own left-aligned line. Lines starting with
+/// `+` are colored green and lines starting with `-` are colored red.
+fn write_html_lines(w: &mut impl io::Write, text: &str) -> io::Result<()> {
+    for (i, line) in text.lines().enumerate() {
+        if i > 0 {
+            write!(w, "{}", BR_LEFT)?;
+        }
 
-        if first {
-            first = false;
+        let escaped = dot::escape_html(line);
+        if line.starts_with('+') {
+            write!(w, r#"<font color="darkgreen">{}</font>"#, escaped)?;
+        } else if line.starts_with('-') {
+            write!(w, r#"<font color="red">{}</font>"#, escaped)?;
         } else {
-            write!(w, "{}", sep)?;
-            curr_line_width += sep_width;
-
-            if let Some(line_break) = &line_break {
-                if curr_line_width + sep_width + escaped_width > line_break.limit {
-                    write!(w, "{}", line_break.sequence)?;
-                    line_break_inserted = true;
-                    curr_line_width = 0;
-                }
-            }
+            write!(w, "{}", escaped)?;
         }
-
-        write!(w, "{}", escaped)?;
-        curr_line_width += escaped_width;
     }
 
-    Ok(line_break_inserted)
+    Ok(())
 }
 
 /// The background color used for zebra-striping the table.
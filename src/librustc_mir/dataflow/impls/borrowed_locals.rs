@@ -1,6 +1,7 @@
 pub use super::*;
 
-use crate::dataflow::{AnalysisDomain, GenKill, GenKillAnalysis};
+use crate::dataflow::{Analysis, AnalysisDomain, GenKill, GenKillAnalysis};
+use rustc_hir::def_id::DefId;
 use rustc_middle::mir::visit::Visitor;
 use rustc_middle::mir::*;
 use rustc_middle::ty::{ParamEnv, TyCtxt};
@@ -78,19 +79,46 @@ impl<K> MaybeBorrowedLocals<K> {
     }
 }
 
+/// Returns the locals that have their address taken anywhere in `body`, according to the
+/// `MaybeBorrowedLocals` analysis.
+///
+/// This includes locals that are dropped, since a `Drop` impl gets a `&mut` to the local.
+pub fn ever_borrowed_locals(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    def_id: DefId,
+) -> BitSet<Local> {
+    let mut borrowed = MaybeBorrowedLocals::all_borrows()
+        .into_engine(tcx, body, def_id)
+        .iterate_to_fixpoint()
+        .into_results_cursor(body);
+
+    // A borrow ends at the `StorageDead` of the borrowed local, so looking at the state at block
+    // ends isn't enough: every location has to be visited.
+    let mut ever_borrowed = BitSet::new_empty(body.local_decls.len());
+    for (block, data) in traversal::preorder(body) {
+        for statement_index in 0..=data.statements.len() {
+            borrowed.seek_after_primary_effect(Location { block, statement_index });
+            ever_borrowed.union(borrowed.get());
+        }
+    }
+    ever_borrowed
+}
+
 impl<K> AnalysisDomain<'tcx> for MaybeBorrowedLocals<K>
 where
     K: BorrowAnalysisKind<'tcx>,
 {
-    type Idx = Local;
+    type Domain = BitSet<Local>;
 
     const NAME: &'static str = K::ANALYSIS_NAME;
 
-    fn bits_per_block(&self, body: &mir::Body<'tcx>) -> usize {
-        body.local_decls().len()
+    fn bottom_value(&self, body: &mir::Body<'tcx>) -> Self::Domain {
+        // bottom = unborrowed
+        BitSet::new_empty(body.local_decls().len())
     }
 
-    fn initialize_start_block(&self, _: &mir::Body<'tcx>, _: &mut BitSet<Self::Idx>) {
+    fn initialize_start_block(&self, _: &mir::Body<'tcx>, _: &mut Self::Domain) {
         // No locals are aliased on function entry
     }
 }
@@ -99,6 +127,8 @@ impl<K> GenKillAnalysis<'tcx> for MaybeBorrowedLocals<K>
 where
     K: BorrowAnalysisKind<'tcx>,
 {
+    type Idx = Local;
+
     fn statement_effect(
         &self,
         trans: &mut impl GenKill<Self::Idx>,
@@ -128,11 +158,6 @@ where
     }
 }
 
-impl<K> BottomValue for MaybeBorrowedLocals<K> {
-    // bottom = unborrowed
-    const BOTTOM_VALUE: bool = false;
-}
-
 /// A `Visitor` that defines the transfer function for `MaybeBorrowedLocals`.
 struct TransferFunction<'a, T, K> {
     trans: &'a mut T,
@@ -0,0 +1,727 @@
+//! A constant propagation analysis on the integers, `bool`s and `char`s stored in a MIR body.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::Range;
+
+use rustc_hir::def_id::DefId;
+use rustc_index::bit_set::BitSet;
+use rustc_index::vec::{Idx, IndexVec};
+use rustc_middle::mir::interpret::{sign_extend, truncate};
+use rustc_middle::mir::{
+    self, AggregateKind, BasicBlock, BinOp, Body, CastKind, Field, InlineAsmOperand, Local,
+    Location, Operand, Place, ProjectionElem, Rvalue, StatementKind, TerminatorKind, UnOp,
+};
+use rustc_middle::ty::{self, ParamEnv, Ty, TyCtxt};
+use rustc_target::abi::{Size, VariantIdx};
+use smallvec::SmallVec;
+
+use super::ever_borrowed_locals;
+use crate::dataflow::lattice::FlatSet;
+use crate::dataflow::{Analysis, AnalysisDomain, DebugWithContext};
+
+/// The maximum number of places tracked in a single body. Each block stores a value for every
+/// tracked place, so this bounds the memory used by the analysis.
+const MAX_TRACKED_PLACES: usize = 512;
+
+/// The maximum number of field projections in a tracked place.
+const MAX_FIELD_DEPTH: usize = 3;
+
+rustc_index::newtype_index! {
+    pub struct TrackedIndex {
+        DEBUG_FORMAT = "tp{}"
+    }
+}
+
+/// A place whose value is tracked by `ConstantPlaces`: a local, possibly followed by some field
+/// projections, that holds an integer, a `bool` or a `char`, or the discriminant of such a place
+/// that holds an enum.
+pub struct TrackedPlace<'tcx> {
+    pub local: Local,
+    pub fields: SmallVec<[Field; 2]>,
+    pub is_discriminant: bool,
+    /// The type of the value, which is the discriminant type for discriminants.
+    pub ty: Ty<'tcx>,
+    pub size: Size,
+}
+
+impl fmt::Debug for TrackedPlace<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_discriminant {
+            write!(f, "discriminant(")?;
+        }
+        write!(f, "{:?}", self.local)?;
+        for field in &self.fields {
+            write!(f, ".{}", field.index())?;
+        }
+        if self.is_discriminant {
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+/// All the places tracked in a body.
+///
+/// Only locals that are never borrowed are tracked, so that the value of a tracked place can only
+/// be changed by the statements and terminators that name it.
+struct TrackedPlaces<'tcx> {
+    places: IndexVec<TrackedIndex, TrackedPlace<'tcx>>,
+
+    /// The indices of the tracked places of each local, which are always contiguous.
+    by_local: IndexVec<Local, Range<usize>>,
+}
+
+impl<'tcx> TrackedPlaces<'tcx> {
+    fn new(
+        tcx: TyCtxt<'tcx>,
+        param_env: ParamEnv<'tcx>,
+        body: &Body<'tcx>,
+        borrowed: &BitSet<Local>,
+    ) -> Self {
+        let mut this = TrackedPlaces {
+            places: IndexVec::new(),
+            by_local: IndexVec::with_capacity(body.local_decls.len()),
+        };
+        for (local, decl) in body.local_decls.iter_enumerated() {
+            let start = this.places.len();
+            if !borrowed.contains(local) {
+                this.add(tcx, param_env, local, &mut SmallVec::new(), decl.ty);
+            }
+            this.by_local.push(start..this.places.len());
+        }
+        this
+    }
+
+    fn add(
+        &mut self,
+        tcx: TyCtxt<'tcx>,
+        param_env: ParamEnv<'tcx>,
+        local: Local,
+        fields: &mut SmallVec<[Field; 2]>,
+        ty: Ty<'tcx>,
+    ) {
+        if self.places.len() >= MAX_TRACKED_PLACES {
+            return;
+        }
+
+        match ty.kind {
+            ty::Bool | ty::Char | ty::Int(_) | ty::Uint(_) => {
+                self.push(tcx, param_env, local, fields, false, ty)
+            }
+            ty::Adt(adt, _) if adt.is_enum() => {
+                self.push(tcx, param_env, local, fields, true, ty.discriminant_ty(tcx))
+            }
+            _ if fields.len() >= MAX_FIELD_DEPTH => {}
+            ty::Tuple(substs) => {
+                for (i, field_ty) in substs.types().enumerate() {
+                    fields.push(Field::new(i));
+                    self.add(tcx, param_env, local, fields, field_ty);
+                    fields.pop();
+                }
+            }
+            ty::Adt(adt, substs) if adt.is_struct() && !adt.is_box() => {
+                for (i, field) in adt.non_enum_variant().fields.iter().enumerate() {
+                    fields.push(Field::new(i));
+                    self.add(tcx, param_env, local, fields, field.ty(tcx, substs));
+                    fields.pop();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn push(
+        &mut self,
+        tcx: TyCtxt<'tcx>,
+        param_env: ParamEnv<'tcx>,
+        local: Local,
+        fields: &[Field],
+        is_discriminant: bool,
+        ty: Ty<'tcx>,
+    ) {
+        if let Ok(layout) = tcx.layout_of(param_env.and(ty)) {
+            let fields = fields.iter().copied().collect();
+            let size = layout.size;
+            self.places.push(TrackedPlace { local, fields, is_discriminant, ty, size });
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.places.len()
+    }
+
+    /// Returns the tracked places inside the place made of `local` followed by `fields`.
+    fn inside<'a>(
+        &'a self,
+        local: Local,
+        fields: &'a [Field],
+    ) -> impl Iterator<Item = (TrackedIndex, &'a TrackedPlace<'tcx>)> + 'a {
+        self.by_local[local]
+            .clone()
+            .map(TrackedIndex::new)
+            .map(move |index| (index, &self.places[index]))
+            .filter(move |(_, place)| place.fields.starts_with(fields))
+    }
+
+    fn find_fields(
+        &self,
+        local: Local,
+        fields: &[Field],
+        is_discriminant: bool,
+    ) -> Option<TrackedIndex> {
+        self.inside(local, fields)
+            .find(|(_, place)| {
+                place.fields.len() == fields.len() && place.is_discriminant == is_discriminant
+            })
+            .map(|(index, _)| index)
+    }
+
+    fn find(&self, place: Place<'tcx>, is_discriminant: bool) -> Option<TrackedIndex> {
+        self.find_fields(place.local, &field_projections(place)?, is_discriminant)
+    }
+}
+
+/// Returns the fields `place` projects to, or `None` if it has any other kind of projection.
+fn field_projections(place: Place<'_>) -> Option<SmallVec<[Field; 2]>> {
+    place
+        .projection
+        .iter()
+        .map(|elem| match elem {
+            ProjectionElem::Field(field, _) => Some(field),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The dataflow state of `ConstantPlaces`: the value of each tracked place.
+pub type ConstantState = IndexVec<TrackedIndex, FlatSet<u128>>;
+
+/// A forward dataflow analysis that computes which places are known to hold a constant at each
+/// point of a body.
+///
+/// Each tracked place is mapped to a `FlatSet` of the bits of its value, truncated to its size.
+/// Values are propagated through assignments, arithmetic, casts, tuple and struct fields and enum
+/// discriminants, including the ones learned by switching on a discriminant. Since states are
+/// joined where control flow merges, a value is still known after a branch or a loop as long as
+/// every path leading there agrees on it.
+///
+/// ```rust
+/// fn foo(c: bool) -> u8 {
+///     let x = if c { 255 } else { 255 };  // x: 255
+///     let mut y = (x, 0u8);               // y.0: 255, y.1: 0
+///     while c {
+///         y.1 = 1;                        // y.0: 255, y.1: 1
+///     }                                   // y.0: 255
+///     y.0
+/// }
+/// ```
+pub struct ConstantPlaces<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    body: &'a Body<'tcx>,
+    places: TrackedPlaces<'tcx>,
+}
+
+impl<'a, 'tcx> ConstantPlaces<'a, 'tcx> {
+    pub fn new(
+        tcx: TyCtxt<'tcx>,
+        param_env: ParamEnv<'tcx>,
+        body: &'a Body<'tcx>,
+        def_id: DefId,
+    ) -> Self {
+        let borrowed = ever_borrowed_locals(tcx, body, def_id);
+        let places = TrackedPlaces::new(tcx, param_env, body, &borrowed);
+        ConstantPlaces { tcx, param_env, body, places }
+    }
+
+    /// Returns whether `state` is the state of unreachable code, i.e. the bottom value.
+    pub fn is_unreachable(&self, state: &ConstantState) -> bool {
+        // The start block begins with every place at `Top`, and the transfer functions only
+        // write `Top` or known values, so reachable states never contain `Bottom`.
+        state.iter().next() == Some(&FlatSet::Bottom)
+    }
+
+    pub fn tracked_place(&self, index: TrackedIndex) -> &TrackedPlace<'tcx> {
+        &self.places.places[index]
+    }
+
+    /// Returns the index of `place` if its value is tracked.
+    pub fn find(&self, place: Place<'tcx>) -> Option<TrackedIndex> {
+        self.places.find(place, false)
+    }
+
+    pub fn size_of(&self, ty: Ty<'tcx>) -> Option<Size> {
+        Some(self.tcx.layout_of(self.param_env.and(ty)).ok()?.size)
+    }
+
+    /// Returns the bits of the value of `operand` in `state`, if it is known.
+    pub fn known_value(&self, state: &ConstantState, operand: &Operand<'tcx>) -> Option<u128> {
+        match self.eval_operand(state, operand) {
+            FlatSet::Elem(bits) => Some(bits),
+            FlatSet::Bottom | FlatSet::Top => None,
+        }
+    }
+
+    fn eval_operand(&self, state: &ConstantState, operand: &Operand<'tcx>) -> FlatSet<u128> {
+        match operand {
+            Operand::Copy(place) | Operand::Move(place) => {
+                self.find(*place).map_or(FlatSet::Top, |index| state[index])
+            }
+            Operand::Constant(constant) => {
+                let ty = constant.literal.ty;
+                if !is_scalar(ty) {
+                    return FlatSet::Top;
+                }
+                constant
+                    .literal
+                    .try_eval_bits(self.tcx, self.param_env, ty)
+                    .map_or(FlatSet::Top, FlatSet::Elem)
+            }
+        }
+    }
+
+    /// Evaluates `left op right` in `state`, returning the bits of the result and whether the
+    /// operation overflowed.
+    pub fn eval_binary_op(
+        &self,
+        state: &ConstantState,
+        op: BinOp,
+        left: &Operand<'tcx>,
+        right: &Operand<'tcx>,
+    ) -> Option<(u128, bool)> {
+        let l = self.known_value(state, left)?;
+        let r = self.known_value(state, right)?;
+        let ty = left.ty(self.body, self.tcx);
+        eval_binary_op(op, ty, self.size_of(ty)?, l, r)
+    }
+
+    /// Evaluates `op operand` in `state`, returning the bits of the result and whether the
+    /// operation overflowed.
+    pub fn eval_unary_op(
+        &self,
+        state: &ConstantState,
+        op: UnOp,
+        operand: &Operand<'tcx>,
+    ) -> Option<(u128, bool)> {
+        let value = self.known_value(state, operand)?;
+        let ty = operand.ty(self.body, self.tcx);
+        Some(eval_unary_op(op, ty, self.size_of(ty)?, value))
+    }
+
+    fn eval_cast(
+        &self,
+        state: &ConstantState,
+        operand: &Operand<'tcx>,
+        to_ty: Ty<'tcx>,
+    ) -> Option<u128> {
+        let from_ty = operand.ty(self.body, self.tcx);
+        if !is_scalar(from_ty) || !(to_ty.is_integral() || to_ty.is_char()) {
+            return None;
+        }
+
+        let value = self.known_value(state, operand)?;
+        let value =
+            if from_ty.is_signed() { sign_extend(value, self.size_of(from_ty)?) } else { value };
+        Some(truncate(value, self.size_of(to_ty)?))
+    }
+
+    /// Evaluates an rvalue whose result is an integer, a `bool` or a `char`.
+    fn eval_rvalue(&self, state: &ConstantState, rvalue: &Rvalue<'tcx>) -> FlatSet<u128> {
+        let value = match rvalue {
+            Rvalue::Use(operand) => return self.eval_operand(state, operand),
+            Rvalue::BinaryOp(op, left, right) => {
+                self.eval_binary_op(state, *op, left, right).and_then(without_overflow)
+            }
+            Rvalue::UnaryOp(op, operand) => {
+                self.eval_unary_op(state, *op, operand).and_then(without_overflow)
+            }
+            Rvalue::Cast(CastKind::Misc, operand, ty) => self.eval_cast(state, operand, *ty),
+            Rvalue::Discriminant(place) => {
+                return self.places.find(*place, true).map_or(FlatSet::Top, |index| state[index]);
+            }
+            Rvalue::Len(place) => match place.ty(self.body, self.tcx).ty.kind {
+                ty::Array(_, len) => len.try_eval_usize(self.tcx, self.param_env).map(u128::from),
+                _ => None,
+            },
+            _ => None,
+        };
+        value.map_or(FlatSet::Top, FlatSet::Elem)
+    }
+
+    /// Assigns `rvalue` to `place`.
+    fn assign(&self, state: &mut ConstantState, place: Place<'tcx>, rvalue: &Rvalue<'tcx>) {
+        let fields = match field_projections(place) {
+            Some(fields) => fields,
+            None => return self.flood(state, place),
+        };
+
+        // `rvalue` may read the old value of `place`, so it has to be evaluated before `place` is
+        // overwritten.
+        let mut writes = Vec::new();
+        match rvalue {
+            Rvalue::Use(operand) => {
+                self.operand_writes(state, place.local, &fields, operand, &mut writes)
+            }
+            Rvalue::CheckedBinaryOp(op, left, right) => {
+                let (value, overflow) = match self.eval_binary_op(state, *op, left, right) {
+                    Some((value, false)) => (FlatSet::Elem(value), FlatSet::Elem(0)),
+                    Some((_, true)) => (FlatSet::Top, FlatSet::Elem(1)),
+                    None => (FlatSet::Top, FlatSet::Top),
+                };
+                for (i, value) in [value, overflow].iter().enumerate() {
+                    let mut field = fields.clone();
+                    field.push(Field::new(i));
+                    if let Some(index) = self.places.find_fields(place.local, &field, false) {
+                        writes.push((index, *value));
+                    }
+                }
+            }
+            Rvalue::Aggregate(box kind, operands) => match kind {
+                AggregateKind::Adt(adt, variant, ..) if adt.is_enum() => {
+                    if let Some(index) = self.places.find_fields(place.local, &fields, true) {
+                        let discr = adt.discriminant_for_variant(self.tcx, *variant);
+                        writes.push((index, FlatSet::Elem(discr.val)));
+                    }
+                }
+                AggregateKind::Tuple | AggregateKind::Adt(..) => {
+                    for (i, operand) in operands.iter().enumerate() {
+                        let mut field = fields.clone();
+                        field.push(Field::new(i));
+                        self.operand_writes(state, place.local, &field, operand, &mut writes);
+                    }
+                }
+                AggregateKind::Array(..)
+                | AggregateKind::Closure(..)
+                | AggregateKind::Generator(..) => {}
+            },
+            _ => {
+                if let Some(index) = self.places.find_fields(place.local, &fields, false) {
+                    writes.push((index, self.eval_rvalue(state, rvalue)));
+                }
+            }
+        }
+
+        self.flood(state, place);
+        for (index, value) in writes {
+            state[index] = value;
+        }
+    }
+
+    /// Collects the values that the tracked places inside the place made of `local` followed by
+    /// `fields` get when `operand` is assigned to it.
+    fn operand_writes(
+        &self,
+        state: &ConstantState,
+        local: Local,
+        fields: &[Field],
+        operand: &Operand<'tcx>,
+        writes: &mut Vec<(TrackedIndex, FlatSet<u128>)>,
+    ) {
+        match operand {
+            Operand::Copy(source) | Operand::Move(source) => {
+                let source_fields = match field_projections(*source) {
+                    Some(fields) => fields,
+                    None => return,
+                };
+                for (source_index, source_place) in self.places.inside(source.local, &source_fields)
+                {
+                    let mut target: SmallVec<[Field; 2]> = fields.iter().copied().collect();
+                    target.extend_from_slice(&source_place.fields[source_fields.len()..]);
+                    let is_discriminant = source_place.is_discriminant;
+                    if let Some(index) = self.places.find_fields(local, &target, is_discriminant) {
+                        writes.push((index, state[source_index]));
+                    }
+                }
+            }
+            Operand::Constant(_) => {
+                if let Some(index) = self.places.find_fields(local, fields, false) {
+                    writes.push((index, self.eval_operand(state, operand)));
+                }
+            }
+        }
+    }
+
+    /// Forgets the value of every tracked place that may overlap `place`.
+    fn flood(&self, state: &mut ConstantState, place: Place<'tcx>) {
+        let mut fields = SmallVec::<[Field; 2]>::new();
+        for elem in place.projection.iter() {
+            match elem {
+                ProjectionElem::Field(field, _) => fields.push(field),
+                _ => break,
+            }
+        }
+
+        for (index, _) in self.places.inside(place.local, &fields) {
+            state[index] = FlatSet::Top;
+        }
+    }
+}
+
+/// Forgets the results of operations that overflow, so that an overflow is only reported once
+/// instead of at every operation its result flows into.
+fn without_overflow((value, overflow): (u128, bool)) -> Option<u128> {
+    if overflow { None } else { Some(value) }
+}
+
+fn is_scalar(ty: Ty<'_>) -> bool {
+    ty.is_bool() || ty.is_char() || ty.is_integral()
+}
+
+/// Evaluates `l op r`, where `l` and `r` are the bits of two values, the left one being of type
+/// `ty` and size `size`. Returns the bits of the result and whether the operation overflowed, or
+/// `None` for divisions by zero and pointer offsets.
+fn eval_binary_op(op: BinOp, ty: Ty<'_>, size: Size, l: u128, r: u128) -> Option<(u128, bool)> {
+    let signed = ty.is_signed();
+    let (sl, sr) = (sign_extend(l, size) as i128, sign_extend(r, size) as i128);
+    let ordering = if signed { sl.cmp(&sr) } else { l.cmp(&r) };
+    let (result, overflow) = match op {
+        BinOp::Eq => (u128::from(l == r), false),
+        BinOp::Ne => (u128::from(l != r), false),
+        BinOp::Lt => (u128::from(ordering == Ordering::Less), false),
+        BinOp::Le => (u128::from(ordering != Ordering::Greater), false),
+        BinOp::Gt => (u128::from(ordering == Ordering::Greater), false),
+        BinOp::Ge => (u128::from(ordering != Ordering::Less), false),
+        BinOp::BitAnd => (l & r, false),
+        BinOp::BitOr => (l | r, false),
+        BinOp::BitXor => (l ^ r, false),
+        BinOp::Shl | BinOp::Shr => {
+            // Like in the interpreter, the shift amount is masked to the size of the left-hand
+            // side, and overflows if it has to be.
+            let bits = u128::from(size.bits());
+            let amount = (r % bits) as u32;
+            let result = match op {
+                BinOp::Shl => l << amount,
+                _ if signed => (sl >> amount) as u128,
+                _ => l >> amount,
+            };
+            (truncate(result, size), r >= bits)
+        }
+        BinOp::Div | BinOp::Rem if r == 0 => return None,
+        BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div | BinOp::Rem if signed => {
+            let (result, overflow) = match op {
+                BinOp::Add => sl.overflowing_add(sr),
+                BinOp::Sub => sl.overflowing_sub(sr),
+                BinOp::Mul => sl.overflowing_mul(sr),
+                BinOp::Div => sl.overflowing_div(sr),
+                _ => sl.overflowing_rem(sr),
+            };
+            // `MIN % -1` fits in any size, but overflows like `MIN / -1` does.
+            let min = sign_extend(1 << (size.bits() - 1), size) as i128;
+            let truncated = truncate(result as u128, size);
+            let overflow = overflow
+                || sign_extend(truncated, size) as i128 != result
+                || (op == BinOp::Rem && sl == min && sr == -1);
+            (truncated, overflow)
+        }
+        BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div | BinOp::Rem => {
+            let (result, overflow) = match op {
+                BinOp::Add => l.overflowing_add(r),
+                BinOp::Sub => l.overflowing_sub(r),
+                BinOp::Mul => l.overflowing_mul(r),
+                BinOp::Div => l.overflowing_div(r),
+                _ => l.overflowing_rem(r),
+            };
+            let truncated = truncate(result, size);
+            (truncated, overflow || truncated != result)
+        }
+        BinOp::Offset => return None,
+    };
+    Some((result, overflow))
+}
+
+/// Evaluates `op value`, where `value` is the bits of a value of type `ty` and size `size`.
+/// Returns the bits of the result and whether the operation overflowed.
+fn eval_unary_op(op: UnOp, ty: Ty<'_>, size: Size, value: u128) -> (u128, bool) {
+    match op {
+        UnOp::Not if ty.is_bool() => (value ^ 1, false),
+        UnOp::Not => (truncate(!value, size), false),
+        UnOp::Neg => {
+            let (result, overflow) = (sign_extend(value, size) as i128).overflowing_neg();
+            let truncated = truncate(result as u128, size);
+            (truncated, overflow || sign_extend(truncated, size) as i128 != result)
+        }
+    }
+}
+
+impl<'tcx> AnalysisDomain<'tcx> for ConstantPlaces<'_, 'tcx> {
+    type Domain = ConstantState;
+    const NAME: &'static str = "constant_places";
+
+    fn bottom_value(&self, _: &mir::Body<'tcx>) -> Self::Domain {
+        // bottom = unreachable
+        IndexVec::from_elem_n(FlatSet::Bottom, self.places.len())
+    }
+
+    fn initialize_start_block(&self, _: &mir::Body<'tcx>, state: &mut Self::Domain) {
+        // Arguments can have any value, and other locals are not initialized yet.
+        for value in state.iter_mut() {
+            *value = FlatSet::Top;
+        }
+    }
+}
+
+impl<'tcx> Analysis<'tcx> for ConstantPlaces<'_, 'tcx> {
+    fn apply_statement_effect(
+        &self,
+        state: &mut Self::Domain,
+        statement: &mir::Statement<'tcx>,
+        _: Location,
+    ) {
+        if self.is_unreachable(state) {
+            return;
+        }
+
+        match &statement.kind {
+            StatementKind::Assign(box (place, rvalue)) => self.assign(state, *place, rvalue),
+
+            StatementKind::SetDiscriminant { place, variant_index } => {
+                if let Some(index) = self.places.find(**place, true) {
+                    let ty = place.ty(self.body, self.tcx).ty;
+                    state[index] = match ty.discriminant_for_variant(self.tcx, *variant_index) {
+                        Some(discr) => FlatSet::Elem(discr.val),
+                        None => FlatSet::Top,
+                    };
+                }
+            }
+
+            StatementKind::StorageLive(local) | StatementKind::StorageDead(local) => {
+                self.flood(state, Place::from(*local))
+            }
+
+            StatementKind::LlvmInlineAsm(asm) => {
+                for output in asm.outputs.iter() {
+                    self.flood(state, *output);
+                }
+            }
+
+            StatementKind::FakeRead(..)
+            | StatementKind::Retag(..)
+            | StatementKind::AscribeUserType(..)
+            | StatementKind::Nop => {}
+        }
+    }
+
+    fn apply_terminator_effect(
+        &self,
+        state: &mut Self::Domain,
+        terminator: &mir::Terminator<'tcx>,
+        _: Location,
+    ) {
+        if self.is_unreachable(state) {
+            return;
+        }
+
+        match &terminator.kind {
+            TerminatorKind::DropAndReplace { place, value, .. } => {
+                self.assign(state, *place, &Rvalue::Use(value.clone()))
+            }
+
+            TerminatorKind::InlineAsm { operands, .. } => {
+                for operand in operands {
+                    match operand {
+                        InlineAsmOperand::Out { place: Some(place), .. }
+                        | InlineAsmOperand::InOut { out_place: Some(place), .. } => {
+                            self.flood(state, *place)
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    fn apply_call_return_effect(
+        &self,
+        state: &mut Self::Domain,
+        _: BasicBlock,
+        _: &mir::Operand<'tcx>,
+        _: &[mir::Operand<'tcx>],
+        return_place: Place<'tcx>,
+    ) {
+        if self.is_unreachable(state) {
+            return;
+        }
+
+        self.flood(state, return_place);
+    }
+
+    fn apply_yield_resume_effect(
+        &self,
+        state: &mut Self::Domain,
+        _: BasicBlock,
+        resume_place: Place<'tcx>,
+    ) {
+        if self.is_unreachable(state) {
+            return;
+        }
+
+        self.flood(state, resume_place);
+    }
+
+    fn apply_discriminant_switch_effect(
+        &self,
+        state: &mut Self::Domain,
+        _: BasicBlock,
+        enum_place: Place<'tcx>,
+        adt: &ty::AdtDef,
+        variant: VariantIdx,
+    ) {
+        if self.is_unreachable(state) {
+            return;
+        }
+
+        if let Some(index) = self.places.find(enum_place, true) {
+            let discr = FlatSet::Elem(adt.discriminant_for_variant(self.tcx, variant).val);
+            match state[index] {
+                // The discriminant is known to be another one, so this edge is never taken.
+                FlatSet::Elem(_) if state[index] != discr => {
+                    for value in state.iter_mut() {
+                        *value = FlatSet::Bottom;
+                    }
+                }
+                _ => state[index] = discr,
+            }
+        }
+    }
+}
+
+impl<'tcx> DebugWithContext<ConstantPlaces<'_, 'tcx>> for ConstantState {
+    fn fmt_with(&self, ctxt: &ConstantPlaces<'_, 'tcx>, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let known = self.iter_enumerated().filter_map(|(index, value)| match value {
+            FlatSet::Elem(bits) => Some((ctxt.tracked_place(index), bits)),
+            FlatSet::Bottom | FlatSet::Top => None,
+        });
+        f.debug_map().entries(known).finish()
+    }
+
+    fn fmt_diff_with(
+        &self,
+        old: &Self,
+        ctxt: &ConstantPlaces<'_, 'tcx>,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        let mut first = true;
+        for (index, value) in self.iter_enumerated() {
+            if *value == old[index] {
+                continue;
+            }
+
+            let place = ctxt.tracked_place(index);
+            let line = match (old[index], value) {
+                (_, FlatSet::Elem(bits)) => format!("+{:?}: {}", place, bits),
+                (FlatSet::Elem(_), _) => format!("-{:?}", place),
+                _ => continue,
+            };
+
+            if !first {
+                writeln!(f)?;
+            }
+            first = false;
+            f.write_str(&line)?;
+        }
+        Ok(())
+    }
+}
@@ -5,9 +5,9 @@ use rustc_span::symbol::{sym, Symbol};
 
 pub(crate) use self::drop_flag_effects::*;
 pub use self::framework::{
-    visit_results, Analysis, AnalysisDomain, Backward, BorrowckFlowState, BorrowckResults,
-    BottomValue, Engine, Forward, GenKill, GenKillAnalysis, Results, ResultsCursor,
-    ResultsRefCursor, ResultsVisitor,
+    lattice, visit_results, Analysis, AnalysisDomain, Backward, BorrowckFlowState,
+    BorrowckResults, DebugWithContext, Engine, Forward, GenKill, GenKillAnalysis,
+    JoinSemiLattice, Results, ResultsCursor, ResultsRefCursor, ResultsVisitor,
 };
 
 use self::move_paths::MoveData;
@@ -227,7 +227,13 @@ impl<'rt, 'mir, 'tcx: 'mir, M: Machine<'mir, 'tcx>> ValidityVisitor<'rt, 'mir, '
                 let mut name = None;
                 if let Some(def_id) = def_id.as_local() {
                     let tables = self.ecx.tcx.typeck(def_id);
-                    if let Some(upvars) = tables.closure_captures.get(&def_id.to_def_id()) {
+                    if tables.closure_min_captures.contains_key(&def_id.to_def_id()) {
+                        // The fields are the captured places, not the captured variables.
+                        name = tables
+                            .closure_min_captures_flattened(def_id.to_def_id())
+                            .nth(field)
+                            .map(|captured| Symbol::intern(&captured.to_string(*self.ecx.tcx)));
+                    } else if let Some(upvars) = tables.closure_captures.get(&def_id.to_def_id()) {
                         // Sometimes the index is beyond the number of upvars (seen
                         // for a generator).
                         if let Some((&var_hir_id, _)) = upvars.get_index(field) {
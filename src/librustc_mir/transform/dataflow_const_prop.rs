@@ -0,0 +1,400 @@
+//! A constant propagation pass based on the `ConstantPlaces` dataflow analysis.
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::DefId;
+use rustc_middle::hir::map::blocks::FnLikeNode;
+use rustc_middle::mir::visit::MutVisitor;
+use rustc_middle::mir::*;
+use rustc_middle::ty::{self, ConstInt, ParamEnv, Ty, TyCtxt};
+use rustc_session::lint;
+use rustc_span::Span;
+use rustc_target::abi::Size;
+
+use crate::dataflow::impls::{ConstantPlaces, ConstantState};
+use crate::dataflow::lattice::FlatSet;
+use crate::dataflow::{Analysis, ResultsVisitor};
+use crate::transform::{MirPass, MirSource};
+
+/// Propagates constants through the whole body with the `ConstantPlaces` dataflow analysis.
+///
+/// Unlike `ConstProp`, which interprets one block at a time, this keeps what it knows across
+/// branches and loops, as long as every path agrees on it:
+///
+/// ```rust
+/// fn foo(c: bool) -> u8 {
+///     let x: u8 = if c { 255 } else { 255 };
+///     x + 1 // Reported as an overflow.
+/// }
+/// ```
+///
+/// The `ARITHMETIC_OVERFLOW` and `UNCONDITIONAL_PANIC` lints are always reported from this
+/// analysis, on every optimization level, see `find_assert_lints`.
+pub struct DataflowConstProp;
+
+impl<'tcx> MirPass<'tcx> for DataflowConstProp {
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut Body<'tcx>) {
+        if tcx.sess.opts.debugging_opts.mir_opt_level < 2 {
+            return;
+        }
+
+        // Like for `ConstProp`, promoteds, constants and statics are left to Miri, which reports
+        // their errors when evaluating them.
+        if source.promoted.is_some() {
+            return;
+        }
+        let def_id = source.def_id();
+        let hir_id = tcx.hir().as_local_hir_id(def_id.expect_local());
+        let is_fn_like = FnLikeNode::from_node(tcx.hir().get(hir_id)).is_some();
+        if !is_fn_like && tcx.def_kind(def_id) != DefKind::AssocConst {
+            return;
+        }
+
+        trace!("DataflowConstProp starting for {:?}", def_id);
+
+        let Findings { assignments, operands, .. } = collect(tcx, body, def_id);
+        Patcher { tcx, assignments, operands }.visit_body(body);
+    }
+}
+
+/// Finds the arithmetic overflows and unconditional panics in `body` that are known from the
+/// `ConstantPlaces` analysis.
+///
+/// `ConstProp` reports these instead of the ones its per-block interpretation finds, which only
+/// knows the values of locals that are assigned once or in the current block.
+pub(super) fn find_assert_lints<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    def_id: DefId,
+) -> Vec<AssertLint> {
+    collect(tcx, body, def_id).lints
+}
+
+/// An `ARITHMETIC_OVERFLOW` or `UNCONDITIONAL_PANIC` lint found by `find_assert_lints`.
+pub(super) struct AssertLint {
+    lint: &'static lint::Lint,
+    pub(super) source_info: SourceInfo,
+    message: &'static str,
+    label: String,
+}
+
+impl AssertLint {
+    pub(super) fn emit(&self, tcx: TyCtxt<'_>, body: &Body<'_>) {
+        let lint_root = match &body.source_scopes[self.source_info.scope].local_data {
+            ClearCrossCrate::Set(data) => data.lint_root,
+            ClearCrossCrate::Clear => return,
+        };
+        tcx.struct_span_lint_hir(self.lint, lint_root, self.source_info.span, |lint| {
+            let mut err = lint.build(self.message);
+            err.span_label(self.source_info.span, &self.label);
+            err.emit()
+        });
+    }
+}
+
+/// Everything `Collector` found in a body.
+struct Findings<'tcx> {
+    /// Assignments whose right-hand side always evaluates to a constant.
+    assignments: FxHashMap<Location, Constant<'tcx>>,
+
+    /// Operands that always read a constant.
+    operands: FxHashMap<(Location, Place<'tcx>), Constant<'tcx>>,
+
+    lints: Vec<AssertLint>,
+}
+
+fn collect<'tcx>(tcx: TyCtxt<'tcx>, body: &Body<'tcx>, def_id: DefId) -> Findings<'tcx> {
+    let param_env = tcx.param_env(def_id);
+    let results = ConstantPlaces::new(tcx, param_env, body, def_id)
+        .into_engine(tcx, body, def_id)
+        .iterate_to_fixpoint();
+    let mut collector = Collector {
+        tcx,
+        param_env,
+        body,
+        analysis: &results.analysis,
+        findings: Findings {
+            assignments: FxHashMap::default(),
+            operands: FxHashMap::default(),
+            lints: Vec::new(),
+        },
+    };
+    results.visit_reachable_with(body, &mut collector);
+    collector.findings
+}
+
+/// Collects lints and the replacements to make in the body, using the dataflow state at each
+/// location.
+struct Collector<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    body: &'a Body<'tcx>,
+    analysis: &'a ConstantPlaces<'a, 'tcx>,
+    findings: Findings<'tcx>,
+}
+
+impl<'a, 'tcx> Collector<'a, 'tcx> {
+    /// Returns the constant held by `place` in `state`, if it is known.
+    fn constant(
+        &self,
+        state: &ConstantState,
+        place: Place<'tcx>,
+        span: Span,
+    ) -> Option<Constant<'tcx>> {
+        let index = self.analysis.find(place)?;
+        let bits = match state[index] {
+            FlatSet::Elem(bits) => bits,
+            FlatSet::Bottom | FlatSet::Top => return None,
+        };
+        let ty = self.analysis.tracked_place(index).ty;
+        let literal = ty::Const::from_bits(self.tcx, bits, self.param_env.and(ty));
+        Some(Constant { span, user_ty: None, literal })
+    }
+
+    fn collect_operand(
+        &mut self,
+        state: &ConstantState,
+        operand: &Operand<'tcx>,
+        location: Location,
+        span: Span,
+    ) {
+        if let Operand::Copy(place) | Operand::Move(place) = *operand {
+            if let Some(constant) = self.constant(state, place, span) {
+                self.findings.operands.insert((location, place), constant);
+            }
+        }
+    }
+
+    /// Returns `operand` as a `ConstInt` if its value is known in `state`.
+    fn const_int(&self, state: &ConstantState, operand: &Operand<'tcx>) -> Option<ConstInt> {
+        let bits = self.analysis.known_value(state, operand)?;
+        let ty = operand.ty(self.body, self.tcx);
+        Some(const_int(bits, ty, self.analysis.size_of(ty)?))
+    }
+
+    fn check_binary_op(
+        &mut self,
+        state: &ConstantState,
+        op: BinOp,
+        left: &Operand<'tcx>,
+        right: &Operand<'tcx>,
+        source_info: SourceInfo,
+    ) {
+        let r = match self.const_int(state, right) {
+            Some(r) => r,
+            None => return,
+        };
+        let left_ty = left.ty(self.body, self.tcx);
+        let left_size = match self.analysis.size_of(left_ty) {
+            Some(size) => size,
+            None => return,
+        };
+        let overflow = match self.analysis.eval_binary_op(state, op, left, right) {
+            Some((_, overflow)) => overflow,
+            // Shifts can be known to overflow from their right-hand side alone.
+            None if op == BinOp::Shl || op == BinOp::Shr => {
+                let r_bits = self.analysis.known_value(state, right).unwrap();
+                r_bits >= u128::from(left_size.bits())
+            }
+            None => false,
+        };
+        if overflow {
+            let l = match self.const_int(state, left) {
+                Some(l) => l,
+                // Invent a dummy value, the diagnostic ignores it anyway.
+                None => const_int(1, left_ty, left_size),
+            };
+            self.push_lint(
+                lint::builtin::ARITHMETIC_OVERFLOW,
+                source_info,
+                "this arithmetic operation will overflow",
+                AssertKind::Overflow(op, l, r),
+            );
+        }
+    }
+
+    fn check_unary_op(
+        &mut self,
+        state: &ConstantState,
+        op: UnOp,
+        operand: &Operand<'tcx>,
+        source_info: SourceInfo,
+    ) {
+        if let Some((_, true)) = self.analysis.eval_unary_op(state, op, operand) {
+            // `AssertKind` only has an `OverflowNeg` variant, so make sure that is
+            // appropriate to use.
+            assert_eq!(op, UnOp::Neg, "Neg is the only UnOp that can overflow");
+            self.push_lint(
+                lint::builtin::ARITHMETIC_OVERFLOW,
+                source_info,
+                "this arithmetic operation will overflow",
+                AssertKind::OverflowNeg(self.const_int(state, operand).unwrap()),
+            );
+        }
+    }
+
+    fn check_assert(
+        &mut self,
+        state: &ConstantState,
+        msg: &AssertKind<Operand<'tcx>>,
+        source_info: SourceInfo,
+    ) {
+        let eval_to_int = |op| match self.const_int(state, op) {
+            Some(int) => DbgVal::Val(int),
+            None => DbgVal::Underscore,
+        };
+        let msg = match msg {
+            AssertKind::DivisionByZero(op) => AssertKind::DivisionByZero(eval_to_int(op)),
+            AssertKind::RemainderByZero(op) => AssertKind::RemainderByZero(eval_to_int(op)),
+            AssertKind::BoundsCheck { len, index } => {
+                AssertKind::BoundsCheck { len: eval_to_int(len), index: eval_to_int(index) }
+            }
+            // Overflows are already reported by `check_binary_op` and `check_unary_op`.
+            _ => return,
+        };
+        self.push_lint(
+            lint::builtin::UNCONDITIONAL_PANIC,
+            source_info,
+            "this operation will panic at runtime",
+            msg,
+        );
+    }
+
+    fn push_lint(
+        &mut self,
+        lint: &'static lint::Lint,
+        source_info: SourceInfo,
+        message: &'static str,
+        panic: AssertKind<impl std::fmt::Debug>,
+    ) {
+        let label = format!("{:?}", panic);
+        self.findings.lints.push(AssertLint { lint, source_info, message, label });
+    }
+}
+
+/// An operand of a failed assertion in a lint, which is printed as `_` if it is not known.
+enum DbgVal<T> {
+    Val(T),
+    Underscore,
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for DbgVal<T> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Val(val) => val.fmt(fmt),
+            Self::Underscore => fmt.write_str("_"),
+        }
+    }
+}
+
+fn const_int(bits: u128, ty: Ty<'_>, size: Size) -> ConstInt {
+    ConstInt::new(bits, size, ty.is_signed(), ty.is_ptr_sized_integral())
+}
+
+impl<'mir, 'tcx> ResultsVisitor<'mir, 'tcx> for Collector<'_, 'tcx> {
+    type FlowState = ConstantState;
+
+    fn visit_statement_before_primary_effect(
+        &mut self,
+        state: &Self::FlowState,
+        statement: &'mir Statement<'tcx>,
+        location: Location,
+    ) {
+        let source_info = statement.source_info;
+        let rvalue = match &statement.kind {
+            StatementKind::Assign(box (_, rvalue)) => rvalue,
+            _ => return,
+        };
+
+        match rvalue {
+            Rvalue::BinaryOp(op, left, right) | Rvalue::CheckedBinaryOp(op, left, right) => {
+                self.check_binary_op(state, *op, left, right, source_info);
+                self.collect_operand(state, left, location, source_info.span);
+                self.collect_operand(state, right, location, source_info.span);
+            }
+            Rvalue::UnaryOp(op, operand) => {
+                self.check_unary_op(state, *op, operand, source_info);
+                self.collect_operand(state, operand, location, source_info.span);
+            }
+            Rvalue::Use(operand) | Rvalue::Repeat(operand, _) | Rvalue::Cast(_, operand, _) => {
+                self.collect_operand(state, operand, location, source_info.span);
+            }
+            Rvalue::Aggregate(_, operands) => {
+                for operand in operands {
+                    self.collect_operand(state, operand, location, source_info.span);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_statement_after_primary_effect(
+        &mut self,
+        state: &Self::FlowState,
+        statement: &'mir Statement<'tcx>,
+        location: Location,
+    ) {
+        if let StatementKind::Assign(box (place, rvalue)) = &statement.kind {
+            if let Rvalue::Use(Operand::Constant(_)) = rvalue {
+                return;
+            }
+            if let Some(constant) = self.constant(state, *place, statement.source_info.span) {
+                self.findings.assignments.insert(location, constant);
+            }
+        }
+    }
+
+    fn visit_terminator_before_primary_effect(
+        &mut self,
+        state: &Self::FlowState,
+        terminator: &'mir Terminator<'tcx>,
+        location: Location,
+    ) {
+        let source_info = terminator.source_info;
+        match &terminator.kind {
+            TerminatorKind::Assert { cond, expected, msg, .. } => {
+                match self.analysis.known_value(state, cond) {
+                    Some(value) if (value == 1) != *expected => {
+                        self.check_assert(state, msg, source_info)
+                    }
+                    Some(_) => self.collect_operand(state, cond, location, source_info.span),
+                    None => {}
+                }
+            }
+            TerminatorKind::SwitchInt { discr, .. } => {
+                self.collect_operand(state, discr, location, source_info.span);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Replaces the assignments and operands found by `Collector` with constants.
+struct Patcher<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    assignments: FxHashMap<Location, Constant<'tcx>>,
+    operands: FxHashMap<(Location, Place<'tcx>), Constant<'tcx>>,
+}
+
+impl<'tcx> MutVisitor<'tcx> for Patcher<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_statement(&mut self, statement: &mut Statement<'tcx>, location: Location) {
+        if let Some(constant) = self.assignments.remove(&location) {
+            if let StatementKind::Assign(box (_, rvalue)) = &mut statement.kind {
+                *rvalue = Rvalue::Use(Operand::Constant(box constant));
+            }
+        }
+        self.super_statement(statement, location);
+    }
+
+    fn visit_operand(&mut self, operand: &mut Operand<'tcx>, location: Location) {
+        if let Operand::Copy(place) | Operand::Move(place) = *operand {
+            if let Some(constant) = self.operands.get(&(location, place)) {
+                *operand = Operand::Constant(box constant.clone());
+            }
+        }
+    }
+}
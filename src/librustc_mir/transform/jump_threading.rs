@@ -0,0 +1,173 @@
+//! A pass that redirects jumps into a `SwitchInt` whose outcome is already known along the jump.
+
+use crate::transform::{MirPass, MirSource};
+use rustc_middle::mir::*;
+use rustc_middle::ty::{ParamEnv, TyCtxt};
+use smallvec::SmallVec;
+
+/// Threads jumps through blocks that do nothing but switch on a value that is known on the edge
+/// leading into them. For example, the result of `?` or of a nested `match` is often matched on
+/// right after being built:
+///
+/// ```text
+/// bb1: {
+///     ((_0 as Some).0: u8) = move _3;
+///     discriminant(_0) = 1;
+///     goto -> bb3;
+/// }
+///
+/// bb3: {
+///     _4 = discriminant(_0);
+///     switchInt(move _4) -> [0_isize: bb4, otherwise: bb5];
+/// }
+/// ```
+///
+/// Here `bb1` can jump straight to `bb5`. Edges out of a `SwitchInt` on the same value are
+/// threaded the same way. The skipped block is left in place for its other predecessors, and is
+/// removed by `SimplifyCfg` if it became unreachable.
+pub struct JumpThreading;
+
+impl<'tcx> MirPass<'tcx> for JumpThreading {
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut Body<'tcx>) {
+        if tcx.sess.opts.debugging_opts.mir_opt_level < 2 {
+            return;
+        }
+
+        let param_env = tcx.param_env(source.def_id());
+        let mut threads = Vec::new();
+        for (bb, data) in body.basic_blocks().iter_enumerated() {
+            let switch = match Switch::of_block(data) {
+                Some(switch) => switch,
+                None => continue,
+            };
+
+            for &pred in &body.predecessors()[bb] {
+                let pred_terminator = body.basic_blocks()[pred].terminator();
+                for (index, &succ) in pred_terminator.successors().enumerate() {
+                    if succ != bb {
+                        continue;
+                    }
+                    let known = known_values(tcx, param_env, body, pred, index);
+                    if let Some(target) = switch.target_for(&known) {
+                        if target != bb {
+                            threads.push((pred, index, target));
+                        }
+                    }
+                }
+            }
+        }
+
+        for (pred, index, target) in threads {
+            debug!("JumpThreading: redirecting edge {} of {:?} to {:?}", index, pred, target);
+            let pred_terminator = body.basic_blocks_mut()[pred].terminator_mut();
+            *pred_terminator.successors_mut().nth(index).unwrap() = target;
+        }
+    }
+}
+
+/// A value that a `SwitchInt` can be on.
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Condition<'tcx> {
+    /// The value stored in a place.
+    Place(Place<'tcx>),
+    /// The discriminant of a place.
+    Discriminant(Place<'tcx>),
+}
+
+/// A block that consists only of a `SwitchInt` on a `Condition`.
+struct Switch<'a, 'tcx> {
+    condition: Condition<'tcx>,
+    values: &'a [u128],
+    targets: &'a [BasicBlock],
+}
+
+impl<'a, 'tcx> Switch<'a, 'tcx> {
+    fn of_block(data: &'a BasicBlockData<'tcx>) -> Option<Self> {
+        let (discr, values, targets) = match &data.terminator().kind {
+            TerminatorKind::SwitchInt { discr, values, targets, .. } => (discr, values, targets),
+            _ => return None,
+        };
+        let mut statements = data.statements.iter().filter(|stmt| stmt.kind != StatementKind::Nop);
+        let condition = match (statements.next().map(|stmt| &stmt.kind), discr) {
+            (None, Operand::Copy(place) | Operand::Move(place)) => Condition::Place(*place),
+            // Skipping the assignment is fine, since the switch moves the only value ever
+            // assigned to this temporary.
+            (
+                Some(StatementKind::Assign(box (lhs, Rvalue::Discriminant(place)))),
+                Operand::Move(discr),
+            ) if lhs == discr && lhs.as_local().is_some() => Condition::Discriminant(*place),
+            _ => return None,
+        };
+        if statements.next().is_some() {
+            return None;
+        }
+        Some(Switch { condition, values, targets })
+    }
+
+    /// Returns the target this switch jumps to when one of the `known` conditions holds.
+    fn target_for(&self, known: &[(Condition<'tcx>, u128)]) -> Option<BasicBlock> {
+        let &(_, value) = known.iter().find(|(condition, _)| *condition == self.condition)?;
+        let index = self.values.iter().position(|&v| v == value).unwrap_or(self.values.len());
+        Some(self.targets[index])
+    }
+}
+
+/// Returns the values known on the edge out of `pred` with the given successor index.
+fn known_values(
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    body: &Body<'tcx>,
+    pred: BasicBlock,
+    index: usize,
+) -> SmallVec<[(Condition<'tcx>, u128); 2]> {
+    let data = &body.basic_blocks()[pred];
+    let last_statement = data
+        .statements
+        .iter()
+        .rev()
+        .map(|stmt| &stmt.kind)
+        .find(|kind| **kind != StatementKind::Nop);
+    let mut known = SmallVec::new();
+    match &data.terminator().kind {
+        TerminatorKind::Goto { .. } => match last_statement {
+            Some(StatementKind::SetDiscriminant { place, variant_index }) => {
+                let ty = place.ty(body, tcx).ty;
+                if let Some(discr) = ty.discriminant_for_variant(tcx, *variant_index) {
+                    known.push((Condition::Discriminant(**place), discr.val));
+                }
+            }
+            Some(StatementKind::Assign(box (place, Rvalue::Use(Operand::Constant(constant))))) => {
+                let ty = constant.literal.ty;
+                if let Some(bits) = constant.literal.try_eval_bits(tcx, param_env, ty) {
+                    known.push((Condition::Place(*place), bits));
+                }
+            }
+            _ => {}
+        },
+        TerminatorKind::SwitchInt { discr, switch_ty, values, .. } => {
+            let value = if index < values.len() {
+                values[index]
+            } else if switch_ty.is_bool() && values[..] == [0] {
+                // The `otherwise` edge of an `if`.
+                1
+            } else {
+                return known;
+            };
+
+            if let Some(place) = discr.place() {
+                known.push((Condition::Place(place), value));
+            }
+            if let (
+                Operand::Move(discr),
+                Some(StatementKind::Assign(box (lhs, Rvalue::Discriminant(place)))),
+            ) = (discr, last_statement)
+            {
+                if lhs == discr {
+                    known.push((Condition::Discriminant(*place), value));
+                }
+            }
+        }
+        _ => {}
+    }
+    known
+}
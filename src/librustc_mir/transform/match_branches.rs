@@ -0,0 +1,193 @@
+//! A pass that replaces a `SwitchInt` by a cast when every arm just assigns the value switched on.
+
+use crate::transform::{MirPass, MirSource};
+use rustc_middle::mir::interpret::{sign_extend, truncate};
+use rustc_middle::mir::*;
+use rustc_middle::ty::{self, ParamEnv, Ty, TyCtxt};
+use rustc_target::abi::Size;
+
+/// If every target of a `SwitchInt` on an integer assigns that same integer, converted to some
+/// integer type, to one place and then jumps to one block, the whole switch is a cast:
+///
+/// ```text
+/// bb0: {
+///     _2 = discriminant(_1);
+///     switchInt(move _2) -> [0_isize: bb1, 1_isize: bb2, otherwise: bb3];
+/// }
+///
+/// bb1: {
+///     _0 = const 0_u8;
+///     goto -> bb4;
+/// }
+///
+/// bb2: {
+///     _0 = const 1_u8;
+///     goto -> bb4;
+/// }
+///
+/// bb3: {
+///     unreachable;
+/// }
+/// ```
+///
+/// becomes
+///
+/// ```text
+/// bb0: {
+///     _2 = discriminant(_1);
+///     _0 = move _2 as u8 (Misc);
+///     goto -> bb4;
+/// }
+/// ```
+///
+/// The value leading to the `otherwise` target is usually unknown, so that target has to be
+/// unreachable. The exception is a switch on the discriminant of an enum with a single
+/// discriminant left over, which is what matches look like after `UnreachablePropagation`.
+pub struct MatchBranchSimplification;
+
+impl<'tcx> MirPass<'tcx> for MatchBranchSimplification {
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut Body<'tcx>) {
+        if tcx.sess.opts.debugging_opts.mir_opt_level < 2 {
+            return;
+        }
+
+        let param_env = tcx.param_env(source.def_id());
+        for bb in body.basic_blocks().indices() {
+            let (dest, rvalue, target) = match switch_as_cast(tcx, param_env, body, bb) {
+                Some(replacement) => replacement,
+                None => continue,
+            };
+            debug!("MatchBranchSimplification: replacing switch in {:?} with {:?}", bb, rvalue);
+
+            let data = &mut body.basic_blocks_mut()[bb];
+            let source_info = data.terminator().source_info;
+            data.statements.push(Statement {
+                source_info,
+                kind: StatementKind::Assign(box (dest, rvalue)),
+            });
+            data.terminator_mut().kind = TerminatorKind::Goto { target };
+        }
+    }
+}
+
+/// Checks whether the switch terminating `bb` can be replaced by a cast. If so, returns the place
+/// all arms assign to, the cast to assign to it instead and the block all arms jump to.
+fn switch_as_cast(
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    body: &Body<'tcx>,
+    bb: BasicBlock,
+) -> Option<(Place<'tcx>, Rvalue<'tcx>, BasicBlock)> {
+    let data = &body.basic_blocks()[bb];
+    let (discr, switch_ty, values, targets) = match &data.terminator().kind {
+        TerminatorKind::SwitchInt { discr, switch_ty, values, targets } => {
+            (discr, *switch_ty, values, targets)
+        }
+        _ => return None,
+    };
+    if !switch_ty.is_integral() {
+        return None;
+    }
+
+    let (&otherwise, targets) = targets.split_last().unwrap();
+    let mut arms: Vec<_> = values.iter().copied().zip(targets.iter().copied()).collect();
+    let otherwise_data = &body.basic_blocks()[otherwise];
+    let otherwise_is_unreachable = otherwise_data.terminator().kind == TerminatorKind::Unreachable
+        && otherwise_data.statements.iter().all(|stmt| stmt.kind == StatementKind::Nop);
+    if !otherwise_is_unreachable {
+        arms.push((otherwise_value(tcx, body, data, values)?, otherwise));
+    }
+
+    let switch_size = layout_size(tcx, param_env, switch_ty)?;
+    let mut common = None;
+    for (value, arm) in arms {
+        let (dest, constant, target) = assigned_constant(&body.basic_blocks()[arm])?;
+        if common.map_or(false, |(common_dest, common_target)| {
+            common_dest != dest || common_target != target
+        }) {
+            return None;
+        }
+        common = Some((dest, target));
+
+        // A cast between integers first sign- or zero-extends the value according to the source
+        // type, and then truncates it to the size of the destination type.
+        let dest_ty = dest.ty(body, tcx).ty;
+        if !dest_ty.is_integral() || constant.literal.ty != dest_ty {
+            return None;
+        }
+        let bits = constant.literal.try_eval_bits(tcx, param_env, dest_ty)?;
+        let value = if switch_ty.is_signed() { sign_extend(value, switch_size) } else { value };
+        if truncate(value, layout_size(tcx, param_env, dest_ty)?) != bits {
+            return None;
+        }
+    }
+
+    let (dest, target) = common?;
+    let dest_ty = dest.ty(body, tcx).ty;
+    let rvalue = if dest_ty == switch_ty {
+        Rvalue::Use(discr.clone())
+    } else {
+        Rvalue::Cast(CastKind::Misc, discr.clone(), dest_ty)
+    };
+    Some((dest, rvalue, target))
+}
+
+/// Returns the only value of a switch on an enum discriminant that leads to the `otherwise`
+/// target, if there is exactly one.
+fn otherwise_value(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    data: &BasicBlockData<'tcx>,
+    values: &[u128],
+) -> Option<u128> {
+    let discr_local = match &data.terminator().kind {
+        TerminatorKind::SwitchInt { discr: Operand::Move(discr), .. } => discr.as_local()?,
+        _ => return None,
+    };
+    let discriminated = match &data.statements.last()?.kind {
+        StatementKind::Assign(box (lhs, Rvalue::Discriminant(place)))
+            if lhs.as_local() == Some(discr_local) =>
+        {
+            place
+        }
+        _ => return None,
+    };
+
+    let adt = match discriminated.ty(body, tcx).ty.kind {
+        ty::Adt(adt, _) if adt.is_enum() => adt,
+        _ => return None,
+    };
+    let mut remaining =
+        adt.discriminants(tcx).map(|(_, discr)| discr.val).filter(|val| !values.contains(val));
+    let value = remaining.next()?;
+    if remaining.next().is_some() {
+        return None;
+    }
+    Some(value)
+}
+
+/// If `data` consists only of an assignment of a constant followed by a `goto`, returns the place
+/// assigned to, the constant and the target of the `goto`.
+fn assigned_constant<'a, 'tcx>(
+    data: &'a BasicBlockData<'tcx>,
+) -> Option<(Place<'tcx>, &'a Constant<'tcx>, BasicBlock)> {
+    let target = match data.terminator().kind {
+        TerminatorKind::Goto { target } => target,
+        _ => return None,
+    };
+    let mut statements = data.statements.iter().filter(|stmt| stmt.kind != StatementKind::Nop);
+    let (dest, constant) = match &statements.next()?.kind {
+        StatementKind::Assign(box (dest, Rvalue::Use(Operand::Constant(constant)))) => {
+            (*dest, &**constant)
+        }
+        _ => return None,
+    };
+    if statements.next().is_some() {
+        return None;
+    }
+    Some((dest, constant, target))
+}
+
+fn layout_size(tcx: TyCtxt<'tcx>, param_env: ParamEnv<'tcx>, ty: Ty<'tcx>) -> Option<Size> {
+    Some(tcx.layout_of(param_env.and(ty)).ok()?.size)
+}
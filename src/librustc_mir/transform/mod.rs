@@ -22,13 +22,17 @@ pub mod check_unsafety;
 pub mod cleanup_post_borrowck;
 pub mod const_prop;
 pub mod copy_prop;
+pub mod dataflow_const_prop;
 pub mod deaggregator;
+pub mod dest_prop;
 pub mod dump_mir;
 pub mod elaborate_drops;
 pub mod generator;
 pub mod inline;
 pub mod instcombine;
 pub mod instrument_coverage;
+pub mod jump_threading;
+pub mod match_branches;
 pub mod no_landing_pads;
 pub mod nrvo;
 pub mod promote_consts;
@@ -39,6 +43,7 @@ pub mod rustc_peek;
 pub mod simplify;
 pub mod simplify_branches;
 pub mod simplify_try;
+pub mod sroa;
 pub mod uninhabited_enum_branching;
 pub mod unreachable_prop;
 pub mod validate;
@@ -437,7 +442,9 @@ fn run_optimization_passes<'tcx>(
         // with async primitives.
         &generator::StateTransform,
         &instcombine::InstCombine,
+        &sroa::ScalarReplacementOfAggregates,
         &const_prop::ConstProp,
+        &dataflow_const_prop::DataflowConstProp,
         &simplify_branches::SimplifyBranches::new("after-const-prop"),
         // Run deaggregation here because:
         //   1. Some codegen backends require it
@@ -446,6 +453,9 @@ fn run_optimization_passes<'tcx>(
         &deaggregator::Deaggregator,
         &simplify_try::SimplifyArmIdentity,
         &simplify_try::SimplifyBranchSame,
+        &jump_threading::JumpThreading,
+        &match_branches::MatchBranchSimplification,
+        &dest_prop::DestinationPropagation,
         &copy_prop::CopyPropagation,
         &simplify_branches::SimplifyBranches::new("after-copy-prop"),
         &remove_noop_landing_pads::RemoveNoopLandingPads,
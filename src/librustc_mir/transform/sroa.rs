@@ -0,0 +1,308 @@
+//! Scalar replacement of aggregates.
+//!
+//! This pass splits locals of struct or tuple type into one local per field, when the aggregate is
+//! only ever accessed through its fields. For example, in
+//!
+//! ```text
+//! _3 = Foo { a: move _4, b: move _5 };
+//! _0 = (_3.1: u16);
+//! ```
+//!
+//! `_3` is replaced by two new locals `_6` and `_7`, giving
+//!
+//! ```text
+//! _6 = move _4;
+//! _7 = move _5;
+//! _0 = _7;
+//! ```
+//!
+//! Working on individual scalars lets later passes like `ConstProp` and `SimplifyLocals` see
+//! through aggregates, and lets LLVM keep the fields in registers instead of a stack slot.
+//!
+//! A local is not split if any of these hold:
+//!
+//! * It is the return place or an argument, since its layout is visible to the caller.
+//! * Its type is not a struct or a tuple. Unions and enums can't be split without knowing which
+//!   fields or variant are active, and arrays are indexed dynamically.
+//! * Its address is taken (or it is dropped, which also takes its address), since the relative
+//!   position of its fields is then observable. This uses the `MaybeBorrowedLocals` analysis.
+//! * It is used as a whole anywhere other than in a `StorageLive`/`StorageDead` statement or as
+//!   the destination of an aggregate assignment.
+//! * Debuginfo is enabled and a user variable refers to the local as a whole, since debuginfo
+//!   can't describe a variable spread across several locals. When debuginfo is disabled, these
+//!   entries are removed instead.
+
+use crate::dataflow::impls::ever_borrowed_locals;
+use crate::transform::{MirPass, MirSource};
+use rustc_hir::def_id::DefId;
+use rustc_index::bit_set::BitSet;
+use rustc_index::vec::IndexVec;
+use rustc_middle::mir::visit::{MutVisitor, PlaceContext, Visitor};
+use rustc_middle::mir::*;
+use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_session::config::DebugInfo;
+
+pub struct ScalarReplacementOfAggregates;
+
+impl<'tcx> MirPass<'tcx> for ScalarReplacementOfAggregates {
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut Body<'tcx>) {
+        if tcx.sess.opts.debugging_opts.mir_opt_level < 2 {
+            return;
+        }
+
+        let keep_debuginfo = tcx.sess.opts.debuginfo != DebugInfo::None;
+        let escaping = escaping_locals(tcx, body, source.def_id(), keep_debuginfo);
+        debug!("escaping locals: {:?}", escaping);
+
+        let fragments = compute_flattening(tcx, body, &escaping);
+        if fragments.iter().all(Option::is_none) {
+            return;
+        }
+        debug!("fragments: {:?}", fragments);
+
+        replace_flattened_locals(tcx, body, fragments);
+    }
+}
+
+/// Returns the locals that must be kept as a whole, according to the rules in the module docs.
+fn escaping_locals(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    def_id: DefId,
+    keep_debuginfo: bool,
+) -> BitSet<Local> {
+    let mut escaping = ever_borrowed_locals(tcx, body, def_id);
+    for (local, decl) in body.local_decls.iter_enumerated() {
+        let is_splittable_ty = match decl.ty.kind {
+            ty::Adt(def, _) => def.is_struct(),
+            ty::Tuple(_) => true,
+            _ => false,
+        };
+        let is_visible_to_caller =
+            matches!(body.local_kind(local), LocalKind::Arg | LocalKind::ReturnPointer);
+        if is_visible_to_caller || !is_splittable_ty {
+            escaping.insert(local);
+        }
+    }
+
+    let mut visitor = EscapeVisitor { escaping, keep_debuginfo };
+    visitor.visit_body(body);
+    visitor.escaping
+}
+
+struct EscapeVisitor {
+    escaping: BitSet<Local>,
+    keep_debuginfo: bool,
+}
+
+impl<'tcx> Visitor<'tcx> for EscapeVisitor {
+    fn visit_local(&mut self, local: &Local, _: PlaceContext, _: Location) {
+        // Any use of a local that doesn't go through `visit_place` below uses it as a whole.
+        self.escaping.insert(*local);
+    }
+
+    fn visit_place(&mut self, place: &Place<'tcx>, context: PlaceContext, location: Location) {
+        // Field projections get rewritten into uses of the field's own local. The remaining
+        // projections can only mention `usize` locals in `Index`, which are never split.
+        if let [PlaceElem::Field(..), ..] = place.projection[..] {
+            return;
+        }
+        self.super_place(place, context, location);
+    }
+
+    fn visit_assign(&mut self, place: &Place<'tcx>, rvalue: &Rvalue<'tcx>, location: Location) {
+        // Aggregates assigned to a whole local are expanded into one assignment per field.
+        if place.as_local().is_some() && matches!(rvalue, Rvalue::Aggregate(..)) {
+            self.visit_rvalue(rvalue, location);
+            return;
+        }
+        self.super_assign(place, rvalue, location);
+    }
+
+    fn visit_statement(&mut self, statement: &Statement<'tcx>, location: Location) {
+        match statement.kind {
+            // Expanded into one storage statement per field.
+            StatementKind::StorageLive(_) | StatementKind::StorageDead(_) => {}
+            _ => self.super_statement(statement, location),
+        }
+    }
+
+    fn visit_var_debug_info(&mut self, var_debug_info: &VarDebugInfo<'tcx>) {
+        if self.keep_debuginfo {
+            self.super_var_debug_info(var_debug_info);
+        }
+    }
+}
+
+/// Creates the locals replacing the fields of every local that isn't in `escaping`.
+///
+/// Only fields that are actually used get a local, since the others don't need any storage.
+/// Returns a map from each split local to the locals of its fields.
+fn compute_flattening(
+    tcx: TyCtxt<'tcx>,
+    body: &mut Body<'tcx>,
+    escaping: &BitSet<Local>,
+) -> IndexVec<Local, Option<IndexVec<Field, Option<Local>>>> {
+    let mut collector = FieldCollector {
+        tcx,
+        escaping,
+        field_tys: IndexVec::from_elem(None, &body.local_decls),
+        local_decls: &body.local_decls,
+    };
+    collector.visit_body(body);
+    let field_tys = collector.field_tys;
+
+    let mut fragments = IndexVec::from_elem(None, &body.local_decls);
+    for (local, field_tys) in field_tys.into_iter_enumerated() {
+        let field_tys = match field_tys {
+            Some(field_tys) => field_tys,
+            None => continue,
+        };
+        let source_info = body.local_decls[local].source_info;
+        fragments[local] = Some(
+            field_tys
+                .into_iter()
+                .map(|ty| {
+                    ty.map(|ty| body.local_decls.push(LocalDecl::with_source_info(ty, source_info)))
+                })
+                .collect(),
+        );
+    }
+    fragments
+}
+
+/// Records the type of every field of a non-escaping local that is read or written.
+struct FieldCollector<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    escaping: &'a BitSet<Local>,
+    field_tys: IndexVec<Local, Option<IndexVec<Field, Option<Ty<'tcx>>>>>,
+    local_decls: &'a IndexVec<Local, LocalDecl<'tcx>>,
+}
+
+impl FieldCollector<'_, 'tcx> {
+    fn record(&mut self, local: Local, field: Field, ty: Ty<'tcx>) {
+        if self.escaping.contains(local) {
+            return;
+        }
+        let field_tys = self.field_tys[local].get_or_insert_with(IndexVec::new);
+        field_tys.ensure_contains_elem(field, || None);
+        field_tys[field] = Some(ty);
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for FieldCollector<'_, 'tcx> {
+    fn visit_place(&mut self, place: &Place<'tcx>, context: PlaceContext, location: Location) {
+        if let [PlaceElem::Field(field, ty), ..] = place.projection[..] {
+            self.record(place.local, field, ty);
+        }
+        self.super_place(place, context, location);
+    }
+
+    fn visit_assign(&mut self, place: &Place<'tcx>, rvalue: &Rvalue<'tcx>, location: Location) {
+        if let (Some(local), Rvalue::Aggregate(_, operands)) = (place.as_local(), rvalue) {
+            for (i, operand) in operands.iter().enumerate() {
+                let ty = operand.ty(self.local_decls, self.tcx);
+                self.record(local, Field::new(i), ty);
+            }
+        }
+        self.super_assign(place, rvalue, location);
+    }
+}
+
+/// Rewrites `body` so that the split locals in `fragments` are no longer mentioned.
+fn replace_flattened_locals(
+    tcx: TyCtxt<'tcx>,
+    body: &mut Body<'tcx>,
+    fragments: IndexVec<Local, Option<IndexVec<Field, Option<Local>>>>,
+) {
+    let fields_of = |local: Local| {
+        fragments[local].iter().flat_map(|fields| fields.iter().filter_map(|field| *field))
+    };
+
+    for bb in body.basic_blocks_mut() {
+        bb.expand_statements(|stmt| {
+            let source_info = stmt.source_info;
+            let statements: Vec<_> = match stmt.kind {
+                StatementKind::StorageLive(local) if fragments[local].is_some() => {
+                    fields_of(local)
+                        .map(|field| Statement {
+                            source_info,
+                            kind: StatementKind::StorageLive(field),
+                        })
+                        .collect()
+                }
+                StatementKind::StorageDead(local) if fragments[local].is_some() => {
+                    fields_of(local)
+                        .map(|field| Statement {
+                            source_info,
+                            kind: StatementKind::StorageDead(field),
+                        })
+                        .collect()
+                }
+                StatementKind::Assign(box (place, Rvalue::Aggregate(..)))
+                    if place.as_local().map_or(false, |local| fragments[local].is_some()) =>
+                {
+                    let fields = fragments[place.local].as_ref().unwrap();
+                    let operands = match stmt.replace_nop().kind {
+                        StatementKind::Assign(box (_, Rvalue::Aggregate(_, operands))) => operands,
+                        _ => bug!(),
+                    };
+                    operands
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, operand)| Statement {
+                            source_info,
+                            kind: StatementKind::Assign(box (
+                                Place::from(fields[Field::new(i)].unwrap()),
+                                Rvalue::Use(operand),
+                            )),
+                        })
+                        .collect()
+                }
+                _ => return None,
+            };
+            Some(statements.into_iter())
+        });
+    }
+
+    // Debuginfo referring to a split local as a whole only survives `escaping_locals` if it isn't
+    // going to be emitted.
+    body.var_debug_info.retain(|var_debug_info| {
+        let place = var_debug_info.place;
+        !place.projection.is_empty() || fragments[place.local].is_none()
+    });
+
+    Replacer { tcx, fragments: &fragments }.visit_body(body);
+}
+
+struct Replacer<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    fragments: &'a IndexVec<Local, Option<IndexVec<Field, Option<Local>>>>,
+}
+
+impl<'tcx> MutVisitor<'tcx> for Replacer<'_, 'tcx> {
+    fn tcx<'a>(&'a self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_place(&mut self, place: &mut Place<'tcx>, context: PlaceContext, location: Location) {
+        if let Some(fields) = &self.fragments[place.local] {
+            if let [PlaceElem::Field(field, _), ref rest @ ..] = place.projection[..] {
+                let new_place = Place {
+                    local: fields[field].unwrap(),
+                    projection: self.tcx.intern_place_elems(rest),
+                };
+                debug!("Replacer: {:?} -> {:?}", place, new_place);
+                *place = new_place;
+            } else {
+                bug!(
+                    "split local {:?} used as a whole in {:?} at {:?}",
+                    place.local,
+                    place,
+                    location,
+                );
+            }
+        }
+        self.super_place(place, context, location);
+    }
+}
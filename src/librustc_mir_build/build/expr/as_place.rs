@@ -2,8 +2,10 @@
 
 use crate::build::expr::category::Category;
 use crate::build::ForGuard::{OutsideGuard, RefWithinGuard};
-use crate::build::{BlockAnd, BlockAndExtension, Builder};
+use crate::build::{BlockAnd, BlockAndExtension, Builder, CapturedUpvar};
 use crate::thir::*;
+use rustc_hir as hir;
+use rustc_middle::hir::place::ProjectionKind as HirProjectionKind;
 use rustc_middle::middle::region;
 use rustc_middle::mir::AssertKind::BoundsCheck;
 use rustc_middle::mir::*;
@@ -20,13 +22,67 @@ use rustc_index::vec::Idx;
 /// and `c` can be progressively pushed onto the place builder that is created when converting `a`.
 #[derive(Clone)]
 struct PlaceBuilder<'tcx> {
-    local: Local,
+    base: PlaceBase,
     projection: Vec<PlaceElem<'tcx>>,
 }
 
+/// The base of a `PlaceBuilder`.
+#[derive(Copy, Clone, Debug)]
+enum PlaceBase {
+    /// A local of the body being built.
+    Local(Local),
+
+    /// A variable captured by a closure that captures places rather than whole variables.
+    ///
+    /// The closure environment only contains the captured places, so the local that this is
+    /// based on can't be known until all of the projections applied to the variable have been
+    /// pushed onto the builder. See `PlaceBuilder::resolve_upvar`.
+    Upvar(hir::HirId),
+}
+
 impl<'tcx> PlaceBuilder<'tcx> {
-    fn into_place(self, tcx: TyCtxt<'tcx>) -> Place<'tcx> {
-        Place { local: self.local, projection: tcx.intern_place_elems(&self.projection) }
+    fn into_place(self, tcx: TyCtxt<'tcx>, captured_upvars: &[CapturedUpvar<'tcx>]) -> Place<'tcx> {
+        let resolved = self.resolve_upvar(captured_upvars);
+        let local = resolved.expect_local();
+        Place { local, projection: tcx.intern_place_elems(&resolved.projection) }
+    }
+
+    /// Replaces an upvar base with the place in the closure environment that holds the captured
+    /// place this builder refers to, keeping any projections applied beyond the captured place.
+    fn resolve_upvar(self, captured_upvars: &[CapturedUpvar<'tcx>]) -> Self {
+        let var_hir_id = match self.base {
+            PlaceBase::Local(_) => return self,
+            PlaceBase::Upvar(var_hir_id) => var_hir_id,
+        };
+
+        let captured = captured_upvars.iter().find(|captured| {
+            captured.var_hir_id == var_hir_id
+                && captured.projections.len() <= self.projection.len()
+                && captured.projections.iter().zip(self.projection.iter()).all(|(hir_proj, elem)| {
+                    match (hir_proj, elem) {
+                        (HirProjectionKind::Deref, ProjectionElem::Deref) => true,
+                        (HirProjectionKind::Field(idx, _), ProjectionElem::Field(field, _)) => {
+                            field.index() == *idx as usize
+                        }
+                        _ => false,
+                    }
+                })
+        });
+        let captured = match captured {
+            Some(captured) => captured,
+            None => bug!("no captured place for upvar {:?} with {:?}", var_hir_id, self.projection),
+        };
+
+        let mut projection = captured.env_place.projection.to_vec();
+        projection.extend_from_slice(&self.projection[captured.projections.len()..]);
+        PlaceBuilder { base: PlaceBase::Local(captured.env_place.local), projection }
+    }
+
+    fn expect_local(&self) -> Local {
+        match self.base {
+            PlaceBase::Local(local) => local,
+            PlaceBase::Upvar(var_hir_id) => bug!("unresolved upvar {:?}", var_hir_id),
+        }
     }
 
     fn field(self, f: Field, ty: Ty<'tcx>) -> Self {
@@ -49,7 +105,13 @@ impl<'tcx> PlaceBuilder<'tcx> {
 
 impl<'tcx> From<Local> for PlaceBuilder<'tcx> {
     fn from(local: Local) -> Self {
-        Self { local, projection: Vec::new() }
+        Self { base: PlaceBase::Local(local), projection: Vec::new() }
+    }
+}
+
+impl<'tcx> From<PlaceBase> for PlaceBuilder<'tcx> {
+    fn from(base: PlaceBase) -> Self {
+        Self { base, projection: Vec::new() }
     }
 }
 
@@ -71,7 +133,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
         M: Mirror<'tcx, Output = Expr<'tcx>>,
     {
         let place_builder = unpack!(block = self.as_place_builder(block, expr));
-        block.and(place_builder.into_place(self.hir.tcx()))
+        block.and(place_builder.into_place(self.hir.tcx(), &self.captured_upvars))
     }
 
     /// This is used when constructing a compound `Place`, so that we can avoid creating
@@ -98,7 +160,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
         M: Mirror<'tcx, Output = Expr<'tcx>>,
     {
         let place_builder = unpack!(block = self.as_read_only_place_builder(block, expr));
-        block.and(place_builder.into_place(self.hir.tcx()))
+        block.and(place_builder.into_place(self.hir.tcx(), &self.captured_upvars))
     }
 
     /// This is used when constructing a compound `Place`, so that we can avoid creating
@@ -171,6 +233,9 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
                 };
                 block.and(place_builder)
             }
+            ExprKind::UpvarRef { var_hir_id } => {
+                block.and(PlaceBuilder::from(PlaceBase::Upvar(var_hir_id)))
+            }
 
             ExprKind::PlaceTypeAscription { source, user_ty } => {
                 let source = this.hir.mirror(source);
@@ -185,7 +250,8 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
                             inferred_ty: expr.ty,
                         });
 
-                    let place = place_builder.clone().into_place(this.hir.tcx());
+                    let place =
+                        place_builder.clone().into_place(this.hir.tcx(), &this.captured_upvars);
                     this.cfg.push(
                         block,
                         Statement {
@@ -299,6 +365,8 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
 
         let base_place =
             unpack!(block = self.expr_as_place(block, lhs, mutability, Some(fake_borrow_temps),));
+        // The fake borrows below need to know the local that the base place is based on.
+        let base_place = base_place.resolve_upvar(&self.captured_upvars);
 
         // Making this a *fresh* temporary means we do not have to worry about
         // the index changing later: Nothing will ever change this temporary.
@@ -307,7 +375,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
 
         block = self.bounds_check(
             block,
-            base_place.clone().into_place(self.hir.tcx()),
+            base_place.clone().into_place(self.hir.tcx(), &self.captured_upvars),
             idx,
             expr_span,
             source_info,
@@ -365,8 +433,8 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
         source_info: SourceInfo,
     ) {
         let tcx = self.hir.tcx();
-        let place_ty =
-            Place::ty_from(base_place.local, &base_place.projection, &self.local_decls, tcx);
+        let local = base_place.expect_local();
+        let place_ty = Place::ty_from(local, &base_place.projection, &self.local_decls, tcx);
         if let ty::Slice(_) = place_ty.ty.kind {
             // We need to create fake borrows to ensure that the bounds
             // check that we just did stays valid. Since we can't assign to
@@ -376,7 +444,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
                 match elem {
                     ProjectionElem::Deref => {
                         let fake_borrow_deref_ty = Place::ty_from(
-                            base_place.local,
+                            local,
                             &base_place.projection[..idx],
                             &self.local_decls,
                             tcx,
@@ -394,14 +462,14 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
                             Rvalue::Ref(
                                 tcx.lifetimes.re_erased,
                                 BorrowKind::Shallow,
-                                Place { local: base_place.local, projection },
+                                Place { local, projection },
                             ),
                         );
                         fake_borrow_temps.push(fake_borrow_temp);
                     }
                     ProjectionElem::Index(_) => {
                         let index_ty = Place::ty_from(
-                            base_place.local,
+                            local,
                             &base_place.projection[..idx],
                             &self.local_decls,
                             tcx,
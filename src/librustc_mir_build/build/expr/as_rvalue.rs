@@ -174,6 +174,10 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
                 block.and(Rvalue::Aggregate(box AggregateKind::Tuple, fields))
             }
             ExprKind::Closure { closure_id, substs, upvars, movability } => {
+                // Closures that capture places rather than whole variables already had the
+                // mutability of their borrows limited when the captures were lowered to THIR.
+                let captures_places =
+                    this.hir.typeck_results().closure_min_captures.contains_key(&closure_id);
                 // see (*) above
                 let operands: Vec<_> = upvars
                     .into_iter()
@@ -198,6 +202,9 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
                                 // variable. This is sound because the mutation
                                 // that caused the capture will cause an error.
                                 match upvar.kind {
+                                    ExprKind::Borrow { .. } if captures_places => {
+                                        unpack!(block = this.as_operand(block, scope, upvar))
+                                    }
                                     ExprKind::Borrow {
                                         borrow_kind:
                                             BorrowKind::Mut { allow_two_phase_borrow: false },
@@ -249,6 +256,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
             | ExprKind::Deref { .. }
             | ExprKind::Index { .. }
             | ExprKind::VarRef { .. }
+            | ExprKind::UpvarRef { .. }
             | ExprKind::SelfRef
             | ExprKind::Break { .. }
             | ExprKind::Continue { .. }
@@ -10,12 +10,13 @@ use rustc_hir::lang_items;
 use rustc_hir::{GeneratorKind, HirIdMap, Node};
 use rustc_index::vec::{Idx, IndexVec};
 use rustc_infer::infer::TyCtxtInferExt;
+use rustc_middle::hir::place::ProjectionKind as HirProjectionKind;
 use rustc_middle::middle::codegen_fn_attrs::CodegenFnAttrFlags;
 use rustc_middle::middle::region;
 use rustc_middle::mir::*;
 use rustc_middle::ty::subst::Subst;
 use rustc_middle::ty::{self, Ty, TyCtxt, TypeFoldable};
-use rustc_span::symbol::kw;
+use rustc_span::symbol::{kw, Symbol};
 use rustc_span::Span;
 use rustc_target::spec::abi::Abi;
 use rustc_target::spec::PanicStrategy;
@@ -331,6 +332,9 @@ struct Builder<'a, 'tcx> {
     local_decls: IndexVec<Local, LocalDecl<'tcx>>,
     canonical_user_type_annotations: ty::CanonicalUserTypeAnnotations<'tcx>,
     upvar_mutbls: Vec<Mutability>,
+    /// The places captured by a closure that captures places rather than whole variables,
+    /// in the order of the fields of the closure environment.
+    captured_upvars: Vec<CapturedUpvar<'tcx>>,
     unit_temp: Option<Place<'tcx>>,
 
     var_debug_info: Vec<VarDebugInfo<'tcx>>,
@@ -432,6 +436,16 @@ enum LocalsForNode {
 }
 
 #[derive(Debug)]
+/// A place captured by the closure whose body is being built, see `Builder::captured_upvars`.
+struct CapturedUpvar<'tcx> {
+    /// The root variable of the captured place.
+    var_hir_id: hir::HirId,
+    /// The projections applied to the root variable to get to the captured place.
+    projections: Vec<HirProjectionKind>,
+    /// The place in the closure environment holding the captured place.
+    env_place: Place<'tcx>,
+}
+
 struct GuardFrameLocal {
     id: hir::HirId,
 }
@@ -534,7 +548,7 @@ macro_rules! unpack {
     }};
 }
 
-fn should_abort_on_panic(tcx: TyCtxt<'_>, fn_def_id: LocalDefId, _abi: Abi) -> bool {
+fn should_abort_on_panic(tcx: TyCtxt<'_>, fn_def_id: LocalDefId, abi: Abi) -> bool {
     // Validate `#[unwind]` syntax regardless of platform-specific panic strategy.
     let attrs = &tcx.get_attrs(fn_def_id.to_def_id());
     let unwind_attr = attr::find_unwind_attr(&tcx.sess, attrs);
@@ -547,7 +561,14 @@ fn should_abort_on_panic(tcx: TyCtxt<'_>, fn_def_id: LocalDefId, _abi: Abi) -> b
     // This is a special case: some functions have a C abi but are meant to
     // unwind anyway. Don't stop them.
     match unwind_attr {
-        None => false, // FIXME(#58794); should be `!(abi == Abi::Rust || abi == Abi::RustCall)`
+        // With `#![feature(c_unwind)]`, a panic escaping a function with a non-Rust ABI that is
+        // not one of the `-unwind` ABIs (such as a plain `extern "C" fn`) aborts instead of
+        // unwinding into foreign frames.
+        // FIXME(#58794): do this unconditionally once the feature is stabilized.
+        None if tcx.features().c_unwind => {
+            !(abi == Abi::Rust || abi == Abi::RustCall || abi.is_unwind())
+        }
+        None => false,
         Some(UnwindAttr::Allowed) => false,
         Some(UnwindAttr::Aborts) => true,
     }
@@ -753,6 +774,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
             local_decls: IndexVec::from_elem_n(LocalDecl::new(return_ty, return_span), 1),
             canonical_user_type_annotations: IndexVec::new(),
             upvar_mutbls: vec![],
+            captured_upvars: vec![],
             var_indices: Default::default(),
             unit_temp: None,
             var_debug_info: vec![],
@@ -834,7 +856,50 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
         // with the closure's DefId. Here, we run through that vec of UpvarIds for
         // the given closure and use the necessary information to create upvar
         // debuginfo and to fill `self.upvar_mutbls`.
-        if let Some(upvars) = hir_typeck_results.closure_captures.get(&fn_def_id) {
+        //
+        // Closures that capture places rather than whole variables have their captures in
+        // `closure_min_captures` instead, and also fill `self.captured_upvars` so that uses of
+        // the captured places can be mapped to the closure environment.
+        if hir_typeck_results.closure_min_captures.contains_key(&fn_def_id) {
+            let closure_env_arg = Local::new(1);
+            let mut closure_env_projs = vec![];
+            let mut closure_ty = self.local_decls[closure_env_arg].ty;
+            if let ty::Ref(_, ty, _) = closure_ty.kind {
+                closure_env_projs.push(ProjectionElem::Deref);
+                closure_ty = ty;
+            }
+            let upvar_tys = match closure_ty.kind {
+                ty::Closure(_, substs) => substs.as_closure().upvar_tys(),
+                _ => span_bug!(self.fn_span, "captures with non-closure env ty {:?}", closure_ty),
+            };
+            let captures_with_tys =
+                hir_typeck_results.closure_min_captures_flattened(fn_def_id).zip(upvar_tys);
+            for (i, (captured, ty)) in captures_with_tys.enumerate() {
+                let mut projs = closure_env_projs.clone();
+                projs.push(ProjectionElem::Field(Field::new(i), ty));
+                match captured.capture_kind {
+                    ty::UpvarCapture::ByValue => {}
+                    ty::UpvarCapture::ByRef(..) => {
+                        projs.push(ProjectionElem::Deref);
+                    }
+                };
+                let env_place =
+                    Place { local: closure_env_arg, projection: tcx.intern_place_elems(&projs) };
+
+                let var_hir_id = captured.get_root_variable();
+                self.var_debug_info.push(VarDebugInfo {
+                    name: Symbol::intern(&captured.to_string(tcx)),
+                    source_info: SourceInfo::outermost(tcx_hir.span(var_hir_id)),
+                    place: env_place,
+                });
+                self.upvar_mutbls.push(captured.mutability);
+                self.captured_upvars.push(CapturedUpvar {
+                    var_hir_id,
+                    projections: captured.place.projections.iter().map(|proj| proj.kind).collect(),
+                    env_place,
+                });
+            }
+        } else if let Some(upvars) = hir_typeck_results.closure_captures.get(&fn_def_id) {
             let closure_env_arg = Local::new(1);
             let mut closure_env_projs = vec![];
             let mut closure_ty = self.local_decls[closure_env_arg].ty;
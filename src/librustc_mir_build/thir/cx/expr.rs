@@ -6,6 +6,7 @@ use crate::thir::*;
 use rustc_hir as hir;
 use rustc_hir::def::{CtorKind, CtorOf, DefKind, Res};
 use rustc_index::vec::Idx;
+use rustc_middle::hir::place::ProjectionKind as HirProjectionKind;
 use rustc_middle::mir::interpret::Scalar;
 use rustc_middle::mir::BorrowKind;
 use rustc_middle::ty::adjustment::{
@@ -249,6 +250,14 @@ fn make_mirror_unadjusted<'a, 'tcx>(
             user_ty: None,
         },
 
+        hir::ExprKind::ConstBlock(ref anon_const) => {
+            // Inline constants are evaluated with the generics of the enclosing item, so the
+            // unevaluated constant is kept as-is until monomorphization.
+            let anon_const_def_id = cx.tcx.hir().local_def_id(anon_const.hir_id);
+            let literal = ty::Const::from_anon_const(cx.tcx, anon_const_def_id);
+            ExprKind::Literal { literal, user_ty: None }
+        }
+
         hir::ExprKind::Binary(op, ref lhs, ref rhs) => {
             if cx.typeck_results().is_method_call(expr) {
                 overloaded_operator(cx, expr, vec![lhs.to_ref(), rhs.to_ref()])
@@ -372,14 +381,21 @@ fn make_mirror_unadjusted<'a, 'tcx>(
                     span_bug!(expr.span, "closure expr w/o closure type: {:?}", closure_ty);
                 }
             };
-            let upvars = cx
-                .tcx
-                .upvars_mentioned(def_id)
-                .iter()
-                .flat_map(|upvars| upvars.iter())
-                .zip(substs.upvar_tys())
-                .map(|((&var_hir_id, _), ty)| capture_upvar(cx, expr, var_hir_id, ty))
-                .collect();
+            let upvars = if cx.typeck_results().closure_min_captures.contains_key(&def_id) {
+                cx.typeck_results()
+                    .closure_min_captures_flattened(def_id)
+                    .zip(substs.upvar_tys())
+                    .map(|(captured_place, ty)| capture_upvar_place(cx, expr, captured_place, ty))
+                    .collect()
+            } else {
+                cx.tcx
+                    .upvars_mentioned(def_id)
+                    .iter()
+                    .flat_map(|upvars| upvars.iter())
+                    .zip(substs.upvar_tys())
+                    .map(|((&var_hir_id, _), ty)| capture_upvar(cx, expr, var_hir_id, ty))
+                    .collect()
+            };
             ExprKind::Closure { closure_id: def_id, substs, upvars, movability }
         }
 
@@ -751,6 +767,9 @@ fn convert_arm<'tcx>(cx: &mut Cx<'_, 'tcx>, arm: &'tcx hir::Arm<'tcx>) -> Arm<'t
         pattern: cx.pattern_from_hir(&arm.pat),
         guard: match arm.guard {
             Some(hir::Guard::If(ref e)) => Some(Guard::If(e.to_ref())),
+            Some(hir::Guard::IfLet(ref pat, ref e)) => {
+                Some(Guard::IfLet(cx.pattern_from_hir(pat), e.to_ref()))
+            }
             _ => None,
         },
         body: arm.body.to_ref(),
@@ -877,6 +896,10 @@ fn convert_var<'tcx>(
     match upvar_index {
         None => ExprKind::VarRef { id: var_hir_id },
 
+        Some(_) if cx.typeck_results().closure_min_captures.contains_key(&cx.body_owner) => {
+            ExprKind::UpvarRef { var_hir_id }
+        }
+
         Some(upvar_index) => {
             let closure_def_id = cx.body_owner;
             let upvar_id = ty::UpvarId {
@@ -1092,6 +1115,59 @@ fn capture_upvar<'tcx>(
     }
 }
 
+/// Like `capture_upvar`, for closures that capture places rather than whole variables.
+fn capture_upvar_place<'tcx>(
+    cx: &mut Cx<'_, 'tcx>,
+    closure_expr: &'tcx hir::Expr<'tcx>,
+    captured_place: &ty::CapturedPlace<'tcx>,
+    upvar_ty: Ty<'tcx>,
+) -> ExprRef<'tcx> {
+    let temp_lifetime = cx.region_scope_tree.temporary_scope(closure_expr.hir_id.local_id);
+    let var_hir_id = captured_place.get_root_variable();
+    let mut captured_place_expr = Expr {
+        temp_lifetime,
+        ty: captured_place.place.base_ty,
+        span: closure_expr.span,
+        kind: convert_var(cx, closure_expr, var_hir_id),
+    };
+    for proj in captured_place.place.projections.iter() {
+        let kind = match proj.kind {
+            HirProjectionKind::Deref => ExprKind::Deref { arg: captured_place_expr.to_ref() },
+            HirProjectionKind::Field(field, _) => ExprKind::Field {
+                lhs: captured_place_expr.to_ref(),
+                name: Field::new(field as usize),
+            },
+            HirProjectionKind::Index | HirProjectionKind::Subslice => {
+                span_bug!(closure_expr.span, "unexpected projection {:?} in captured place", proj)
+            }
+        };
+        captured_place_expr = Expr { temp_lifetime, ty: proj.ty, span: closure_expr.span, kind };
+    }
+
+    match captured_place.capture_kind {
+        ty::UpvarCapture::ByValue => captured_place_expr.to_ref(),
+        ty::UpvarCapture::ByRef(upvar_borrow) => {
+            let borrow_kind = match upvar_borrow.kind {
+                ty::BorrowKind::ImmBorrow => BorrowKind::Shared,
+                ty::BorrowKind::UniqueImmBorrow => BorrowKind::Unique,
+                // As in `limit_capture_mutability`, a place that can't be mutated is
+                // uniquely borrowed instead, leaving the error to the mutation itself.
+                ty::BorrowKind::MutBorrow => match captured_place.mutability {
+                    hir::Mutability::Mut => BorrowKind::Mut { allow_two_phase_borrow: false },
+                    hir::Mutability::Not => BorrowKind::Unique,
+                },
+            };
+            Expr {
+                temp_lifetime,
+                ty: upvar_ty,
+                span: closure_expr.span,
+                kind: ExprKind::Borrow { borrow_kind, arg: captured_place_expr.to_ref() },
+            }
+            .to_ref()
+        }
+    }
+}
+
 /// Converts a list of named fields (i.e., for struct-like struct/enum ADTs) into FieldExprRef.
 fn field_refs<'a, 'tcx>(
     cx: &mut Cx<'a, 'tcx>,
@@ -211,6 +211,14 @@ crate enum ExprKind<'tcx> {
     VarRef {
         id: hir::HirId,
     },
+    /// A variable captured by the closure whose body is being built, when the
+    /// closure captures places rather than whole variables (see
+    /// `capture_disjoint_fields`). Which captured place, and so which field of
+    /// the closure environment, is used depends on the projections applied to
+    /// the variable, so this is only resolved when building the place.
+    UpvarRef {
+        var_hir_id: hir::HirId,
+    },
     /// first argument, used for self in a closure
     SelfRef,
     Borrow {
@@ -331,6 +339,7 @@ crate struct Arm<'tcx> {
 #[derive(Clone, Debug)]
 crate enum Guard<'tcx> {
     If(ExprRef<'tcx>),
+    IfLet(Pat<'tcx>, ExprRef<'tcx>),
 }
 
 #[derive(Copy, Clone, Debug)]
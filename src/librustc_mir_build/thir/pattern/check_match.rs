@@ -64,13 +64,14 @@ impl<'tcx> Visitor<'tcx> for MatchVisitor<'_, 'tcx> {
     fn visit_local(&mut self, loc: &'tcx hir::Local<'tcx>) {
         intravisit::walk_local(self, loc);
 
-        let (msg, sp) = match loc.source {
-            hir::LocalSource::Normal => ("local binding", Some(loc.span)),
+        let (msg, local) = match loc.source {
+            hir::LocalSource::Normal => ("local binding", Some(loc)),
             hir::LocalSource::ForLoopDesugar => ("`for` loop binding", None),
             hir::LocalSource::AsyncFn => ("async fn binding", None),
             hir::LocalSource::AwaitDesugar => ("`await` future binding", None),
+            hir::LocalSource::AssignDesugar(_) => ("destructuring assignment binding", None),
         };
-        self.check_irrefutable(&loc.pat, msg, sp);
+        self.check_irrefutable(&loc.pat, msg, local);
         self.check_patterns(false, &loc.pat);
     }
 
@@ -166,6 +167,10 @@ impl<'tcx> MatchVisitor<'_, 'tcx> {
         for arm in arms {
             // Check the arm for some things unrelated to exhaustiveness.
             self.check_patterns(arm.guard.is_some(), &arm.pat);
+            if let Some(hir::Guard::IfLet(ref pat, _)) = arm.guard {
+                self.check_patterns(false, pat);
+                self.check_if_let_guard(pat);
+            }
         }
 
         let mut cx = self.new_cx(scrut.hir_id);
@@ -195,7 +200,28 @@ impl<'tcx> MatchVisitor<'_, 'tcx> {
         check_exhaustive(&mut cx, scrut_ty, scrut.span, &matrix, scrut.hir_id, is_empty_match);
     }
 
-    fn check_irrefutable(&self, pat: &'tcx Pat<'tcx>, origin: &str, sp: Option<Span>) {
+    /// Lints on `if let` guards whose pattern always matches.
+    fn check_if_let_guard(&self, pat: &'tcx Pat<'tcx>) {
+        let mut cx = self.new_cx(pat.hir_id);
+        let mut have_errors = false;
+        let (pattern, pattern_ty) = self.lower_pattern(&mut cx, pat, &mut have_errors);
+        if have_errors {
+            return;
+        }
+        let pats: Matrix<'_, '_> = vec![PatStack::from_pattern(pattern)].into_iter().collect();
+        if check_not_useful(&mut cx, pattern_ty, &pats, pat.hir_id).is_ok() {
+            self.tcx.struct_span_lint_hir(IRREFUTABLE_LET_PATTERNS, pat.hir_id, pat.span, |lint| {
+                lint.build("irrefutable if-let guard").emit()
+            });
+        }
+    }
+
+    fn check_irrefutable(
+        &self,
+        pat: &'tcx Pat<'tcx>,
+        origin: &str,
+        local: Option<&'tcx hir::Local<'tcx>>,
+    ) {
         let mut cx = self.new_cx(pat.hir_id);
 
         let (pattern, pattern_ty) = self.lower_pattern(&mut cx, pat, &mut false);
@@ -228,7 +254,8 @@ impl<'tcx> MatchVisitor<'_, 'tcx> {
             }
         };
 
-        if let (Some(span), true) = (sp, suggest_if_let) {
+        if let (Some(local), true) = (local, suggest_if_let) {
+            let span = local.span;
             err.note(
                 "`let` bindings require an \"irrefutable pattern\", like a `struct` or \
                  an `enum` with only one variant",
@@ -240,6 +267,15 @@ impl<'tcx> MatchVisitor<'_, 'tcx> {
                     format!("if {} {{ /* */ }}", &snippet[..snippet.len() - 1]),
                     Applicability::HasPlaceholders,
                 );
+                if local.init.is_some() && self.tcx.features().let_else {
+                    err.span_suggestion(
+                        span,
+                        "you might want to use `let...else` to handle the variant that isn't \
+                         matched",
+                        format!("{} else {{ todo!() }};", &snippet[..snippet.len() - 1]),
+                        Applicability::HasPlaceholders,
+                    );
+                }
             }
             err.note(
                 "for more information, visit \
@@ -351,6 +387,7 @@ fn irrefutable_let_pattern(tcx: TyCtxt<'_>, span: Span, id: HirId, source: hir::
         let msg = match source {
             hir::MatchSource::IfLetDesugar { .. } => "irrefutable if-let pattern",
             hir::MatchSource::WhileLetDesugar => "irrefutable while-let pattern",
+            hir::MatchSource::LetElseDesugar => "irrefutable let...else pattern",
             _ => bug!(),
         };
         lint.build(msg).emit()
@@ -372,7 +409,9 @@ fn check_arms<'p, 'tcx>(
                 match source {
                     hir::MatchSource::IfDesugar { .. } | hir::MatchSource::WhileDesugar => bug!(),
 
-                    hir::MatchSource::IfLetDesugar { .. } | hir::MatchSource::WhileLetDesugar => {
+                    hir::MatchSource::IfLetDesugar { .. }
+                    | hir::MatchSource::WhileLetDesugar
+                    | hir::MatchSource::LetElseDesugar => {
                         // Check which arm we're on.
                         match arm_index {
                             // The arm with the user-specified pattern.
@@ -17,7 +17,8 @@ use log::debug;
 use rustc_ast::ast::DUMMY_NODE_ID;
 use rustc_ast::ast::{self, AttrStyle, AttrVec, Const, CrateSugar, Extern, Unsafe};
 use rustc_ast::ast::{
-    Async, MacArgs, MacDelimiter, Mutability, StrLit, Visibility, VisibilityKind,
+    AnonConst, Async, Expr, ExprKind, MacArgs, MacDelimiter, Mutability, StrLit, Visibility,
+    VisibilityKind,
 };
 use rustc_ast::ptr::P;
 use rustc_ast::token::{self, DelimToken, Token, TokenKind};
@@ -861,13 +862,33 @@ impl<'a> Parser<'a> {
 
     /// Parses constness: `const` or nothing.
     fn parse_constness(&mut self) -> Const {
-        if self.eat_keyword(kw::Const) {
+        // Avoid parsing inline `const` blocks as `const` items.
+        if !self.check_inline_const() && self.eat_keyword(kw::Const) {
             Const::Yes(self.prev_token.uninterpolated_span())
         } else {
             Const::No
         }
     }
 
+    /// Checks for an inline `const` block, i.e. `const` followed by `{`.
+    fn check_inline_const(&mut self) -> bool {
+        self.check_keyword(kw::Const)
+            && self.look_ahead(1, |t| t == &token::OpenDelim(DelimToken::Brace))
+    }
+
+    /// Parses an inline `const` block: `const { ... }`.
+    fn parse_const_block(&mut self, span: Span, attrs: AttrVec) -> PResult<'a, P<Expr>> {
+        self.sess.gated_spans.gate(sym::inline_const, span);
+        self.expect_keyword(kw::Const)?;
+        let blk = self.parse_block()?;
+        let anon_const = AnonConst {
+            id: DUMMY_NODE_ID,
+            value: self.mk_expr(blk.span, ExprKind::Block(blk, None), AttrVec::new()),
+        };
+        let span = span.to(self.prev_token.span);
+        Ok(self.mk_expr(span, ExprKind::ConstBlock(anon_const), attrs))
+    }
+
     /// Parses mutability (`mut` or nothing).
     fn parse_mutability(&mut self) -> Mutability {
         if self.eat_keyword(kw::Mut) { Mutability::Mut } else { Mutability::Not }
@@ -217,8 +217,46 @@ impl<'a> Parser<'a> {
                 return Err(err);
             }
         };
+        let els = if init.is_some() && self.eat_keyword(kw::Else) {
+            let else_span = self.prev_token.span;
+            if let Some(init) = &init {
+                self.check_let_else_init(init, else_span);
+            }
+            let els = self.parse_block()?;
+            self.sess.gated_spans.gate(sym::let_else, else_span.to(self.prev_token.span));
+            Some(els)
+        } else {
+            None
+        };
         let hi = if self.token == token::Semi { self.token.span } else { self.prev_token.span };
-        Ok(P(ast::Local { ty, pat, init, id: DUMMY_NODE_ID, span: lo.to(hi), attrs }))
+        Ok(P(ast::Local { ty, pat, init, els, id: DUMMY_NODE_ID, span: lo.to(hi), attrs }))
+    }
+
+    /// Rejects initializers of a `let...else` statement that would make the `else` ambiguous,
+    /// e.g., `let Some(x) = if a { b } else { c } else { return };`.
+    fn check_let_else_init(&self, init: &Expr, else_span: Span) {
+        let msg = match init.kind {
+            ExprKind::Binary(op, ..) if op.node.lazy() => format!(
+                "a `{}` expression cannot be directly assigned in `let...else`",
+                op.node.to_string()
+            ),
+            _ if !classify::expr_requires_semi_to_be_stmt(init) => {
+                "right curly brace `}` before `else` in a `let...else` statement not allowed"
+                    .to_string()
+            }
+            _ => return,
+        };
+        let mut err = self.struct_span_err(init.span, &msg);
+        err.span_label(else_span, "this `else` belongs to the `let...else` statement");
+        if let Ok(snippet) = self.span_to_snippet(init.span) {
+            err.span_suggestion(
+                init.span,
+                "wrap the expression in parentheses",
+                format!("({})", snippet),
+                Applicability::MachineApplicable,
+            );
+        }
+        err.emit();
     }
 
     /// Parses the RHS of a local variable declaration (e.g., '= 14;').
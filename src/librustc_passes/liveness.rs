@@ -452,6 +452,9 @@ fn visit_local<'tcx>(ir: &mut IrMaps<'tcx>, local: &'tcx hir::Local<'tcx>) {
 
 fn visit_arm<'tcx>(ir: &mut IrMaps<'tcx>, arm: &'tcx hir::Arm<'tcx>) {
     add_from_pat(ir, &arm.pat);
+    if let Some(hir::Guard::IfLet(ref pat, _)) = arm.guard {
+        add_from_pat(ir, pat);
+    }
     intravisit::walk_arm(ir, arm);
 }
 
@@ -503,6 +506,7 @@ fn visit_expr<'tcx>(ir: &mut IrMaps<'tcx>, expr: &'tcx Expr<'tcx>) {
         hir::ExprKind::Index(..)
         | hir::ExprKind::Field(..)
         | hir::ExprKind::Array(..)
+        | hir::ExprKind::ConstBlock(..)
         | hir::ExprKind::Call(..)
         | hir::ExprKind::MethodCall(..)
         | hir::ExprKind::Tup(..)
@@ -1093,10 +1097,13 @@ impl<'a, 'tcx> Liveness<'a, 'tcx> {
                 for arm in arms {
                     let body_succ = self.propagate_through_expr(&arm.body, succ);
 
-                    let guard_succ = self.propagate_through_opt_expr(
-                        arm.guard.as_ref().map(|hir::Guard::If(e)| *e),
-                        body_succ,
-                    );
+                    let guard_succ = arm.guard.as_ref().map_or(body_succ, |g| match g {
+                        hir::Guard::If(e) => self.propagate_through_expr(e, body_succ),
+                        hir::Guard::IfLet(pat, e) => {
+                            let let_bind = self.define_bindings_in_pat(pat, body_succ);
+                            self.propagate_through_expr(e, let_bind)
+                        }
+                    });
                     let arm_succ = self.define_bindings_in_pat(&arm.pat, guard_succ);
                     self.merge_from_succ(ln, arm_succ, first_merge);
                     first_merge = false;
@@ -1309,6 +1316,7 @@ impl<'a, 'tcx> Liveness<'a, 'tcx> {
             }
 
             hir::ExprKind::Lit(..)
+            | hir::ExprKind::ConstBlock(..)
             | hir::ExprKind::Err
             | hir::ExprKind::Path(hir::QPath::TypeRelative(..)) => succ,
 
@@ -1486,6 +1494,9 @@ impl<'a, 'tcx> Visitor<'tcx> for Liveness<'a, 'tcx> {
 
     fn visit_arm(&mut self, arm: &'tcx hir::Arm<'tcx>) {
         self.check_unused_vars_in_pat(&arm.pat, None, |_, _, _, _| {});
+        if let Some(hir::Guard::IfLet(ref pat, _)) = arm.guard {
+            self.check_unused_vars_in_pat(pat, None, |_, _, _, _| {});
+        }
         intravisit::walk_arm(self, arm);
     }
 }
@@ -1545,6 +1556,7 @@ fn check_expr<'tcx>(this: &mut Liveness<'_, 'tcx>, expr: &'tcx Expr<'tcx>) {
         | hir::ExprKind::Index(..)
         | hir::ExprKind::Field(..)
         | hir::ExprKind::Array(..)
+        | hir::ExprKind::ConstBlock(..)
         | hir::ExprKind::Tup(..)
         | hir::ExprKind::Binary(..)
         | hir::ExprKind::Cast(..)
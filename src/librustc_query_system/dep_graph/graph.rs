@@ -90,6 +90,10 @@ struct DepGraphData<K: DepKind> {
     previous_work_products: FxHashMap<WorkProductId, WorkProduct>,
 
     dep_node_debug: Lock<FxHashMap<DepNode<K>, String>>,
+
+    /// For every node of the previous graph that could not be marked green, the
+    /// red dependency that prevented it. Only filled in with `-Zincremental-explain`.
+    red_node_causes: Lock<FxHashMap<SerializedDepNodeIndex, SerializedDepNodeIndex>>,
 }
 
 pub fn hash_result<HashCtxt, R>(hcx: &mut HashCtxt, result: &R) -> Option<Fingerprint>
@@ -113,6 +117,7 @@ impl<K: DepKind> DepGraph<K> {
             data: Some(Lrc::new(DepGraphData {
                 previous_work_products: prev_work_products,
                 dep_node_debug: Default::default(),
+                red_node_causes: Default::default(),
                 current: CurrentDepGraph::new(prev_graph_node_count),
                 emitting_diagnostics: Default::default(),
                 emitting_diagnostics_cond_var: Condvar::new(),
@@ -398,6 +403,61 @@ impl<K: DepKind> DepGraph<K> {
         data[dep_node_index].fingerprint
     }
 
+    /// Returns a fingerprint of everything that the node at `dep_node_index`
+    /// read while it was computed. Nodes whose result is not hashed, such as
+    /// anonymous nodes, contribute what they read in turn. Unlike the node
+    /// indices, this fingerprint is the same in every session that computes
+    /// the node from the same inputs.
+    ///
+    /// Returns `None` if the node depends on an eval-always node whose result
+    /// is not hashed, since nothing is known about what that node computed.
+    pub fn input_fingerprint_of(&self, dep_node_index: DepNodeIndex) -> Option<Fingerprint> {
+        fn input_fingerprint<K: DepKind>(
+            data: &IndexVec<DepNodeIndex, DepNodeData<K>>,
+            dep_node_index: DepNodeIndex,
+            cache: &mut FxHashMap<DepNodeIndex, Option<Fingerprint>>,
+        ) -> Option<Fingerprint> {
+            if let Some(&fingerprint) = cache.get(&dep_node_index) {
+                return fingerprint;
+            }
+
+            let mut hasher = StableHasher::new();
+            let mut known = true;
+            for &edge in data[dep_node_index].edges.iter() {
+                let input = &data[edge];
+                if input.fingerprint != Fingerprint::ZERO {
+                    input.node.hash(&mut hasher);
+                    input.fingerprint.hash(&mut hasher);
+                } else if input.node.kind.is_eval_always() {
+                    known = false;
+                    break;
+                } else {
+                    // The hash of an anonymous node is mixed with a per
+                    // session seed, so only its kind is stable.
+                    if input.node.kind.is_anon() {
+                        input.node.kind.hash(&mut hasher);
+                    } else {
+                        input.node.hash(&mut hasher);
+                    }
+                    match input_fingerprint(data, edge, cache) {
+                        Some(fingerprint) => fingerprint.hash(&mut hasher),
+                        None => {
+                            known = false;
+                            break;
+                        }
+                    }
+                }
+            }
+
+            let fingerprint = if known { Some(hasher.finish()) } else { None };
+            cache.insert(dep_node_index, fingerprint);
+            fingerprint
+        }
+
+        let data = self.data.as_ref().expect("dep graph enabled").current.data.lock();
+        input_fingerprint(&data, dep_node_index, &mut FxHashMap::default())
+    }
+
     pub fn prev_fingerprint_of(&self, dep_node: &DepNode<K>) -> Option<Fingerprint> {
         self.data.as_ref().unwrap().previous.fingerprint_of(dep_node)
     }
@@ -594,6 +654,7 @@ impl<K: DepKind> DepGraph<K> {
                         dep_node,
                         data.previous.index_to_node(dep_dep_node_index)
                     );
+                    self.record_red_cause(tcx, data, prev_dep_node_index, dep_dep_node_index);
                     return None;
                 }
                 None => {
@@ -649,6 +710,12 @@ impl<K: DepKind> DepGraph<K> {
                                         dependency {:?} was red after forcing",
                                     dep_node, dep_dep_node
                                 );
+                                self.record_red_cause(
+                                    tcx,
+                                    data,
+                                    prev_dep_node_index,
+                                    dep_dep_node_index,
+                                );
                                 return None;
                             }
                             None => {
@@ -684,6 +751,7 @@ impl<K: DepKind> DepGraph<K> {
                                 could not be forced",
                             dep_node, dep_dep_node
                         );
+                        self.record_red_cause(tcx, data, prev_dep_node_index, dep_dep_node_index);
                         return None;
                     }
                 }
@@ -732,6 +800,49 @@ impl<K: DepKind> DepGraph<K> {
         Some(dep_node_index)
     }
 
+    /// Remembers that `dep_dep_node_index` is the dependency that kept
+    /// `prev_dep_node_index` from being marked green.
+    #[inline]
+    fn record_red_cause<Ctxt: DepContext<DepKind = K>>(
+        &self,
+        tcx: Ctxt,
+        data: &DepGraphData<K>,
+        prev_dep_node_index: SerializedDepNodeIndex,
+        dep_dep_node_index: SerializedDepNodeIndex,
+    ) {
+        if unlikely!(tcx.explain_red_nodes()) {
+            data.red_node_causes.lock().insert(prev_dep_node_index, dep_dep_node_index);
+        }
+    }
+
+    /// Returns the chain of nodes that kept `dep_node` from being marked green,
+    /// starting with the first changed input and ending with `dep_node` itself.
+    ///
+    /// Every node but the first could not be marked green because the node
+    /// before it was red. The chain is only recorded with `-Zincremental-explain`;
+    /// otherwise it just consists of `dep_node`.
+    pub fn red_node_chain(&self, dep_node: &DepNode<K>) -> Vec<DepNode<K>> {
+        let mut chain = vec![*dep_node];
+
+        let data = match self.data {
+            Some(ref data) => data,
+            None => return chain,
+        };
+        let mut index = match data.previous.node_to_index_opt(dep_node) {
+            Some(index) => index,
+            None => return chain,
+        };
+
+        let causes = data.red_node_causes.lock();
+        while let Some(&cause) = causes.get(&index) {
+            chain.push(data.previous.index_to_node(cause));
+            index = cause;
+        }
+
+        chain.reverse();
+        chain
+    }
+
     /// Atomically emits some loaded diagnostics.
     /// This may be called concurrently on multiple threads for the same dep node.
     #[cold]
@@ -862,6 +973,15 @@ pub struct WorkProduct {
     pub cgu_name: String,
     /// Saved file associated with this CGU.
     pub saved_file: Option<String>,
+    /// Saved `.dwo` file associated with this CGU, when compiling with split DWARF.
+    pub saved_dwarf_file: Option<String>,
+}
+
+impl WorkProduct {
+    /// Iterates over the names of all files saved for this CGU in the incremental directory.
+    pub fn saved_files(&self) -> impl Iterator<Item = &String> {
+        self.saved_file.iter().chain(self.saved_dwarf_file.iter())
+    }
 }
 
 #[derive(Clone)]
@@ -29,6 +29,9 @@ pub trait DepContext: Copy {
     fn debug_dep_tasks(&self) -> bool;
     fn debug_dep_node(&self) -> bool;
 
+    /// Whether to record why dep nodes could not be marked green, for `-Zincremental-explain`.
+    fn explain_red_nodes(&self) -> bool;
+
     /// Try to force a dep node to execute and see if it's green.
     fn try_force_from_dep_node(&self, dep_node: &DepNode<Self::DepKind>) -> bool;
 
@@ -65,6 +68,9 @@ pub trait DepKind: Copy + fmt::Debug + Eq + Ord + Hash {
     /// Return whether this kind always require evaluation.
     fn is_eval_always(&self) -> bool;
 
+    /// Return whether this kind is used for anonymous nodes.
+    fn is_anon(&self) -> bool;
+
     /// Return whether this kind requires additional parameters to be executed.
     fn has_params(&self) -> bool;
 
@@ -417,6 +417,7 @@ where
 
     if query.anon {
         let prof_timer = tcx.profiler().query_provider();
+        let stats_timer = tcx.profiler().query_stats_provider(query.name);
 
         let ((result, dep_node_index), diagnostics) = with_diagnostics(|diagnostics| {
             tcx.start_query(job.id, diagnostics, |tcx| {
@@ -425,6 +426,7 @@ where
         });
 
         prof_timer.finish_with_query_invocation_id(dep_node_index.into());
+        drop(stats_timer);
 
         tcx.dep_graph().read_index(dep_node_index);
 
@@ -508,11 +510,13 @@ where
         // We could not load a result from the on-disk cache, so
         // recompute.
         let prof_timer = tcx.profiler().query_provider();
+        let stats_timer = tcx.profiler().query_stats_provider(query.name);
 
         // The dep-graph for this computation is already in-place.
         let result = tcx.dep_graph().with_ignore(|| query.compute(tcx, key));
 
         prof_timer.finish_with_query_invocation_id(dep_node_index.into());
+        drop(stats_timer);
 
         result
     };
@@ -584,6 +588,7 @@ where
     );
 
     let prof_timer = tcx.profiler().query_provider();
+    let stats_timer = tcx.profiler().query_stats_provider(query.name);
 
     let ((result, dep_node_index), diagnostics) = with_diagnostics(|diagnostics| {
         tcx.start_query(job.id, diagnostics, |tcx| {
@@ -602,6 +607,7 @@ where
     });
 
     prof_timer.finish_with_query_invocation_id(dep_node_index.into());
+    drop(stats_timer);
 
     if unlikely!(!diagnostics.is_empty()) {
         if dep_node.kind != DepKind::NULL {
@@ -633,10 +639,14 @@ where
         state,
         key,
         |value, index| {
+            tcx.profiler().query_stats_invocation(query.name, true);
             tcx.dep_graph().read_index(index);
             value.clone()
         },
-        |key, lookup| try_execute_query(tcx, state, span, key, lookup, query),
+        |key, lookup| {
+            tcx.profiler().query_stats_invocation(query.name, false);
+            try_execute_query(tcx, state, span, key, lookup, query)
+        },
     )
 }
 
@@ -658,6 +668,8 @@ fn ensure_query_impl<CTX, C>(
     C::Key: Eq + Clone + crate::dep_graph::DepNodeParams<CTX>,
     CTX: QueryContext,
 {
+    tcx.profiler().query_stats_ensure(query.name);
+
     if query.eval_always {
         let _ = get_query_impl(tcx, state, DUMMY_SP, key, query);
         return;
@@ -680,6 +692,7 @@ fn ensure_query_impl<CTX, C>(
         }
         Some((_, dep_node_index)) => {
             tcx.profiler().query_cache_hit(dep_node_index.into());
+            tcx.profiler().query_stats_invocation(query.name, true);
         }
     }
 }
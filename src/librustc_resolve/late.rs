@@ -1350,6 +1350,10 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
         // Resolve the initializer.
         walk_list!(self, visit_expr, &local.init);
 
+        // Resolve the `else` block. This happens before the pattern, as the bindings introduced
+        // by the pattern are not in scope in the `else` block.
+        walk_list!(self, visit_block, &local.els);
+
         // Resolve the pattern.
         self.resolve_pattern_top(&local.pat, PatternSource::Let);
     }
@@ -2177,6 +2181,12 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
                 visit::walk_expr(self, expr);
             }
 
+            ExprKind::ConstBlock(ref anon_const) => {
+                // Inline constants are evaluated with the generics of the enclosing item,
+                // so unlike other anonymous constants they may always use its parameters.
+                self.with_constant_rib(true, |this| visit::walk_anon_const(this, anon_const));
+            }
+
             ExprKind::Break(Some(label), _) | ExprKind::Continue(Some(label)) => {
                 if let Some(node_id) = self.resolve_label(label.ident) {
                     // Since this res is a label, it is never read.
@@ -1438,6 +1438,12 @@ impl<'tcx> Visitor<'tcx> for DumpVisitor<'tcx> {
                     v.visit_expr(&body.value)
                 });
             }
+            hir::ExprKind::ConstBlock(ref anon_const) => {
+                let map = self.tcx.hir();
+                self.nest_typeck_results(self.tcx.hir().local_def_id(anon_const.hir_id), |v| {
+                    v.visit_expr(&map.body(anon_const.body).value)
+                });
+            }
             hir::ExprKind::Repeat(ref expr, ref anon_const) => {
                 self.visit_expr(expr);
                 let map = self.tcx.hir();
@@ -1458,8 +1464,13 @@ impl<'tcx> Visitor<'tcx> for DumpVisitor<'tcx> {
 
     fn visit_arm(&mut self, arm: &'tcx hir::Arm<'tcx>) {
         self.process_var_decl(&arm.pat);
-        if let Some(hir::Guard::If(expr)) = &arm.guard {
-            self.visit_expr(expr);
+        match &arm.guard {
+            Some(hir::Guard::If(expr)) => self.visit_expr(expr),
+            Some(hir::Guard::IfLet(pat, expr)) => {
+                self.process_var_decl(pat);
+                self.visit_expr(expr);
+            }
+            None => {}
         }
         self.visit_expr(&arm.body);
     }
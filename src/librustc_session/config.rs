@@ -8,13 +8,13 @@ use crate::search_paths::SearchPath;
 use crate::utils::NativeLibKind;
 use crate::{early_error, early_warn, Session};
 
-use rustc_data_structures::fx::FxHashSet;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::impl_stable_hash_via_hash;
 use rustc_data_structures::stable_hasher::{HashStable, StableHasher};
 
 use rustc_target::spec::{Target, TargetTriple};
 
-use crate::parse::CrateConfig;
+use crate::parse::{CrateCheckConfig, CrateConfig};
 use rustc_feature::UnstableFeatures;
 use rustc_span::edition::{Edition, DEFAULT_EDITION, EDITION_NAME_LIST};
 use rustc_span::source_map::{FileName, FilePathMapping};
@@ -29,6 +29,7 @@ use std::collections::btree_map::{
 };
 use std::collections::{BTreeMap, BTreeSet};
 use std::fmt;
+use std::hash::Hash;
 use std::iter::{self, FromIterator};
 use std::path::{Path, PathBuf};
 use std::str::{self, FromStr};
@@ -45,6 +46,9 @@ bitflags! {
         const LEAK    = 1 << 1;
         const MEMORY  = 1 << 2;
         const THREAD  = 1 << 3;
+        const CFI     = 1 << 4;
+        const HWADDRESS = 1 << 5;
+        const SHADOWCALLSTACK = 1 << 6;
     }
 }
 
@@ -58,6 +62,9 @@ impl fmt::Display for SanitizerSet {
                 SanitizerSet::LEAK => "leak",
                 SanitizerSet::MEMORY => "memory",
                 SanitizerSet::THREAD => "thread",
+                SanitizerSet::CFI => "cfi",
+                SanitizerSet::HWADDRESS => "hwaddress",
+                SanitizerSet::SHADOWCALLSTACK => "shadow-call-stack",
                 _ => panic!("unrecognized sanitizer {:?}", s),
             };
             if !first {
@@ -75,12 +82,20 @@ impl IntoIterator for SanitizerSet {
     type IntoIter = std::vec::IntoIter<SanitizerSet>;
 
     fn into_iter(self) -> Self::IntoIter {
-        [SanitizerSet::ADDRESS, SanitizerSet::LEAK, SanitizerSet::MEMORY, SanitizerSet::THREAD]
-            .iter()
-            .copied()
-            .filter(|&s| self.contains(s))
-            .collect::<Vec<_>>()
-            .into_iter()
+        [
+            SanitizerSet::ADDRESS,
+            SanitizerSet::LEAK,
+            SanitizerSet::MEMORY,
+            SanitizerSet::THREAD,
+            SanitizerSet::CFI,
+            SanitizerSet::HWADDRESS,
+            SanitizerSet::SHADOWCALLSTACK,
+        ]
+        .iter()
+        .copied()
+        .filter(|&s| self.contains(s))
+        .collect::<Vec<_>>()
+        .into_iter()
     }
 }
 
@@ -103,6 +118,19 @@ pub enum Strip {
     Symbols,
 }
 
+/// The different settings that the `-C split-debuginfo` flag can have.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum SplitDebuginfo {
+    /// Keep all DWARF in the object files and the final artifact.
+    Off,
+
+    /// Emit DWARF into `.dwo` files and package them into a single `.dwp` file when linking.
+    Packed,
+
+    /// Emit DWARF into `.dwo` files and leave them next to the object files.
+    Unpacked,
+}
+
 /// The different settings that the `-C control-flow-guard` flag can have.
 #[derive(Clone, Copy, PartialEq, Hash, Debug)]
 pub enum CFGuard {
@@ -116,6 +144,16 @@ pub enum CFGuard {
     Checks,
 }
 
+/// The different settings that the `-Z query-stats` flag can have.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum QueryStatsFormat {
+    /// Print a human-readable summary of the query caches.
+    Text,
+
+    /// Print a JSON object with per-query invocation counts, timings and result sizes.
+    Json,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Hash)]
 pub enum OptLevel {
     No,         // -O0
@@ -303,6 +341,8 @@ pub enum ErrorOutputType {
         /// human output.
         json_rendered: HumanReadableErrorType,
     },
+    /// A SARIF log for code scanning tools, written once compilation is done.
+    Sarif,
 }
 
 impl Default for ErrorOutputType {
@@ -503,6 +543,7 @@ impl_stable_hash_via_hash!(OutputFilenames);
 
 pub const RLINK_EXT: &str = "rlink";
 pub const RUST_CGU_EXT: &str = "rcgu";
+pub const DWARF_OBJECT_EXT: &str = "dwo";
 
 impl OutputFilenames {
     pub fn new(
@@ -536,6 +577,26 @@ impl OutputFilenames {
         self.temp_path_ext(extension, codegen_unit_name)
     }
 
+    /// Gets the path of the `.dwo` file that the DWARF of the given codegen unit is written to,
+    /// or `None` if the DWARF stays in the object file.
+    pub fn split_dwarf_path(
+        &self,
+        split_debuginfo: SplitDebuginfo,
+        codegen_unit_name: Option<&str>,
+    ) -> Option<PathBuf> {
+        match split_debuginfo {
+            SplitDebuginfo::Off => None,
+            SplitDebuginfo::Packed | SplitDebuginfo::Unpacked => {
+                Some(self.temp_path_dwo(codegen_unit_name))
+            }
+        }
+    }
+
+    /// Like `temp_path`, but for the `.dwo` file of a codegen unit.
+    pub fn temp_path_dwo(&self, codegen_unit_name: Option<&str>) -> PathBuf {
+        self.temp_path_ext(DWARF_OBJECT_EXT, codegen_unit_name)
+    }
+
     /// Like temp_path, but also supports things where there is no corresponding
     /// OutputType, like noopt-bitcode or lto-bitcode.
     pub fn temp_path_ext(&self, ext: &str, codegen_unit_name: Option<&str>) -> PathBuf {
@@ -783,6 +844,88 @@ pub fn build_configuration(sess: &Session, mut user_cfg: CrateConfig) -> CrateCo
     user_cfg
 }
 
+/// The `cfg` names and values declared with `--check-cfg`.
+#[derive(Clone, Debug)]
+pub struct CheckCfg<T = String> {
+    /// The names declared with `names(...)`, or `None` if names are not checked.
+    pub names_valid: Option<FxHashSet<T>>,
+    /// The values declared with `values(name, ...)`. Only the values of names in this map are
+    /// checked.
+    pub values_valid: FxHashMap<T, FxHashSet<T>>,
+}
+
+impl<T> Default for CheckCfg<T> {
+    fn default() -> Self {
+        CheckCfg { names_valid: None, values_valid: FxHashMap::default() }
+    }
+}
+
+impl<T> CheckCfg<T> {
+    fn map_data<O: Eq + Hash>(&self, f: impl Fn(&T) -> O) -> CheckCfg<O> {
+        CheckCfg {
+            names_valid: self
+                .names_valid
+                .as_ref()
+                .map(|names_valid| names_valid.iter().map(|a| f(a)).collect()),
+            values_valid: self
+                .values_valid
+                .iter()
+                .map(|(a, b)| (f(a), b.iter().map(|b| f(b)).collect()))
+                .collect(),
+        }
+    }
+}
+
+/// Converts the crate `--check-cfg` options from `String` to `Symbol`.
+/// See `to_crate_config` for why this has to happen late.
+pub fn to_crate_check_config(cfg: CheckCfg) -> CrateCheckConfig {
+    cfg.map_data(|s| Symbol::intern(s))
+}
+
+impl CrateCheckConfig {
+    /// Declares the names that rustc itself may set for some target or configuration, so that
+    /// `names(...)` only has to list the crate's own names.
+    pub fn fill_well_known(&mut self) {
+        const WELL_KNOWN_NAMES: &[Symbol] = &[
+            sym::unix,
+            sym::windows,
+            sym::target_os,
+            sym::target_family,
+            sym::target_arch,
+            sym::target_endian,
+            sym::target_pointer_width,
+            sym::target_env,
+            sym::target_vendor,
+            sym::target_thread_local,
+            sym::target_has_atomic,
+            sym::target_has_atomic_load_store,
+            sym::sanitize,
+            sym::debug_assertions,
+            sym::proc_macro,
+            sym::test,
+            sym::doc,
+            sym::doctest,
+        ];
+        if let Some(names_valid) = &mut self.names_valid {
+            names_valid.extend(WELL_KNOWN_NAMES.iter().copied());
+            // A name whose values are declared is a declared name as well.
+            names_valid.extend(self.values_valid.keys().copied());
+        }
+    }
+
+    /// Declares everything that is actually set in this compilation, e.g., with `--cfg`.
+    pub fn fill_actual(&mut self, cfg: &CrateConfig) {
+        for &(name, value) in cfg {
+            if let Some(names_valid) = &mut self.names_valid {
+                names_valid.insert(name);
+            }
+            if let (Some(values_valid), Some(value)) = (self.values_valid.get_mut(&name), value) {
+                values_valid.insert(value);
+            }
+        }
+    }
+}
+
 pub fn build_target_config(opts: &Options, error_format: ErrorOutputType) -> Config {
     let target = Target::search(&opts.target_triple).unwrap_or_else(|e| {
         early_error(
@@ -1012,7 +1155,7 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
             "",
             "error-format",
             "How errors and other messages are produced",
-            "human|json|short",
+            "human|json|sarif|short",
         ),
         opt::multi_s("", "json", "Configure the JSON output of the compiler", "CONFIG"),
         opt::opt_s(
@@ -1039,6 +1182,12 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
             "Remap source names in all output (compiler messages and output files)",
             "FROM=TO",
         ),
+        opt::multi(
+            "",
+            "check-cfg",
+            "Provide list of valid cfg options for checking",
+            "[names(NAME, ...)|values(NAME, \"VALUE\", ...)]",
+        ),
     ]);
     opts
 }
@@ -1157,12 +1306,13 @@ pub fn parse_error_format(
             }
             Some("json") => ErrorOutputType::Json { pretty: false, json_rendered },
             Some("pretty-json") => ErrorOutputType::Json { pretty: true, json_rendered },
+            Some("sarif") => ErrorOutputType::Sarif,
             Some("short") => ErrorOutputType::HumanReadable(HumanReadableErrorType::Short(color)),
 
             Some(arg) => early_error(
                 ErrorOutputType::HumanReadable(HumanReadableErrorType::Default(color)),
                 &format!(
-                    "argument for `--error-format` must be `human`, `json` or \
+                    "argument for `--error-format` must be `human`, `json`, `sarif` or \
                      `short` (instead was `{}`)",
                     arg
                 ),
@@ -1240,6 +1390,12 @@ fn check_debug_option_stability(
                 "`--error-format=human-annotate-rs` is unstable",
             );
         }
+        if let ErrorOutputType::Sarif = error_format {
+            early_error(
+                ErrorOutputType::Json { pretty: false, json_rendered },
+                "`--error-format=sarif` is unstable",
+            );
+        }
     }
 }
 
@@ -2048,15 +2204,15 @@ impl PpMode {
 crate mod dep_tracking {
     use super::{
         CFGuard, CrateType, DebugInfo, ErrorOutputType, LinkerPluginLto, LtoCli, OptLevel,
-        OutputTypes, Passes, SanitizerSet, SourceFileHashAlgorithm, SwitchWithOptPath,
-        SymbolManglingVersion,
+        OutputTypes, Passes, SanitizerSet, SourceFileHashAlgorithm, SplitDebuginfo,
+        SwitchWithOptPath, SymbolManglingVersion,
     };
     use crate::lint;
     use crate::utils::NativeLibKind;
     use rustc_feature::UnstableFeatures;
     use rustc_span::edition::Edition;
     use rustc_target::spec::{CodeModel, MergeFunctions, PanicStrategy, RelocModel};
-    use rustc_target::spec::{RelroLevel, TargetTriple, TlsModel};
+    use rustc_target::spec::{RelroLevel, StackProtector, TargetTriple, TlsModel};
     use std::collections::hash_map::DefaultHasher;
     use std::collections::BTreeMap;
     use std::hash::Hash;
@@ -2107,6 +2263,7 @@ crate mod dep_tracking {
     impl_dep_tracking_hash_via_hash!(Option<RelocModel>);
     impl_dep_tracking_hash_via_hash!(Option<CodeModel>);
     impl_dep_tracking_hash_via_hash!(Option<TlsModel>);
+    impl_dep_tracking_hash_via_hash!(Option<StackProtector>);
     impl_dep_tracking_hash_via_hash!(Option<PanicStrategy>);
     impl_dep_tracking_hash_via_hash!(Option<RelroLevel>);
     impl_dep_tracking_hash_via_hash!(Option<lint::Level>);
@@ -2124,6 +2281,7 @@ crate mod dep_tracking {
     impl_dep_tracking_hash_via_hash!(NativeLibKind);
     impl_dep_tracking_hash_via_hash!(SanitizerSet);
     impl_dep_tracking_hash_via_hash!(CFGuard);
+    impl_dep_tracking_hash_via_hash!(SplitDebuginfo);
     impl_dep_tracking_hash_via_hash!(TargetTriple);
     impl_dep_tracking_hash_via_hash!(Edition);
     impl_dep_tracking_hash_via_hash!(LinkerPluginLto);
@@ -1,7 +1,8 @@
 pub use self::Level::*;
+use rustc_ast::ast;
 use rustc_ast::node_id::{NodeId, NodeMap};
 use rustc_data_structures::stable_hasher::{HashStable, StableHasher, ToStableHashKey};
-use rustc_errors::{pluralize, Applicability, DiagnosticBuilder};
+use rustc_errors::{pluralize, Applicability, DiagnosticBuilder, LintExpectationId};
 use rustc_span::edition::Edition;
 use rustc_span::{sym, symbol::Ident, MultiSpan, Span, Symbol};
 
@@ -11,6 +12,8 @@ pub mod builtin;
 #[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Debug, Hash)]
 pub enum Level {
     Allow,
+    /// Like `Allow`, but `unfulfilled_lint_expectations` is emitted if the lint never fires.
+    Expect(LintExpectationId),
     Warn,
     Deny,
     Forbid,
@@ -23,6 +26,7 @@ impl Level {
     pub fn as_str(self) -> &'static str {
         match self {
             Level::Allow => "allow",
+            Level::Expect(_) => "expect",
             Level::Warn => "warn",
             Level::Deny => "deny",
             Level::Forbid => "forbid",
@@ -40,10 +44,16 @@ impl Level {
         }
     }
 
-    /// Converts a symbol to a level.
-    pub fn from_symbol(x: Symbol) -> Option<Level> {
-        match x {
+    /// Converts the name of a lint attribute to a level.
+    ///
+    /// `#[expect]` attributes are identified by their `AttrId` and the first lint they name.
+    pub fn from_attr(attr: &ast::Attribute) -> Option<Level> {
+        match attr.name_or_empty() {
             sym::allow => Some(Level::Allow),
+            sym::expect => Some(Level::Expect(LintExpectationId::Unstable {
+                attr_id: attr.id.as_u32(),
+                lint_index: 0,
+            })),
             sym::warn => Some(Level::Warn),
             sym::deny => Some(Level::Deny),
             sym::forbid => Some(Level::Forbid),
@@ -549,6 +549,35 @@ declare_lint! {
     };
 }
 
+declare_lint! {
+    pub DISJOINT_CAPTURE_DROP_REORDER,
+    Allow,
+    "detects closures whose captures would be dropped in a different order \
+     with `capture_disjoint_fields`",
+    @future_incompatible = FutureIncompatibleInfo {
+        reference: "issue #53488 <https://github.com/rust-lang/rust/issues/53488>",
+        edition: Some(Edition::Edition2021),
+    };
+}
+
+declare_lint! {
+    pub UNFULFILLED_LINT_EXPECTATIONS,
+    Warn,
+    "detects `#[expect]` attributes whose lint was never emitted"
+}
+
+declare_lint! {
+    pub UNEXPECTED_CFGS,
+    Warn,
+    "detects unexpected names and values in `#[cfg]` conditions"
+}
+
+declare_lint! {
+    pub MUST_NOT_SUSPEND,
+    Allow,
+    "detects values of `#[must_not_suspend]` types held across a suspend point"
+}
+
 declare_lint_pass! {
     /// Does nothing as a lint pass, but registers some `Lint`s
     /// that are used by other parts of the compiler.
@@ -623,6 +652,10 @@ declare_lint_pass! {
         UNSAFE_OP_IN_UNSAFE_FN,
         INCOMPLETE_INCLUDE,
         CENUM_IMPL_DROP_CAST,
+        DISJOINT_CAPTURE_DROP_REORDER,
+        UNFULFILLED_LINT_EXPECTATIONS,
+        UNEXPECTED_CFGS,
+        MUST_NOT_SUSPEND,
     ]
 }
 
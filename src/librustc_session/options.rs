@@ -6,7 +6,7 @@ use crate::search_paths::SearchPath;
 use crate::utils::NativeLibKind;
 
 use rustc_target::spec::{CodeModel, LinkerFlavor, MergeFunctions, PanicStrategy};
-use rustc_target::spec::{RelocModel, RelroLevel, TargetTriple, TlsModel};
+use rustc_target::spec::{RelocModel, RelroLevel, StackProtector, TargetTriple, TlsModel};
 
 use rustc_feature::UnstableFeatures;
 use rustc_span::edition::Edition;
@@ -248,11 +248,13 @@ macro_rules! options {
         pub const parse_passes: &str = "a space-separated list of passes, or `all`";
         pub const parse_panic_strategy: &str = "either `unwind` or `abort`";
         pub const parse_relro_level: &str = "one of: `full`, `partial`, or `off`";
-        pub const parse_sanitizers: &str = "comma separated list of sanitizers: `address`, `leak`, `memory` or `thread`";
+        pub const parse_sanitizers: &str = "comma separated list of sanitizers: `address`, `cfi`, \
+            `hwaddress`, `leak`, `memory`, `shadow-call-stack` or `thread`";
         pub const parse_sanitizer_memory_track_origins: &str = "0, 1, or 2";
         pub const parse_cfguard: &str =
             "either a boolean (`yes`, `no`, `on`, `off`, etc), `checks`, or `nochecks`";
         pub const parse_strip: &str = "either `none`, `debuginfo`, or `symbols`";
+        pub const parse_split_debuginfo: &str = "one of: `off`, `packed`, or `unpacked`";
         pub const parse_linker_flavor: &str = ::rustc_target::spec::LinkerFlavor::one_of();
         pub const parse_optimization_fuel: &str = "crate=integer";
         pub const parse_unpretty: &str = "`string` or `string=string`";
@@ -272,6 +274,8 @@ macro_rules! options {
             "one of supported code models (`rustc --print code-models`)";
         pub const parse_tls_model: &str =
             "one of supported TLS models (`rustc --print tls-models`)";
+        pub const parse_stack_protector: &str = "one of: `none`, `basic`, `strong`, or `all`";
+        pub const parse_query_stats: &str = "either no value, `text`, or `json`";
         pub const parse_target_feature: &str = parse_string;
     }
 
@@ -467,6 +471,9 @@ macro_rules! options {
                         "leak" => SanitizerSet::LEAK,
                         "memory" => SanitizerSet::MEMORY,
                         "thread" => SanitizerSet::THREAD,
+                        "cfi" => SanitizerSet::CFI,
+                        "hwaddress" => SanitizerSet::HWADDRESS,
+                        "shadow-call-stack" => SanitizerSet::SHADOWCALLSTACK,
                         _ => return false,
                     }
                 }
@@ -495,6 +502,16 @@ macro_rules! options {
             true
         }
 
+        fn parse_split_debuginfo(slot: &mut SplitDebuginfo, v: Option<&str>) -> bool {
+            match v {
+                Some("off") => *slot = SplitDebuginfo::Off,
+                Some("packed") => *slot = SplitDebuginfo::Packed,
+                Some("unpacked") => *slot = SplitDebuginfo::Unpacked,
+                _ => return false,
+            }
+            true
+        }
+
         fn parse_cfguard(slot: &mut CFGuard, v: Option<&str>) -> bool {
             if v.is_some() {
                 let mut bool_arg = None;
@@ -641,6 +658,26 @@ macro_rules! options {
             true
         }
 
+        fn parse_stack_protector(slot: &mut Option<StackProtector>, v: Option<&str>) -> bool {
+            match v.and_then(|s| StackProtector::from_str(s).ok()) {
+                Some(stack_protector) => *slot = Some(stack_protector),
+                _ => return false,
+            }
+            true
+        }
+
+        fn parse_query_stats(slot: &mut Option<QueryStatsFormat>, v: Option<&str>) -> bool {
+            *slot = match v {
+                Some("y") | Some("yes") | Some("on") | Some("text") | None => {
+                    Some(QueryStatsFormat::Text)
+                }
+                Some("json") => Some(QueryStatsFormat::Json),
+                Some("n") | Some("no") | Some("off") => None,
+                _ => return false,
+            };
+            true
+        }
+
         fn parse_symbol_mangling_version(
             slot: &mut SymbolManglingVersion,
             v: Option<&str>,
@@ -768,6 +805,9 @@ options! {CodegenOptions, CodegenSetter, basic_codegen_options,
         "save all temporary output files during compilation (default: no)"),
     soft_float: bool = (false, parse_bool, [TRACKED],
         "use soft float ABI (*eabihf targets only) (default: no)"),
+    split_debuginfo: SplitDebuginfo = (SplitDebuginfo::Off, parse_split_debuginfo, [TRACKED],
+        "how to handle split DWARF debuginfo on Linux: `off` (default), `packed` (into a \
+        `.dwp` file next to the output) or `unpacked` (as `.dwo` files next to the objects)"),
     target_cpu: Option<String> = (None, parse_opt_string, [TRACKED],
         "select target processor (`rustc --print target-cpus` for details)"),
     target_feature: String = (String::new(), parse_target_feature, [TRACKED],
@@ -864,11 +904,18 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "generate human-readable, predictable names for codegen units (default: no)"),
     identify_regions: bool = (false, parse_bool, [UNTRACKED],
         "display unnamed regions as `'<id>`, using a non-ident unique id (default: no)"),
+    incremental_explain: bool = (false, parse_bool, [UNTRACKED],
+        "explain why each codegen unit that could not be reused had to be recompiled \
+        (default: no)"),
     incremental_ignore_spans: bool = (false, parse_bool, [UNTRACKED],
         "ignore spans during ICH computation -- used for testing (default: no)"),
     incremental_info: bool = (false, parse_bool, [UNTRACKED],
         "print high-level information about incremental reuse (or the lack thereof) \
         (default: no)"),
+    incremental_shared_cache: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "also store incremental compilation sessions and object files in, and reuse them \
+        from, this content-addressed directory shared between checkouts, which the compiler \
+        never cleans up"),
     incremental_verify_ich: bool = (false, parse_bool, [UNTRACKED],
         "verify incr. comp. hashes of green query instances (default: no)"),
     inline_in_all_cgus: Option<bool> = (None, parse_opt_bool, [TRACKED],
@@ -972,8 +1019,9 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         (default based on relative source path)"),
     query_dep_graph: bool = (false, parse_bool, [UNTRACKED],
         "enable queries of the dependency graph for regression testing (default: no)"),
-    query_stats: bool = (false, parse_bool, [UNTRACKED],
-        "print some statistics about the query system (default: no)"),
+    query_stats: Option<QueryStatsFormat> = (None, parse_query_stats, [UNTRACKED],
+        "print some statistics about the query system, as `text` (the default if no \
+        value is given) or as `json`"),
     relro_level: Option<RelroLevel> = (None, parse_relro_level, [TRACKED],
         "choose which RELRO level to use"),
     report_delayed_bugs: bool = (false, parse_bool, [TRACKED],
@@ -1015,6 +1063,9 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "exclude spans when debug-printing compiler state (default: no)"),
     src_hash_algorithm: Option<SourceFileHashAlgorithm> = (None, parse_src_file_hash, [TRACKED],
         "hash algorithm of source files in debug info (`md5`, or `sha1`)"),
+    stack_protector: Option<StackProtector> = (None, parse_stack_protector, [TRACKED],
+        "emit stack canaries to detect stack buffer overflows in functions (`none`, `basic`, \
+        `strong` or `all`) (default: the target's default)"),
     strip: Strip = (Strip::None, parse_strip, [UNTRACKED],
         "tell the linker which information to strip (`none` (default), `debuginfo` or `symbols`)"),
     symbol_mangling_version: SymbolManglingVersion = (SymbolManglingVersion::Legacy,
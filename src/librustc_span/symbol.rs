@@ -274,6 +274,7 @@ symbols! {
         assume_init,
         async_await,
         async_closure,
+        async_fn_in_trait,
         atomics,
         att_syntax,
         attr,
@@ -308,11 +309,13 @@ symbols! {
         breakpoint,
         bridge,
         bswap,
+        c_unwind,
         c_variadic,
         call,
         call_mut,
         call_once,
         caller_location,
+        capture_disjoint_fields,
         cdylib,
         ceilf32,
         ceilf64,
@@ -327,6 +330,7 @@ symbols! {
         cfg_target_thread_local,
         cfg_target_vendor,
         cfg_version,
+        cfi,
         char,
         client,
         clippy,
@@ -421,6 +425,7 @@ symbols! {
         deref,
         deref_mut,
         derive,
+        destructuring_assignment,
         diagnostic,
         direct,
         discriminant_kind,
@@ -475,6 +480,7 @@ symbols! {
         existential_type,
         exp2f32,
         exp2f64,
+        expect,
         expected,
         expf32,
         expf64,
@@ -563,6 +569,7 @@ symbols! {
         html_no_source,
         html_playground_url,
         html_root_url,
+        hwaddress,
         i,
         i128,
         i128_type,
@@ -572,6 +579,7 @@ symbols! {
         i8,
         ident,
         if_let,
+        if_let_guard,
         if_while_or_patterns,
         ignore,
         impl_header_lifetime_elision,
@@ -589,6 +597,7 @@ symbols! {
         infer_static_outlives_requirements,
         inlateout,
         inline,
+        inline_const,
         inout,
         intel,
         into_iter,
@@ -612,6 +621,7 @@ symbols! {
         lazy_normalization_consts,
         le,
         let_chains,
+        let_else,
         lhs,
         lib,
         libc,
@@ -688,12 +698,14 @@ symbols! {
         mul,
         mul_assign,
         mul_with_overflow,
+        must_not_suspend,
         must_use,
         mut_ptr,
         mut_slice_ptr,
         naked,
         naked_functions,
         name,
+        names,
         ne,
         nearbyintf32,
         nearbyintf64,
@@ -874,6 +886,7 @@ symbols! {
         rust,
         rust_2015_preview,
         rust_2018_preview,
+        rust_2021_preview,
         rust_begin_unwind,
         rust_eh_personality,
         rust_eh_register_frames,
@@ -947,6 +960,7 @@ symbols! {
         self_struct_ctor,
         semitransparent,
         send_trait,
+        shadow_call_stack,
         shl,
         shl_assign,
         should_panic,
@@ -1164,6 +1178,7 @@ symbols! {
         va_list,
         va_start,
         val,
+        values,
         var,
         variant_count,
         vec,
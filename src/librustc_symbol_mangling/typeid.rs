@@ -0,0 +1,336 @@
+//! Type identifiers for the control-flow integrity sanitizer (`-Zsanitizer=cfi`).
+//!
+//! LLVM attaches these identifiers to functions and vtables as `!type` metadata, and the
+//! `llvm.type.test` intrinsic checks at runtime whether a pointer points to a global carrying a
+//! given identifier. Indirect calls are thus restricted to functions with the signature the
+//! caller expects, and dynamic method calls to vtables of the trait the caller expects.
+//!
+//! Identifiers have to agree between all crates linked into a binary, so they are derived from
+//! stable hashes rather than from anything specific to the crate computing them.
+//!
+//! The exception are functions with a C calling convention whose signature only uses types with
+//! a C equivalent. They get the identifier Clang emits for the equivalent C function type with
+//! `-fsanitize=cfi-icall`, i.e. `_ZTS` followed by the Itanium mangling of the type, so that C
+//! code can call them through function pointers and vice versa. Rust types do not determine a
+//! unique C type, so the encoding picks the C type Rust code most commonly stands for: `i8` is
+//! `char` where `c_char` is `i8` and `signed char` elsewhere, `i64` is `long` on LP64 targets and
+//! `long long` elsewhere, and so on.
+
+use rustc_ast::ast::{FloatTy, IntTy, UintTy};
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::stable_hasher::{HashStable, StableHasher};
+use rustc_hir as hir;
+use rustc_hir::def_id::DefId;
+use rustc_middle::ich::NodeIdHashingMode;
+use rustc_middle::ty::subst::SubstsRef;
+use rustc_middle::ty::{self, Instance, Ty, TyCtxt};
+use rustc_span::symbol::sym;
+use rustc_span::DUMMY_SP;
+use rustc_target::abi::call::{FnAbi, PassMode};
+use rustc_target::spec::abi::Abi;
+
+/// Returns the type identifier of the given function.
+pub fn typeid_for_instance<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    instance: Instance<'tcx>,
+    fn_abi: &FnAbi<'tcx, Ty<'tcx>>,
+) -> String {
+    if let ty::InstanceDef::Item(_) = instance.def {
+        let ty = instance.ty(tcx, ty::ParamEnv::reveal_all());
+        if let ty::FnDef(..) = ty.kind {
+            return typeid_for_fn(tcx, ty.fn_sig(tcx), fn_abi);
+        }
+    }
+
+    typeid_for_fnabi(tcx, fn_abi)
+}
+
+/// Returns the type identifier of functions with the given signature and ABI.
+///
+/// This is the identifier Clang uses for the equivalent C function type if there is one, and
+/// `typeid_for_fnabi` otherwise.
+pub fn typeid_for_fn<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    fn_sig: ty::PolyFnSig<'tcx>,
+    fn_abi: &FnAbi<'tcx, Ty<'tcx>>,
+) -> String {
+    if is_c_like(fn_sig.abi()) {
+        let fn_sig = tcx.normalize_erasing_late_bound_regions(ty::ParamEnv::reveal_all(), &fn_sig);
+        let mut encoder = ItaniumEncoder { tcx, substitutions: FxHashMap::default() };
+        if let Some(encoding) = encoder.encode_fn_sig(fn_sig) {
+            return format!("_ZTS{}", encoding.emitted);
+        }
+    }
+
+    typeid_for_fnabi(tcx, fn_abi)
+}
+
+/// Returns the type identifier of functions with the given ABI.
+///
+/// Ignored arguments are left out, so that e.g. a closure without captures and the shim that
+/// reifies it to a function pointer get the same identifier.
+pub fn typeid_for_fnabi<'tcx>(tcx: TyCtxt<'tcx>, fn_abi: &FnAbi<'tcx, Ty<'tcx>>) -> String {
+    let mut hasher = StableHasher::new();
+    let mut hcx = tcx.create_stable_hashing_context();
+
+    hcx.while_hashing_spans(false, |hcx| {
+        hcx.with_node_id_hashing_mode(NodeIdHashingMode::HashDefPath, |hcx| {
+            fn_abi.ret.layout.ty.hash_stable(hcx, &mut hasher);
+            for arg in fn_abi.args.iter().filter(|arg| !matches!(arg.mode, PassMode::Ignore)) {
+                arg.layout.ty.hash_stable(hcx, &mut hasher);
+            }
+        });
+    });
+    fn_abi.c_variadic.hash_stable(&mut hcx, &mut hasher);
+    format!("{:?}", fn_abi.conv).hash_stable(&mut hcx, &mut hasher);
+
+    format!("_RFN{}", hasher.finish::<Fingerprint>().to_hex())
+}
+
+/// Returns the type identifier of vtables for trait objects with the given principal trait.
+pub fn typeid_for_trait_ref<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    trait_ref: Option<ty::PolyExistentialTraitRef<'tcx>>,
+) -> String {
+    let trait_ref = tcx.erase_regions(&trait_ref);
+
+    let mut hasher = StableHasher::new();
+    let mut hcx = tcx.create_stable_hashing_context();
+
+    hcx.while_hashing_spans(false, |hcx| {
+        hcx.with_node_id_hashing_mode(NodeIdHashingMode::HashDefPath, |hcx| {
+            trait_ref.hash_stable(hcx, &mut hasher);
+        });
+    });
+
+    format!("_RVT{}", hasher.finish::<Fingerprint>().to_hex())
+}
+
+fn is_c_like(abi: Abi) -> bool {
+    matches!(
+        abi,
+        Abi::C | Abi::Cdecl | Abi::SysV64 | Abi::System | Abi::CUnwind | Abi::SystemUnwind
+    )
+}
+
+/// The encoding of a type, both in full and as emitted after applying substitutions.
+struct Encoding {
+    full: String,
+    emitted: String,
+}
+
+impl Encoding {
+    fn builtin(s: &str) -> Encoding {
+        Encoding { full: s.to_string(), emitted: s.to_string() }
+    }
+}
+
+/// Encodes the C equivalents of Rust types as Itanium-mangled C++ types, returning `None` for
+/// types without a C equivalent.
+struct ItaniumEncoder<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    /// The substitutable components encoded so far, keyed by their full encoding.
+    substitutions: FxHashMap<String, usize>,
+}
+
+impl ItaniumEncoder<'tcx> {
+    /// Records a substitutable component, or refers to its earlier occurrence.
+    fn substitutable(&mut self, full: String, emitted: String) -> Encoding {
+        if let Some(&index) = self.substitutions.get(&full) {
+            let emitted = match index {
+                0 => "S_".to_string(),
+                _ => format!("S{}_", base36(index - 1)),
+            };
+            return Encoding { full, emitted };
+        }
+        let index = self.substitutions.len();
+        self.substitutions.insert(full.clone(), index);
+        Encoding { full, emitted }
+    }
+
+    fn encode_fn_sig(&mut self, fn_sig: ty::FnSig<'tcx>) -> Option<Encoding> {
+        let mut full = String::from("F");
+        let mut emitted = String::from("F");
+
+        let output = fn_sig.output();
+        let ret = if output.is_unit() || output.is_never() {
+            Encoding::builtin("v")
+        } else {
+            self.encode_ty(output)?
+        };
+        full.push_str(&ret.full);
+        emitted.push_str(&ret.emitted);
+
+        if fn_sig.inputs().is_empty() && !fn_sig.c_variadic {
+            full.push('v');
+            emitted.push('v');
+        }
+        for &input in fn_sig.inputs() {
+            let param = self.encode_ty(input)?;
+            full.push_str(&param.full);
+            emitted.push_str(&param.emitted);
+        }
+        if fn_sig.c_variadic {
+            full.push('z');
+            emitted.push('z');
+        }
+
+        full.push('E');
+        emitted.push('E');
+        Some(Encoding { full, emitted })
+    }
+
+    fn encode_ty(&mut self, ty: Ty<'tcx>) -> Option<Encoding> {
+        let target = &self.tcx.sess.target;
+        // `long` is as wide as a pointer on all targets but 64-bit Windows.
+        let long_is_64_bit = target.ptr_width == 64 && !target.target.options.is_like_windows;
+
+        let builtin = match ty.kind {
+            ty::Bool => "b",
+            ty::Int(IntTy::I8) if c_char_is_signed(self.tcx) => "c",
+            ty::Int(IntTy::I8) => "a",
+            ty::Uint(UintTy::U8) if c_char_is_signed(self.tcx) => "h",
+            ty::Uint(UintTy::U8) => "c",
+            ty::Int(IntTy::I16) => "s",
+            ty::Uint(UintTy::U16) => "t",
+            ty::Int(IntTy::I32) => "i",
+            ty::Uint(UintTy::U32) => "j",
+            ty::Int(IntTy::I64) if long_is_64_bit => "l",
+            ty::Int(IntTy::I64) => "x",
+            ty::Uint(UintTy::U64) if long_is_64_bit => "m",
+            ty::Uint(UintTy::U64) => "y",
+            ty::Int(IntTy::I128) => "n",
+            ty::Uint(UintTy::U128) => "o",
+            // `intptr_t` and `size_t` are `int` and `unsigned int` on 32-bit targets.
+            ty::Int(IntTy::Isize) => match target.ptr_width {
+                64 if long_is_64_bit => "l",
+                64 => "x",
+                _ => "i",
+            },
+            ty::Uint(UintTy::Usize) => match target.ptr_width {
+                64 if long_is_64_bit => "m",
+                64 => "y",
+                _ => "j",
+            },
+            ty::Float(FloatTy::F32) => "f",
+            ty::Float(FloatTy::F64) => "d",
+
+            ty::RawPtr(ty::TypeAndMut { ty: pointee, mutbl }) | ty::Ref(_, pointee, mutbl) => {
+                return self.encode_ptr(pointee, mutbl);
+            }
+            ty::Adt(def, _) if def.is_box() => {
+                return self.encode_ptr(ty.boxed_ty(), hir::Mutability::Mut);
+            }
+            ty::FnPtr(fn_sig) => {
+                if !is_c_like(fn_sig.abi()) {
+                    return None;
+                }
+                let param_env = ty::ParamEnv::reveal_all();
+                let fn_sig = self.tcx.normalize_erasing_late_bound_regions(param_env, &fn_sig);
+                let fn_ty = self.encode_fn_sig(fn_sig)?;
+                let fn_ty = self.substitutable(fn_ty.full, fn_ty.emitted);
+                return Some(self.substitutable(
+                    format!("P{}", fn_ty.full),
+                    format!("P{}", fn_ty.emitted),
+                ));
+            }
+
+            // `Option`s of non-nullable pointers are nullable pointers.
+            ty::Adt(def, substs) if self.tcx.is_diagnostic_item(sym::option_type, def.did) => {
+                let inner = substs.type_at(0);
+                return match inner.kind {
+                    ty::Ref(..) | ty::FnPtr(..) => self.encode_ty(inner),
+                    ty::Adt(def, _) if def.is_box() => self.encode_ty(inner),
+                    _ => None,
+                };
+            }
+            ty::Adt(def, substs) if def.repr.transparent() => {
+                return self.encode_ty(self.transparent_field_ty(def, substs)?);
+            }
+            ty::Adt(def, substs) => {
+                if substs.non_erasable_generics().next().is_some() {
+                    return None;
+                }
+                return Some(self.encode_name(def.did));
+            }
+            ty::Foreign(def_id) => return Some(self.encode_name(def_id)),
+
+            _ => return None,
+        };
+        Some(Encoding::builtin(builtin))
+    }
+
+    fn encode_ptr(&mut self, pointee: Ty<'tcx>, mutbl: hir::Mutability) -> Option<Encoding> {
+        if !pointee.is_sized(self.tcx.at(DUMMY_SP), ty::ParamEnv::reveal_all()) {
+            return None;
+        }
+
+        let pointee = match pointee.kind {
+            ty::Adt(def, _) if self.tcx.item_name(def.did).as_str() == "c_void" => {
+                Encoding::builtin("v")
+            }
+            _ => self.encode_ty(pointee)?,
+        };
+        let pointee = match mutbl {
+            hir::Mutability::Not => {
+                self.substitutable(format!("K{}", pointee.full), format!("K{}", pointee.emitted))
+            }
+            hir::Mutability::Mut => pointee,
+        };
+        Some(self.substitutable(format!("P{}", pointee.full), format!("P{}", pointee.emitted)))
+    }
+
+    fn encode_name(&mut self, def_id: DefId) -> Encoding {
+        let name = self.tcx.item_name(def_id);
+        let name = name.as_str();
+        let encoding = format!("{}{}", name.len(), name);
+        self.substitutable(encoding.clone(), encoding)
+    }
+
+    fn transparent_field_ty(
+        &self,
+        def: &'tcx ty::AdtDef,
+        substs: SubstsRef<'tcx>,
+    ) -> Option<Ty<'tcx>> {
+        def.non_enum_variant().fields.iter().map(|field| field.ty(self.tcx, substs)).find(|ty| {
+            self.tcx
+                .layout_of(ty::ParamEnv::reveal_all().and(ty))
+                .map_or(false, |layout| !layout.is_zst())
+        })
+    }
+}
+
+/// Returns whether `char` is signed on the target, mirroring the definition of `c_char`.
+fn c_char_is_signed(tcx: TyCtxt<'_>) -> bool {
+    let target = &tcx.sess.target.target;
+    let arch = &target.arch[..];
+    let unsigned = match &target.target_os[..] {
+        "linux" => matches!(
+            arch,
+            "aarch64" | "arm" | "hexagon" | "powerpc" | "powerpc64" | "s390x" | "riscv64"
+        ),
+        "android" => matches!(arch, "aarch64" | "arm"),
+        "l4re" => arch == "x86_64",
+        "freebsd" | "vxworks" => matches!(arch, "aarch64" | "arm" | "powerpc" | "powerpc64"),
+        "netbsd" => matches!(arch, "aarch64" | "arm" | "powerpc"),
+        "openbsd" | "fuchsia" => arch == "aarch64",
+        _ => false,
+    };
+    !unsigned
+}
+
+/// Formats a substitution index as an Itanium sequence id.
+fn base36(mut n: usize) -> String {
+    const DIGITS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    let mut digits = Vec::new();
+    loop {
+        digits.push(DIGITS[n % 36]);
+        n /= 36;
+        if n == 0 {
+            break;
+        }
+    }
+    digits.reverse();
+    String::from_utf8(digits).unwrap()
+}
@@ -43,6 +43,15 @@ pub enum Abi {
     RustCall,
     PlatformIntrinsic,
     Unadjusted,
+
+    // Variants of the C-like ABIs above that are allowed to unwind
+    //
+    // These come last so that adding them did not renumber the ABIs
+    // above (see the note at the top of this enum).
+    CUnwind,
+    SystemUnwind,
+    StdcallUnwind,
+    ThiscallUnwind,
 }
 
 #[derive(Copy, Clone)]
@@ -87,6 +96,11 @@ const AbiDatas: &[AbiData] = &[
     AbiData { abi: Abi::RustCall, name: "rust-call", generic: true },
     AbiData { abi: Abi::PlatformIntrinsic, name: "platform-intrinsic", generic: true },
     AbiData { abi: Abi::Unadjusted, name: "unadjusted", generic: true },
+    // Unwinding ABIs
+    AbiData { abi: Abi::CUnwind, name: "C-unwind", generic: true },
+    AbiData { abi: Abi::SystemUnwind, name: "system-unwind", generic: true },
+    AbiData { abi: Abi::StdcallUnwind, name: "stdcall-unwind", generic: false },
+    AbiData { abi: Abi::ThiscallUnwind, name: "thiscall-unwind", generic: false },
 ];
 
 /// Returns the ABI with the given name (if any).
@@ -116,6 +130,15 @@ impl Abi {
     pub fn generic(self) -> bool {
         self.data().generic
     }
+
+    /// Whether functions with this ABI are allowed to unwind, i.e. whether a
+    /// panic or a foreign exception may propagate out of them.
+    pub fn is_unwind(self) -> bool {
+        match self {
+            Abi::CUnwind | Abi::SystemUnwind | Abi::StdcallUnwind | Abi::ThiscallUnwind => true,
+            _ => false,
+        }
+    }
 }
 
 impl fmt::Display for Abi {
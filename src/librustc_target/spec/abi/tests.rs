@@ -13,6 +13,13 @@ fn lookup_cdecl() {
     assert!(abi.is_some() && abi.unwrap().data().name == "cdecl");
 }
 
+#[test]
+fn lookup_c_unwind() {
+    let abi = lookup("C-unwind");
+    assert!(abi.is_some() && abi.unwrap().data().name == "C-unwind");
+    assert!(abi.unwrap().is_unwind());
+}
+
 #[test]
 fn lookup_baz() {
     let abi = lookup("baz");
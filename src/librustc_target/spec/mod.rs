@@ -382,6 +382,48 @@ impl ToJson for TlsModel {
     }
 }
 
+/// Which functions get stack canaries that are checked before returning, to detect overflows of
+/// stack buffers. Corresponds to the `-fstack-protector*` family of options in GCC/Clang.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum StackProtector {
+    /// No function is protected.
+    None,
+    /// Functions with character arrays, or large arrays or `alloca`s, are protected. This is
+    /// LLVM's `ssp` attribute.
+    Basic,
+    /// Functions with any array, or any local whose address is taken, are protected. This is
+    /// LLVM's `sspstrong` attribute.
+    Strong,
+    /// Every function is protected. This is LLVM's `sspreq` attribute.
+    All,
+}
+
+impl FromStr for StackProtector {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<StackProtector, ()> {
+        Ok(match s {
+            "none" => StackProtector::None,
+            "basic" => StackProtector::Basic,
+            "strong" => StackProtector::Strong,
+            "all" => StackProtector::All,
+            _ => return Err(()),
+        })
+    }
+}
+
+impl ToJson for StackProtector {
+    fn to_json(&self) -> Json {
+        match *self {
+            StackProtector::None => "none",
+            StackProtector::Basic => "basic",
+            StackProtector::Strong => "strong",
+            StackProtector::All => "all",
+        }
+        .to_json()
+    }
+}
+
 /// Everything is flattened to a single enum to make the json encoding/decoding less annoying.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub enum LinkOutputKind {
@@ -799,6 +841,9 @@ pub struct TargetOptions {
     /// TLS model to use. Options are "global-dynamic" (default), "local-dynamic", "initial-exec"
     /// and "local-exec". This is similar to the -ftls-model option in GCC/Clang.
     pub tls_model: TlsModel,
+    /// Which functions get stack canaries by default. Options are "none" (default), "basic",
+    /// "strong" and "all". This is similar to the -fstack-protector options in GCC/Clang.
+    pub stack_protector: StackProtector,
     /// Do not emit code that uses the "red zone", if the ABI has one. Defaults to false.
     pub disable_redzone: bool,
     /// Eliminate frame pointers from stack frames if possible. Defaults to true.
@@ -1015,6 +1060,7 @@ impl Default for TargetOptions {
             relocation_model: RelocModel::Pic,
             code_model: None,
             tls_model: TlsModel::GeneralDynamic,
+            stack_protector: StackProtector::None,
             disable_redzone: false,
             eliminate_frame_pointer: true,
             function_sections: true,
@@ -1100,12 +1146,22 @@ impl Target {
                     Abi::C
                 }
             }
+            Abi::SystemUnwind => {
+                if self.options.is_like_windows && self.arch == "x86" {
+                    Abi::StdcallUnwind
+                } else {
+                    Abi::CUnwind
+                }
+            }
             // These ABI kinds are ignored on non-x86 Windows targets.
             // See https://docs.microsoft.com/en-us/cpp/cpp/argument-passing-and-naming-conventions
             // and the individual pages for __stdcall et al.
             Abi::Stdcall | Abi::Fastcall | Abi::Vectorcall | Abi::Thiscall => {
                 if self.options.is_like_windows && self.arch != "x86" { Abi::C } else { abi }
             }
+            Abi::StdcallUnwind | Abi::ThiscallUnwind => {
+                if self.options.is_like_windows && self.arch != "x86" { Abi::CUnwind } else { abi }
+            }
             Abi::EfiApi => {
                 if self.arch == "x86_64" {
                     Abi::Win64
@@ -1239,6 +1295,18 @@ impl Target {
                     Some(Ok(()))
                 })).unwrap_or(Ok(()))
             } );
+            ($key_name:ident, StackProtector) => ( {
+                let name = (stringify!($key_name)).replace("_", "-");
+                obj.find(&name[..]).and_then(|o| o.as_string().and_then(|s| {
+                    match s.parse::<StackProtector>() {
+                        Ok(stack_protector) => base.options.$key_name = stack_protector,
+                        _ => return Some(Err(format!("'{}' is not a valid stack protector. \
+                                                      Use 'none', 'basic', 'strong' or 'all'.",
+                                                      s))),
+                    }
+                    Some(Ok(()))
+                })).unwrap_or(Ok(()))
+            } );
             ($key_name:ident, PanicStrategy) => ( {
                 let name = (stringify!($key_name)).replace("_", "-");
                 obj.find(&name[..]).and_then(|o| o.as_string().and_then(|s| {
@@ -1419,6 +1487,7 @@ impl Target {
         key!(relocation_model, RelocModel)?;
         key!(code_model, CodeModel)?;
         key!(tls_model, TlsModel)?;
+        key!(stack_protector, StackProtector)?;
         key!(disable_redzone, bool);
         key!(eliminate_frame_pointer, bool);
         key!(function_sections, bool);
@@ -1657,6 +1726,7 @@ impl ToJson for Target {
         target_option_val!(relocation_model);
         target_option_val!(code_model);
         target_option_val!(tls_model);
+        target_option_val!(stack_protector);
         target_option_val!(disable_redzone);
         target_option_val!(eliminate_frame_pointer);
         target_option_val!(function_sections);
@@ -67,6 +67,8 @@ pub fn target() -> TargetResult {
                 Abi::Msp430Interrupt,
                 Abi::X86Interrupt,
                 Abi::AmdGpuKernel,
+                Abi::StdcallUnwind,
+                Abi::ThiscallUnwind,
             ],
 
             ..Default::default()
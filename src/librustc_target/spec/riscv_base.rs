@@ -16,5 +16,7 @@ pub fn unsupported_abis() -> Vec<Abi> {
         Abi::Msp430Interrupt,
         Abi::X86Interrupt,
         Abi::AmdGpuKernel,
+        Abi::StdcallUnwind,
+        Abi::ThiscallUnwind,
     ]
 }
@@ -1777,7 +1777,12 @@ impl<'a, 'tcx> InferCtxtExt<'tcx> for InferCtxt<'a, 'tcx> {
                          #49147 <https://github.com/rust-lang/rust/issues/49147> \
                          for more information",
                     );
-                    if tcx.sess.opts.unstable_features.is_nightly_build() {
+                    if tcx.features().inline_const {
+                        err.help(
+                            "wrap the repeated element in an inline `const { ... }` block \
+                             to evaluate it at compile-time",
+                        );
+                    } else if tcx.sess.opts.unstable_features.is_nightly_build() {
                         err.help(
                             "add `#![feature(const_in_array_repeat_expressions)]` to the \
                              crate attributes to enable",
@@ -12,6 +12,7 @@ use crate::check::BreakableCtxt;
 use crate::check::Diverges;
 use crate::check::Expectation::{self, ExpectCastableToType, ExpectHasType, NoExpectation};
 use crate::check::FnCtxt;
+use crate::check::GatherLocalsVisitor;
 use crate::check::Needs;
 use crate::check::TupleArgumentsFlag::DontTupleArguments;
 use crate::type_error_struct;
@@ -25,6 +26,7 @@ use rustc_errors::{pluralize, struct_span_err, Applicability, DiagnosticBuilder,
 use rustc_hir as hir;
 use rustc_hir::def::{CtorKind, DefKind, Res};
 use rustc_hir::def_id::DefId;
+use rustc_hir::intravisit::Visitor;
 use rustc_hir::lang_items;
 use rustc_hir::{ExprKind, QPath};
 use rustc_infer::infer;
@@ -278,6 +280,9 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
             }
             ExprKind::DropTemps(ref e) => self.check_expr_with_expectation(e, expected),
             ExprKind::Array(ref args) => self.check_expr_array(args, expected, expr),
+            ExprKind::ConstBlock(ref anon_const) => {
+                self.check_expr_const_block(anon_const, expected)
+            }
             ExprKind::Repeat(ref element, ref count) => {
                 self.check_expr_repeat(element, count, expected, expr)
             }
@@ -735,9 +740,10 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
                 DiagnosticId::Error(err_code.into()),
             );
             err.span_label(lhs.span, "cannot assign to this expression");
+            // Destructuring assignments with `=` are desugared before type checking, so this is a
+            // compound assignment.
             if self.is_destructuring_place_expr(lhs) {
-                err.note("destructuring assignments are not currently supported");
-                err.note("for more information, see https://github.com/rust-lang/rfcs/issues/372");
+                err.note("destructuring assignments can only be used with `=`");
             }
             err.emit();
         }
@@ -989,6 +995,24 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
         self.tcx.mk_array(element_ty, args.len() as u64)
     }
 
+    fn check_expr_const_block(
+        &self,
+        anon_const: &'tcx hir::AnonConst,
+        expected: Expectation<'tcx>,
+    ) -> Ty<'tcx> {
+        // Inline constants are type-checked together with the body they appear in, like
+        // closures, so that their type can be inferred from the surrounding expression. They
+        // get their own `FnCtxt` as `return` and `break` cannot leave the constant.
+        let body = self.tcx.hir().body(anon_const.body);
+        let fcx = FnCtxt::new(self.inh, self.param_env, body.value.hir_id);
+        GatherLocalsVisitor { fcx: &fcx, parent_id: anon_const.hir_id }.visit_body(body);
+
+        let ty = fcx.check_expr_with_expectation(&body.value, expected);
+        fcx.require_type_is_sized(ty, body.value.span, traits::ConstSized);
+        fcx.write_ty(anon_const.hir_id, ty);
+        ty
+    }
+
     fn check_expr_repeat(
         &self,
         element: &'tcx hir::Expr<'tcx>,
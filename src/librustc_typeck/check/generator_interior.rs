@@ -11,7 +11,9 @@ use rustc_hir::def_id::DefId;
 use rustc_hir::intravisit::{self, NestedVisitorMap, Visitor};
 use rustc_hir::{Expr, ExprKind, Pat, PatKind};
 use rustc_middle::middle::region::{self, YieldData};
-use rustc_middle::ty::{self, Ty};
+use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_session::lint;
+use rustc_span::symbol::sym;
 use rustc_span::Span;
 
 struct InteriorVisitor<'a, 'tcx> {
@@ -21,12 +23,14 @@ struct InteriorVisitor<'a, 'tcx> {
     expr_count: usize,
     kind: hir::GeneratorKind,
     prev_unresolved_span: Option<Span>,
+    linted_values: FxHashSet<hir::HirId>,
 }
 
 impl<'a, 'tcx> InteriorVisitor<'a, 'tcx> {
     fn record(
         &mut self,
         ty: Ty<'tcx>,
+        hir_id: hir::HirId,
         scope: Option<region::Scope>,
         expr: Option<&'tcx Expr<'tcx>>,
         source_span: Span,
@@ -88,6 +92,21 @@ impl<'a, 'tcx> InteriorVisitor<'a, 'tcx> {
                     .span_note(yield_data.span, &*note)
                     .emit();
             } else {
+                // Values without a scope are only conservatively assumed to
+                // be live across a yield, so don't lint about those.
+                if scope.is_some()
+                    && !self.linted_values.contains(&hir_id)
+                    && check_must_not_suspend_ty(
+                        self.fcx.tcx,
+                        ty,
+                        hir_id,
+                        source_span,
+                        yield_data.span,
+                    )
+                {
+                    self.linted_values.insert(hir_id);
+                }
+
                 // Map the type to the number of types added before it
                 let entries = self.types.len();
                 let scope_span = scope.map(|s| s.span(self.fcx.tcx, self.region_scope_tree));
@@ -137,6 +156,7 @@ pub fn resolve_interior<'a, 'tcx>(
         expr_count: 0,
         kind,
         prev_unresolved_span: None,
+        linted_values: FxHashSet::default(),
     };
     intravisit::walk_body(&mut visitor, body);
 
@@ -223,7 +243,7 @@ impl<'a, 'tcx> Visitor<'tcx> for InteriorVisitor<'a, 'tcx> {
         if let PatKind::Binding(..) = pat.kind {
             let scope = self.region_scope_tree.var_scope(pat.hir_id.local_id);
             let ty = self.fcx.typeck_results.borrow().pat_ty(pat);
-            self.record(ty, Some(scope), None, pat.span);
+            self.record(ty, pat.hir_id, Some(scope), None, pat.span);
         }
     }
 
@@ -264,7 +284,7 @@ impl<'a, 'tcx> Visitor<'tcx> for InteriorVisitor<'a, 'tcx> {
         // If there are adjustments, then record the final type --
         // this is the actual value that is being produced.
         if let Some(adjusted_ty) = self.fcx.typeck_results.borrow().expr_ty_adjusted_opt(expr) {
-            self.record(adjusted_ty, scope, Some(expr), expr.span);
+            self.record(adjusted_ty, expr.hir_id, scope, Some(expr), expr.span);
         }
 
         // Also record the unadjusted type (which is the only type if
@@ -292,9 +312,64 @@ impl<'a, 'tcx> Visitor<'tcx> for InteriorVisitor<'a, 'tcx> {
         // The type table might not have information for this expression
         // if it is in a malformed scope. (#66387)
         if let Some(ty) = self.fcx.typeck_results.borrow().expr_ty_opt(expr) {
-            self.record(ty, scope, Some(expr), expr.span);
+            self.record(ty, expr.hir_id, scope, Some(expr), expr.span);
         } else {
             self.fcx.tcx.sess.delay_span_bug(expr.span, "no type for node");
         }
     }
 }
+
+/// Emits the `must_not_suspend` lint if `ty` is, or directly contains as a
+/// tuple or array element, a type marked `#[must_not_suspend]`. Returns
+/// whether the lint was emitted.
+fn check_must_not_suspend_ty<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    ty: Ty<'tcx>,
+    hir_id: hir::HirId,
+    source_span: Span,
+    yield_span: Span,
+) -> bool {
+    match ty.kind {
+        ty::Adt(def, _) => {
+            check_must_not_suspend_def(tcx, def.did, hir_id, source_span, yield_span)
+        }
+        ty::Tuple(_) => ty
+            .tuple_fields()
+            .any(|ty| check_must_not_suspend_ty(tcx, ty, hir_id, source_span, yield_span)),
+        ty::Array(ty, _) => check_must_not_suspend_ty(tcx, ty, hir_id, source_span, yield_span),
+        _ => false,
+    }
+}
+
+fn check_must_not_suspend_def(
+    tcx: TyCtxt<'_>,
+    def_id: DefId,
+    hir_id: hir::HirId,
+    source_span: Span,
+    yield_span: Span,
+) -> bool {
+    for attr in tcx.get_attrs(def_id).iter() {
+        if tcx.sess.check_name(attr, sym::must_not_suspend) {
+            tcx.struct_span_lint_hir(lint::builtin::MUST_NOT_SUSPEND, hir_id, source_span, |lint| {
+                let msg = format!(
+                    "`{}` held across a suspend point, but should not be",
+                    tcx.def_path_str(def_id)
+                );
+                let mut err = lint.build(&msg);
+                err.span_label(yield_span, "the value is held across this suspend point");
+                // check for #[must_not_suspend = "..."]
+                if let Some(note) = attr.value_str() {
+                    err.span_note(source_span, &note.as_str());
+                }
+                err.span_help(
+                    source_span,
+                    "consider using a block (`{ ... }`) to shrink the value's scope, \
+                     ending before the suspend point",
+                );
+                err.emit();
+            });
+            return true;
+        }
+    }
+    false
+}
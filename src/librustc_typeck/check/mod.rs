@@ -822,6 +822,20 @@ fn primary_body_of(
     }
 }
 
+/// If `def_id` is an inline `const` block, returns the body it appears in.
+fn inline_const_enclosing_body(tcx: TyCtxt<'_>, def_id: LocalDefId) -> Option<LocalDefId> {
+    let hir = tcx.hir();
+    let id = hir.local_def_id_to_hir_id(def_id);
+    match hir.find(hir.get_parent_node(id)) {
+        Some(Node::Expr(&hir::Expr { kind: ExprKind::ConstBlock(ref anon_const), .. }))
+            if anon_const.hir_id == id =>
+        {
+            Some(hir.local_def_id(hir.enclosing_body_owner(id)))
+        }
+        _ => None,
+    }
+}
+
 fn has_typeck_results(tcx: TyCtxt<'_>, def_id: DefId) -> bool {
     // Closures' typeck results come from their outermost function,
     // as they are part of the same "inference environment".
@@ -831,6 +845,10 @@ fn has_typeck_results(tcx: TyCtxt<'_>, def_id: DefId) -> bool {
     }
 
     if let Some(def_id) = def_id.as_local() {
+        if let Some(outer_def_id) = inline_const_enclosing_body(tcx, def_id) {
+            return tcx.has_typeck_results(outer_def_id.to_def_id());
+        }
+
         let id = tcx.hir().local_def_id_to_hir_id(def_id);
         primary_body_of(tcx, id).is_some()
     } else {
@@ -992,6 +1010,11 @@ fn typeck_with_fallback<'tcx>(
         return tcx.typeck(outer_def_id);
     }
 
+    // So are the typeck results of inline `const` blocks, see `check_expr_const_block`.
+    if let Some(outer_def_id) = inline_const_enclosing_body(tcx, def_id) {
+        return tcx.typeck(outer_def_id);
+    }
+
     let id = tcx.hir().as_local_hir_id(def_id);
     let span = tcx.hir().span(id);
 
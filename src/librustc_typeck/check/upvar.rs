@@ -29,18 +29,36 @@
 //! `ty::InferBorrow(upvar_id)` or something like that, but this would
 //! then mean that all later passes would have to check for these figments
 //! and report an error, and it just seems like more mess in the end.)
+//!
+//! ### Disjoint captures
+//!
+//! With `#![feature(capture_disjoint_fields)]`, closures capture the places
+//! they use (e.g., `self.a`) rather than the whole variables those places are
+//! rooted at. While walking the closure body, we record every place rooted at
+//! an upvar along with the kind of capture it needs, truncating the places that
+//! cannot be captured on their own (see `restrict_capture_precision()`). The
+//! places are then reduced to a minimal set in `compute_min_captures()`, where a
+//! place absorbs any of its descendants, and stored in
+//! `TypeckResults::closure_min_captures`. Each place in that set becomes a field
+//! of the closure environment.
+//!
+//! Without the feature, the same places are used to find closures whose drop
+//! order would change once the feature is enabled, which are reported by the
+//! `disjoint_capture_drop_reorder` lint.
 
 use super::FnCtxt;
 
 use crate::expr_use_visitor as euv;
 use rustc_data_structures::fx::FxIndexMap;
+use rustc_errors::Applicability;
 use rustc_hir as hir;
 use rustc_hir::def_id::DefId;
 use rustc_hir::def_id::LocalDefId;
 use rustc_hir::intravisit::{self, NestedVisitorMap, Visitor};
 use rustc_infer::infer::UpvarRegion;
-use rustc_middle::hir::place::{PlaceBase, PlaceWithHirId};
-use rustc_middle::ty::{self, Ty, TyCtxt, UpvarSubsts};
+use rustc_middle::hir::place::{Place, PlaceBase, PlaceWithHirId, ProjectionKind};
+use rustc_middle::ty::{self, Ty, TyCtxt, TypeFoldable, UpvarSubsts};
+use rustc_session::lint;
 use rustc_span::{Span, Symbol};
 
 impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
@@ -68,6 +86,10 @@ impl<'a, 'tcx> Visitor<'tcx> for InferBorrowKindVisitor<'a, 'tcx> {
             let body = self.fcx.tcx.hir().body(body_id);
             self.visit_body(body);
             self.fcx.analyze_closure(expr.hir_id, expr.span, body, cc);
+        } else if let hir::ExprKind::ConstBlock(ref anon_const) = expr.kind {
+            // Closures in inline constants are type-checked with the enclosing body.
+            let body = self.fcx.tcx.hir().body(anon_const.body);
+            self.visit_body(body);
         }
 
         intravisit::walk_expr(self, expr);
@@ -110,6 +132,10 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
             None
         };
 
+        // Generators always capture whole variables, see `check_closure`.
+        let capture_disjoint_fields = self.tcx.features().capture_disjoint_fields
+            && matches!(substs, UpvarSubsts::Closure(_));
+
         if let Some(upvars) = self.tcx.upvars_mentioned(closure_def_id) {
             let mut closure_captures: FxIndexMap<hir::HirId, ty::UpvarId> =
                 FxIndexMap::with_capacity_and_hasher(upvars.len(), Default::default());
@@ -155,6 +181,8 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
             current_closure_kind: ty::ClosureKind::LATTICE_BOTTOM,
             current_origin: None,
             adjust_upvar_captures: ty::UpvarCaptureMap::default(),
+            capture_clause,
+            capture_information: Default::default(),
         };
         euv::ExprUseVisitor::new(
             &mut delegate,
@@ -183,6 +211,8 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
 
         self.typeck_results.borrow_mut().upvar_capture_map.extend(delegate.adjust_upvar_captures);
 
+        let min_captures = self.compute_min_captures(closure_def_id, delegate.capture_information);
+
         // Now that we've analyzed the closure, we know how each
         // variable is borrowed, and we know what traits the closure
         // implements (Fn vs FnMut etc). We now have some updates to do
@@ -196,13 +226,41 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
         // inference algorithm will reject it).
 
         // Equate the type variables for the upvars with the actual types.
-        let final_upvar_tys = self.final_upvar_tys(closure_hir_id);
-        debug!(
-            "analyze_closure: id={:?} substs={:?} final_upvar_tys={:?}",
-            closure_hir_id, substs, final_upvar_tys
-        );
-        for (upvar_ty, final_upvar_ty) in substs.upvar_tys().zip(final_upvar_tys) {
-            self.demand_suptype(span, upvar_ty, final_upvar_ty);
+        if let (true, UpvarSubsts::Closure(closure_substs)) = (capture_disjoint_fields, substs) {
+            let final_upvar_tys = self.final_upvar_tys_for_captures(&min_captures);
+            debug!(
+                "analyze_closure: id={:?} substs={:?} final_upvar_tys={:?}",
+                closure_hir_id, substs, final_upvar_tys
+            );
+            let final_tupled_upvars_ty = self.tcx.mk_tup(final_upvar_tys.into_iter());
+            self.demand_suptype(
+                span,
+                closure_substs.as_closure().tupled_upvars_ty(),
+                final_tupled_upvars_ty,
+            );
+            self.typeck_results
+                .borrow_mut()
+                .closure_min_captures
+                .insert(closure_def_id, min_captures);
+        } else {
+            let final_upvar_tys = self.final_upvar_tys(closure_hir_id);
+            debug!(
+                "analyze_closure: id={:?} substs={:?} final_upvar_tys={:?}",
+                closure_hir_id, substs, final_upvar_tys
+            );
+            for (upvar_ty, final_upvar_ty) in substs.upvar_tys().zip(final_upvar_tys) {
+                self.demand_suptype(span, upvar_ty, final_upvar_ty);
+            }
+
+            if let UpvarSubsts::Closure(_) = substs {
+                self.lint_disjoint_capture_drop_reorder(
+                    closure_hir_id,
+                    closure_def_id,
+                    span,
+                    body.value.span,
+                    &min_captures,
+                );
+            }
         }
 
         // If we are also inferred the closure kind here,
@@ -213,6 +271,228 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
         }
     }
 
+    /// Reduces the places accessed by the closure to a minimal set of places to capture, in
+    /// which no place is an ancestor of another: a place absorbs all of its descendants, and
+    /// is then captured with the strongest capture kind any of them needs.
+    ///
+    /// Every variable mentioned by the closure is captured, even if no place rooted at it is
+    /// accessed (e.g., `let _ = x;`), and the variables are kept in `upvars_mentioned` order.
+    fn compute_min_captures(
+        &self,
+        closure_def_id: DefId,
+        capture_information: FxIndexMap<Place<'tcx>, ty::UpvarCapture<'tcx>>,
+    ) -> ty::RootVariableMinCaptureList<'tcx> {
+        let mut root_var_min_capture_list = ty::RootVariableMinCaptureList::default();
+
+        if let Some(upvars) = self.tcx.upvars_mentioned(closure_def_id) {
+            for &var_hir_id in upvars.keys() {
+                root_var_min_capture_list.insert(var_hir_id, Vec::new());
+            }
+        }
+
+        for (place, capture_kind) in capture_information {
+            let var_hir_id = match place.base {
+                PlaceBase::Upvar(upvar_id) => upvar_id.var_path.hir_id,
+                base => bug!("expected upvar, found={:?}", base),
+            };
+            let min_cap_list =
+                root_var_min_capture_list.entry(var_hir_id).or_insert_with(Vec::new);
+
+            // Any captured descendant of `place` is now captured through `place`.
+            let mut capture_kind = capture_kind;
+            min_cap_list.retain(|possible_descendant| {
+                if !is_ancestor_or_same_capture(&place, &possible_descendant.place) {
+                    return true;
+                }
+                let mut descendant_place = possible_descendant.place.clone();
+                let mut descendant_kind = possible_descendant.capture_kind;
+                truncate_place_to_len(
+                    &mut descendant_place,
+                    &mut descendant_kind,
+                    place.projections.len(),
+                );
+                capture_kind = max_capture_kind(capture_kind, descendant_kind);
+                false
+            });
+
+            // If an ancestor of `place` is already captured, `place` is captured through it.
+            if let Some(ancestor) = min_cap_list.iter_mut().find(|possible_ancestor| {
+                is_ancestor_or_same_capture(&possible_ancestor.place, &place)
+            }) {
+                let mut place = place;
+                let ancestor_len = ancestor.place.projections.len();
+                truncate_place_to_len(&mut place, &mut capture_kind, ancestor_len);
+                ancestor.capture_kind = max_capture_kind(ancestor.capture_kind, capture_kind);
+                continue;
+            }
+
+            let mutability = self.determine_capture_mutability(&place);
+            min_cap_list.push(ty::CapturedPlace { place, capture_kind, mutability });
+        }
+
+        // Variables the closure doesn't access any place of are captured as a whole.
+        for (&var_hir_id, min_cap_list) in root_var_min_capture_list.iter_mut() {
+            if min_cap_list.is_empty() {
+                let upvar_id = ty::UpvarId {
+                    var_path: ty::UpvarPath { hir_id: var_hir_id },
+                    closure_expr_id: closure_def_id.expect_local(),
+                };
+                let place = Place {
+                    base_ty: self.node_ty(var_hir_id),
+                    base: PlaceBase::Upvar(upvar_id),
+                    projections: vec![],
+                };
+                let capture_kind = self.typeck_results.borrow().upvar_capture(upvar_id);
+                let mutability = self.determine_capture_mutability(&place);
+                min_cap_list.push(ty::CapturedPlace { place, capture_kind, mutability });
+            }
+        }
+
+        debug!("compute_min_captures({:?}) = {:?}", closure_def_id, root_var_min_capture_list);
+        root_var_min_capture_list
+    }
+
+    /// Returns whether the place captured by a closure can be mutated through the capture,
+    /// see `ty::CapturedPlace::mutability`.
+    fn determine_capture_mutability(&self, place: &Place<'tcx>) -> hir::Mutability {
+        let var_hir_id = match place.base {
+            PlaceBase::Upvar(upvar_id) => upvar_id.var_path.hir_id,
+            base => bug!("expected upvar, found={:?}", base),
+        };
+        let bm = *self
+            .typeck_results
+            .borrow()
+            .pat_binding_modes()
+            .get(var_hir_id)
+            .expect("missing binding mode");
+        let mut mutability = match bm {
+            ty::BindByValue(mutability) => mutability,
+            ty::BindByReference(_) => hir::Mutability::Not,
+        };
+
+        for pointer_ty in place.deref_tys() {
+            match pointer_ty.kind {
+                // Dereferencing a `&mut` allows mutating the dereferenced place.
+                ty::Ref(.., hir::Mutability::Mut) => mutability = hir::Mutability::Mut,
+                // Dereferencing a box doesn't change the mutability.
+                ty::Adt(def, ..) if def.is_box() => {}
+                // Dereferencing a shared reference never allows mutation, and raw
+                // pointers are never dereferenced in captured places.
+                _ => return hir::Mutability::Not,
+            }
+        }
+
+        mutability
+    }
+
+    /// Returns the types of the fields of the closure environment for the given captures.
+    fn final_upvar_tys_for_captures(
+        &self,
+        min_captures: &ty::RootVariableMinCaptureList<'tcx>,
+    ) -> Vec<Ty<'tcx>> {
+        min_captures
+            .values()
+            .flat_map(|captures| captures.iter())
+            .map(|captured_place| {
+                let upvar_ty = captured_place.place.ty();
+                match captured_place.capture_kind {
+                    ty::UpvarCapture::ByValue => upvar_ty,
+                    ty::UpvarCapture::ByRef(borrow) => self.tcx.mk_ref(
+                        borrow.region,
+                        ty::TypeAndMut { ty: upvar_ty, mutbl: borrow.kind.to_mutbl_lossy() },
+                    ),
+                }
+            })
+            .collect()
+    }
+
+    /// Reports closures that take some variable by value, but would only capture parts of it
+    /// with `capture_disjoint_fields`, which changes when the rest of the variable is dropped.
+    fn lint_disjoint_capture_drop_reorder(
+        &self,
+        closure_hir_id: hir::HirId,
+        closure_def_id: DefId,
+        span: Span,
+        body_span: Span,
+        min_captures: &ty::RootVariableMinCaptureList<'tcx>,
+    ) {
+        let mut need_migrations = Vec::new();
+
+        for (&var_hir_id, captures) in min_captures.iter() {
+            let upvar_id = ty::UpvarId {
+                var_path: ty::UpvarPath { hir_id: var_hir_id },
+                closure_expr_id: closure_def_id.expect_local(),
+            };
+            // Only variables moved into the closure are dropped along with it.
+            if self.typeck_results.borrow().upvar_capture(upvar_id) != ty::UpvarCapture::ByValue {
+                continue;
+            }
+
+            let ty = self.resolve_vars_if_possible(&self.node_ty(var_hir_id));
+            let ty = self.tcx.erase_regions(&ty);
+            if ty.needs_infer() || !ty.needs_drop(self.tcx, self.param_env) {
+                continue;
+            }
+
+            let is_fully_captured = match captures[..] {
+                [ref captured_place] => captured_place.place.projections.is_empty(),
+                _ => false,
+            };
+            if !is_fully_captured {
+                need_migrations.push(var_hir_id);
+            }
+        }
+
+        if need_migrations.is_empty() {
+            return;
+        }
+
+        self.tcx.struct_span_lint_hir(
+            lint::builtin::DISJOINT_CAPTURE_DROP_REORDER,
+            closure_hir_id,
+            span,
+            |lint| {
+                let names: Vec<_> = need_migrations
+                    .iter()
+                    .map(|&var_hir_id| format!("`{}`", var_name(self.tcx, var_hir_id)))
+                    .collect();
+                let refs: Vec<_> = need_migrations
+                    .iter()
+                    .map(|&var_hir_id| format!("&{}", var_name(self.tcx, var_hir_id)))
+                    .collect();
+                let dummy_let = if refs.len() == 1 {
+                    format!("let _ = {};", refs[0])
+                } else {
+                    format!("let _ = ({});", refs.join(", "))
+                };
+                let msg =
+                    format!("add a dummy let to cause {} to be fully captured", names.join(", "));
+                let mut err = lint
+                    .build("drop order affected for closure because of `capture_disjoint_fields`");
+                match self.tcx.sess.source_map().span_to_snippet(body_span) {
+                    Ok(body) => {
+                        // Put the dummy let at the start of the closure body, turning the body
+                        // into a block first if it isn't one already.
+                        let sugg = match body.strip_prefix('{') {
+                            Some(rest) => format!("{{ {}{}", dummy_let, rest),
+                            None => format!("{{ {} {} }}", dummy_let, body),
+                        };
+                        err.span_suggestion_verbose(
+                            body_span,
+                            &msg,
+                            sugg,
+                            Applicability::MachineApplicable,
+                        );
+                    }
+                    Err(_) => {
+                        err.note(&format!("{}: `{}`", msg, dummy_let));
+                    }
+                }
+                err.emit()
+            },
+        );
+    }
+
     // Returns a list of `Ty`s for each upvar.
     fn final_upvar_tys(&self, closure_id: hir::HirId) -> Vec<Ty<'tcx>> {
         // Presently an unboxed closure type cannot "escape" out of a
@@ -270,6 +550,14 @@ struct InferBorrowKind<'a, 'tcx> {
     // For each upvar that we access, we track the minimal kind of
     // access we need (ref, ref mut, move, etc).
     adjust_upvar_captures: ty::UpvarCaptureMap<'tcx>,
+
+    // The capture clause of the closure, `move` or not.
+    capture_clause: hir::CaptureBy,
+
+    // For each place rooted at an upvar that the closure accesses, the
+    // minimal kind of capture it needs, after truncating the place to
+    // something that can be captured on its own. See the module docs.
+    capture_information: FxIndexMap<Place<'tcx>, ty::UpvarCapture<'tcx>>,
 }
 
 impl<'a, 'tcx> InferBorrowKind<'a, 'tcx> {
@@ -425,6 +713,46 @@ impl<'a, 'tcx> InferBorrowKind<'a, 'tcx> {
         }
     }
 
+    /// Records that the closure accesses `place_with_id`, which needs it to be captured by
+    /// value if `borrow_kind` is `None`, and by a borrow of that kind otherwise.
+    fn record_capture(
+        &mut self,
+        place_with_id: &PlaceWithHirId<'tcx>,
+        borrow_kind: Option<ty::BorrowKind>,
+    ) {
+        let upvar_id = match place_with_id.place.base {
+            PlaceBase::Upvar(upvar_id) => upvar_id,
+            _ => return,
+        };
+        if upvar_id.closure_expr_id.to_def_id() != self.closure_def_id {
+            return;
+        }
+
+        let capture_kind = match (self.capture_clause, borrow_kind) {
+            (hir::CaptureBy::Value, _) | (hir::CaptureBy::Ref, None) => ty::UpvarCapture::ByValue,
+            (hir::CaptureBy::Ref, Some(kind)) => {
+                match self.fcx.typeck_results.borrow().upvar_capture(upvar_id) {
+                    ty::UpvarCapture::ByRef(upvar_borrow) => {
+                        ty::UpvarCapture::ByRef(ty::UpvarBorrow { kind, ..upvar_borrow })
+                    }
+                    ty::UpvarCapture::ByValue => {
+                        bug!("by-value seed for upvar {:?} of a non-`move` closure", upvar_id)
+                    }
+                }
+            }
+        };
+
+        let (place, capture_kind) =
+            restrict_capture_precision(self.fcx.tcx, place_with_id.place.clone(), capture_kind);
+        debug!("record_capture(place={:?}, capture_kind={:?})", place, capture_kind);
+
+        let capture_kind = match self.capture_information.get(&place) {
+            Some(&existing) => max_capture_kind(existing, capture_kind),
+            None => capture_kind,
+        };
+        self.capture_information.insert(place, capture_kind);
+    }
+
     fn adjust_closure_kind(
         &mut self,
         closure_id: LocalDefId,
@@ -472,10 +800,16 @@ impl<'a, 'tcx> euv::Delegate<'tcx> for InferBorrowKind<'a, 'tcx> {
     fn consume(&mut self, place_with_id: &PlaceWithHirId<'tcx>, mode: euv::ConsumeMode) {
         debug!("consume(place_with_id={:?},mode={:?})", place_with_id, mode);
         self.adjust_upvar_borrow_kind_for_consume(place_with_id, mode);
+
+        match mode {
+            euv::Copy => self.record_capture(place_with_id, Some(ty::ImmBorrow)),
+            euv::Move => self.record_capture(place_with_id, None),
+        }
     }
 
     fn borrow(&mut self, place_with_id: &PlaceWithHirId<'tcx>, bk: ty::BorrowKind) {
         debug!("borrow(place_with_id={:?}, bk={:?})", place_with_id, bk);
+        self.record_capture(place_with_id, Some(bk));
 
         match bk {
             ty::ImmBorrow => {}
@@ -492,6 +826,117 @@ impl<'a, 'tcx> euv::Delegate<'tcx> for InferBorrowKind<'a, 'tcx> {
         debug!("mutate(assignee_place={:?})", assignee_place);
 
         self.adjust_upvar_borrow_kind_for_mut(assignee_place);
+        self.record_capture(assignee_place, Some(ty::MutBorrow));
+    }
+
+    fn fake_read(&mut self, place_with_id: &PlaceWithHirId<'tcx>) {
+        debug!("fake_read(place_with_id={:?})", place_with_id);
+        self.record_capture(place_with_id, Some(ty::ImmBorrow));
+    }
+}
+
+/// Truncates a place accessed by a closure so that it can be captured on its own with
+/// `capture_kind`, and returns it along with the adjusted capture kind. The place stops:
+///
+/// - before the dereference of a raw pointer, which needs `unsafe` and so can't
+///   happen when the closure is created;
+/// - before an index, since we don't track which element is used;
+/// - before a field of an enum, a union or a packed struct, whose fields can't be
+///   captured separately;
+/// - for by-value captures, before any dereference (nothing can be moved out of a
+///   reference, and moving out of a box moves the box) and before a field of a
+///   type implementing `Drop`.
+fn restrict_capture_precision<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    mut place: Place<'tcx>,
+    mut capture_kind: ty::UpvarCapture<'tcx>,
+) -> (Place<'tcx>, ty::UpvarCapture<'tcx>) {
+    let is_by_value = capture_kind == ty::UpvarCapture::ByValue;
+    let mut len = place.projections.len();
+
+    for (i, proj) in place.projections.iter().enumerate() {
+        let ty_before = place.ty_before_projection(i);
+        let truncate = match proj.kind {
+            ProjectionKind::Deref if ty_before.is_unsafe_ptr() => {
+                // Raw pointers don't inherit mutability: reading the pointer is enough.
+                if let ty::UpvarCapture::ByRef(ref mut borrow) = capture_kind {
+                    borrow.kind = ty::ImmBorrow;
+                }
+                true
+            }
+            ProjectionKind::Deref => is_by_value,
+            ProjectionKind::Field(..) => match ty_before.kind {
+                ty::Adt(def, _) if def.is_enum() || def.is_union() || def.repr.packed() => true,
+                ty::Adt(def, _) => is_by_value && def.has_dtor(tcx),
+                _ => false,
+            },
+            ProjectionKind::Index | ProjectionKind::Subslice => true,
+        };
+        if truncate {
+            len = i;
+            break;
+        }
+    }
+
+    truncate_place_to_len(&mut place, &mut capture_kind, len);
+    (place, capture_kind)
+}
+
+/// Truncates `place` to its first `len` projections. A mutable borrow that went through a
+/// `&mut` reference in the truncated part only needs a unique borrow of the truncated place,
+/// the same way `adjust_upvar_borrow_kind_for_mut` treats whole variables.
+fn truncate_place_to_len<'tcx>(
+    place: &mut Place<'tcx>,
+    capture_kind: &mut ty::UpvarCapture<'tcx>,
+    len: usize,
+) {
+    if let ty::UpvarCapture::ByRef(ref mut borrow) = *capture_kind {
+        if borrow.kind == ty::MutBorrow {
+            let derefs_mut_ref = (len..place.projections.len()).any(|i| {
+                place.projections[i].kind == ProjectionKind::Deref
+                    && matches!(
+                        place.ty_before_projection(i).kind,
+                        ty::Ref(.., hir::Mutability::Mut)
+                    )
+            });
+            if derefs_mut_ref {
+                borrow.kind = ty::UniqueImmBorrow;
+            }
+        }
+    }
+    place.projections.truncate(len);
+}
+
+/// Returns whether `ancestor` is `descendant` itself or one of the places `descendant`
+/// is projected from. Both places are expected to be captured by the same closure.
+fn is_ancestor_or_same_capture(ancestor: &Place<'_>, descendant: &Place<'_>) -> bool {
+    ancestor.base == descendant.base
+        && ancestor.projections.len() <= descendant.projections.len()
+        && ancestor
+            .projections
+            .iter()
+            .zip(descendant.projections.iter())
+            .all(|(a, d)| a.kind == d.kind)
+}
+
+/// Returns the stronger of two capture kinds: capturing by value is stronger than capturing
+/// by reference, and borrows follow the `ImmBorrow < UniqueImmBorrow < MutBorrow` lattice.
+fn max_capture_kind<'tcx>(
+    a: ty::UpvarCapture<'tcx>,
+    b: ty::UpvarCapture<'tcx>,
+) -> ty::UpvarCapture<'tcx> {
+    let rank = |kind: ty::BorrowKind| match kind {
+        ty::ImmBorrow => 0,
+        ty::UniqueImmBorrow => 1,
+        ty::MutBorrow => 2,
+    };
+    match (a, b) {
+        (ty::UpvarCapture::ByValue, _) | (_, ty::UpvarCapture::ByValue) => {
+            ty::UpvarCapture::ByValue
+        }
+        (ty::UpvarCapture::ByRef(borrow_a), ty::UpvarCapture::ByRef(borrow_b)) => {
+            if rank(borrow_b.kind) > rank(borrow_a.kind) { b } else { a }
+        }
     }
 }
 
@@ -9,6 +9,7 @@ use rustc_hir as hir;
 use rustc_hir::intravisit::{self, NestedVisitorMap, Visitor};
 use rustc_infer::infer::error_reporting::TypeAnnotationNeeded::E0282;
 use rustc_infer::infer::InferCtxt;
+use rustc_middle::hir::place::{Place as HirPlace, Projection as HirProjection};
 use rustc_middle::ty::adjustment::{Adjust, Adjustment, PointerCast};
 use rustc_middle::ty::fold::{TypeFoldable, TypeFolder};
 use rustc_middle::ty::{self, Ty, TyCtxt};
@@ -56,6 +57,7 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
         }
         wbcx.visit_body(body);
         wbcx.visit_upvar_capture_map();
+        wbcx.visit_min_capture_map();
         wbcx.visit_closures();
         wbcx.visit_liberated_fn_sigs();
         wbcx.visit_fru_field_types();
@@ -269,6 +271,12 @@ impl<'cx, 'tcx> Visitor<'tcx> for WritebackCx<'cx, 'tcx> {
 
                 self.visit_body(body);
             }
+            hir::ExprKind::ConstBlock(ref anon_const) => {
+                self.visit_node_id(e.span, anon_const.hir_id);
+
+                let body = self.fcx.tcx.hir().body(anon_const.body);
+                self.visit_body(body);
+            }
             hir::ExprKind::Struct(_, fields, _) => {
                 for field in fields {
                     self.visit_field_id(field.hir_id);
@@ -344,6 +352,56 @@ impl<'cx, 'tcx> WritebackCx<'cx, 'tcx> {
         }
     }
 
+    fn visit_min_capture_map(&mut self) {
+        let min_captures = self.fcx.typeck_results.borrow().closure_min_captures.clone();
+        let mut min_captures_wb = ty::MinCaptureInformationMap::with_capacity_and_hasher(
+            min_captures.len(),
+            Default::default(),
+        );
+        for (&closure_def_id, root_min_captures) in min_captures.iter() {
+            let closure_hir_id = self.tcx().hir().as_local_hir_id(closure_def_id.expect_local());
+            let mut root_var_map_wb = ty::RootVariableMinCaptureList::default();
+            for (&var_hir_id, min_list) in root_min_captures.iter() {
+                let min_list_wb = min_list
+                    .iter()
+                    .map(|captured_place| {
+                        let place = &captured_place.place;
+                        let place = HirPlace {
+                            base_ty: self.resolve(&place.base_ty, &closure_hir_id),
+                            base: place.base,
+                            projections: place
+                                .projections
+                                .iter()
+                                .map(|proj| HirProjection {
+                                    ty: self.resolve(&proj.ty, &closure_hir_id),
+                                    kind: proj.kind,
+                                })
+                                .collect(),
+                        };
+                        let capture_kind = match captured_place.capture_kind {
+                            ty::UpvarCapture::ByValue => ty::UpvarCapture::ByValue,
+                            ty::UpvarCapture::ByRef(ref upvar_borrow) => {
+                                ty::UpvarCapture::ByRef(ty::UpvarBorrow {
+                                    kind: upvar_borrow.kind,
+                                    region: self.tcx().lifetimes.re_erased,
+                                })
+                            }
+                        };
+                        ty::CapturedPlace {
+                            place,
+                            capture_kind,
+                            mutability: captured_place.mutability,
+                        }
+                    })
+                    .collect();
+                root_var_map_wb.insert(var_hir_id, min_list_wb);
+            }
+            debug!("Min captures for {:?} resolved to {:?}", closure_def_id, root_var_map_wb);
+            min_captures_wb.insert(closure_def_id, root_var_map_wb);
+        }
+        self.typeck_results.closure_min_captures = min_captures_wb;
+    }
+
     fn visit_closures(&mut self) {
         let fcx_typeck_results = self.fcx.typeck_results.borrow();
         assert_eq!(fcx_typeck_results.hir_owner, self.typeck_results.hir_owner);
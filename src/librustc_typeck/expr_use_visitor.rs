@@ -35,6 +35,10 @@ pub trait Delegate<'tcx> {
 
     // The path at `place_with_id` is being assigned to.
     fn mutate(&mut self, assignee_place: &PlaceWithHirId<'tcx>);
+
+    // The value found at `place` is inspected without being copied, moved
+    // or borrowed, e.g. the initializer of `let _ = place;`.
+    fn fake_read(&mut self, _place_with_id: &PlaceWithHirId<'tcx>) {}
 }
 
 #[derive(Copy, Clone, PartialEq, Debug)]
@@ -258,7 +262,10 @@ impl<'a, 'tcx> ExprUseVisitor<'a, 'tcx> {
                 self.consume_exprs(&ia.inputs_exprs);
             }
 
-            hir::ExprKind::Continue(..) | hir::ExprKind::Lit(..) | hir::ExprKind::Err => {}
+            hir::ExprKind::Continue(..)
+            | hir::ExprKind::Lit(..)
+            | hir::ExprKind::ConstBlock(..)
+            | hir::ExprKind::Err => {}
 
             hir::ExprKind::Loop(ref blk, _, _) => {
                 self.walk_block(blk);
@@ -348,6 +355,7 @@ impl<'a, 'tcx> ExprUseVisitor<'a, 'tcx> {
             // `walk_pat`:
             self.walk_expr(&expr);
             let init_place = return_if_err!(self.mc.cat_expr(&expr));
+            self.delegate.fake_read(&init_place);
             self.walk_irrefutable_pat(&init_place, &local.pat);
         }
     }
@@ -487,8 +495,14 @@ impl<'a, 'tcx> ExprUseVisitor<'a, 'tcx> {
     fn walk_arm(&mut self, discr_place: &PlaceWithHirId<'tcx>, arm: &hir::Arm<'_>) {
         self.walk_pat(discr_place, &arm.pat);
 
-        if let Some(hir::Guard::If(ref e)) = arm.guard {
-            self.consume_expr(e)
+        match arm.guard {
+            Some(hir::Guard::If(ref e)) => self.consume_expr(e),
+            Some(hir::Guard::IfLet(ref pat, ref e)) => {
+                let place = return_if_err!(self.mc.cat_expr(e));
+                self.borrow_expr(e, ty::ImmBorrow);
+                self.walk_pat(&place, pat);
+            }
+            None => {}
         }
 
         self.consume_expr(&arm.body);
@@ -546,7 +560,29 @@ impl<'a, 'tcx> ExprUseVisitor<'a, 'tcx> {
         debug!("walk_captures({:?})", closure_expr);
 
         let closure_def_id = self.tcx().hir().local_def_id(closure_expr.hir_id);
-        if let Some(upvars) = self.tcx().upvars_mentioned(closure_def_id) {
+        let typeck_results = self.mc.typeck_results;
+        let closure_did = closure_def_id.to_def_id();
+        if typeck_results.closure_min_captures.contains_key(&closure_did) {
+            // With `capture_disjoint_fields`, the closure captures places rooted at the
+            // variables it mentions rather than the variables themselves.
+            for captured in typeck_results.closure_min_captures_flattened(closure_did) {
+                let mut captured_place = return_if_err!(self.cat_captured_var(
+                    closure_expr.hir_id,
+                    fn_decl_span,
+                    captured.get_root_variable(),
+                ));
+                captured_place.place.projections.extend(captured.place.projections.iter().cloned());
+                match captured.capture_kind {
+                    ty::UpvarCapture::ByValue => {
+                        let mode = copy_or_move(&self.mc, &captured_place);
+                        self.delegate.consume(&captured_place, mode);
+                    }
+                    ty::UpvarCapture::ByRef(upvar_borrow) => {
+                        self.delegate.borrow(&captured_place, upvar_borrow.kind);
+                    }
+                }
+            }
+        } else if let Some(upvars) = self.tcx().upvars_mentioned(closure_def_id) {
             for &var_id in upvars.keys() {
                 let upvar_id = ty::UpvarId {
                     var_path: ty::UpvarPath { hir_id: var_id },
@@ -1,47 +1,241 @@
+//! Rustdoc's JSON backend
+//!
+//! This module contains the logic for rendering a crate as JSON rather than the normal static HTML
+//! output. See [the RFC](https://github.com/rust-lang/rfcs/pull/2963) and the [`types`] module
+//! docs for usage and details.
+
+mod conversions;
+pub mod types;
+
+use std::cell::RefCell;
+use std::collections::hash_map::Entry;
+use std::fs::{self, File};
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_span::def_id::DefId;
+use rustc_span::edition::Edition;
+
 use crate::clean;
 use crate::config::{RenderInfo, RenderOptions};
+use crate::docfs::PathError;
 use crate::error::Error;
 use crate::formats::cache::Cache;
 use crate::formats::FormatRenderer;
+use crate::html::render::cache::ExternalLocation;
 
-use rustc_span::edition::Edition;
+/// The current version of the JSON format. This must be bumped whenever a change is made to the
+/// types in [`types`] that existing consumers couldn't handle.
+pub const FORMAT_VERSION: u32 = 1;
 
 #[derive(Clone)]
-pub struct JsonRenderer {}
+pub struct JsonRenderer {
+    /// A mapping of IDs that contains all local items for this crate which gets output as a top
+    /// level field of the JSON blob.
+    index: Rc<RefCell<FxHashMap<types::Id, types::Item>>>,
+    /// The directory where the blob will be written to.
+    out_path: PathBuf,
+}
+
+impl JsonRenderer {
+    fn get_trait_implementors(
+        &mut self,
+        id: DefId,
+        cache: &Cache,
+    ) -> Result<Vec<types::Id>, Error> {
+        let implementors = match cache.implementors.get(&id) {
+            Some(implementors) => implementors,
+            None => return Ok(Vec::new()),
+        };
+        implementors
+            .iter()
+            .map(|i| {
+                let item = &i.impl_item;
+                self.item(item.clone(), cache)?;
+                Ok(item.def_id.into())
+            })
+            .collect()
+    }
+
+    fn get_impls(&mut self, id: DefId, cache: &Cache) -> Result<Vec<types::Id>, Error> {
+        let impls = match cache.impls.get(&id) {
+            Some(impls) => impls,
+            None => return Ok(Vec::new()),
+        };
+        impls
+            .iter()
+            .map(|i| &i.impl_item)
+            .filter(|item| item.def_id.is_local())
+            .map(|item| {
+                self.item(item.clone(), cache)?;
+                Ok(item.def_id.into())
+            })
+            .collect()
+    }
+
+    fn get_trait_items(&mut self, cache: &Cache) -> Result<Vec<(types::Id, types::Item)>, Error> {
+        cache
+            .traits
+            .iter()
+            // only need to synthesize items for external traits
+            .filter(|(id, _)| !id.is_local())
+            .map(|(&id, trait_item)| {
+                for i in trait_item.items.clone() {
+                    self.item(i, cache)?;
+                }
+                Ok((
+                    id.into(),
+                    types::Item {
+                        id: id.into(),
+                        crate_id: id.krate.as_u32(),
+                        name: cache
+                            .paths
+                            .get(&id)
+                            .or_else(|| cache.external_paths.get(&id))
+                            .and_then(|(path, _)| path.last().cloned()),
+                        source: None,
+                        visibility: types::Visibility::Public,
+                        docs: Default::default(),
+                        links: Default::default(),
+                        attrs: Default::default(),
+                        deprecation: Default::default(),
+                        kind: types::ItemKind::Trait,
+                        inner: types::ItemEnum::TraitItem(trait_item.clone().into()),
+                    },
+                ))
+            })
+            .collect()
+    }
+}
 
 impl FormatRenderer for JsonRenderer {
     fn init(
-        _krate: clean::Crate,
-        _options: RenderOptions,
+        krate: clean::Crate,
+        options: RenderOptions,
         _render_info: RenderInfo,
         _edition: Edition,
         _cache: &mut Cache,
     ) -> Result<(Self, clean::Crate), Error> {
-        unimplemented!()
+        debug!("Initializing json renderer");
+        Ok((
+            JsonRenderer {
+                index: Rc::new(RefCell::new(FxHashMap::default())),
+                out_path: options.output,
+            },
+            krate,
+        ))
     }
 
-    fn item(&mut self, _item: clean::Item, _cache: &Cache) -> Result<(), Error> {
-        unimplemented!()
+    /// Inserts an item into the index. This should be used rather than directly calling insert on
+    /// the hashmap because certain items (traits and types) need to have their mappings for trait
+    /// implementations filled out before they're inserted.
+    fn item(&mut self, item: clean::Item, cache: &Cache) -> Result<(), Error> {
+        // Flatten items that recursively store other items
+        for i in item.inner.inner_items() {
+            self.item(i.clone(), cache)?;
+        }
+
+        let id = item.def_id;
+        let new_item: Option<types::Item> = item.into();
+        if let Some(mut new_item) = new_item {
+            match new_item.inner {
+                types::ItemEnum::TraitItem(ref mut t) => {
+                    t.implementors = self.get_trait_implementors(id, cache)?
+                }
+                types::ItemEnum::StructItem(ref mut s) => s.impls = self.get_impls(id, cache)?,
+                types::ItemEnum::EnumItem(ref mut e) => e.impls = self.get_impls(id, cache)?,
+                _ => {}
+            }
+            // Items can be reached more than once, e.g. an impl is found through both its type
+            // and its trait. Every path should produce the same output, so the first one is kept.
+            match self.index.borrow_mut().entry(id.into()) {
+                Entry::Occupied(old_item) => debug_assert_eq!(*old_item.get(), new_item),
+                Entry::Vacant(slot) => {
+                    slot.insert(new_item);
+                }
+            }
+        }
+
+        Ok(())
     }
 
     fn mod_item_in(
         &mut self,
-        _item: &clean::Item,
-        _item_name: &str,
-        _cache: &Cache,
+        item: &clean::Item,
+        _module_name: &str,
+        cache: &Cache,
     ) -> Result<(), Error> {
-        unimplemented!()
+        use clean::types::ItemEnum::*;
+        if let ModuleItem(m) = &item.inner {
+            for item in &m.items {
+                match &item.inner {
+                    // These don't have names so they don't get added to the output by default
+                    ImportItem(_) => self.item(item.clone(), cache)?,
+                    ExternCrateItem(_, _) => self.item(item.clone(), cache)?,
+                    _ => {}
+                }
+            }
+        }
+        self.item(item.clone(), cache)
     }
 
     fn mod_item_out(&mut self, _item_name: &str) -> Result<(), Error> {
-        unimplemented!()
+        Ok(())
     }
 
-    fn after_krate(&mut self, _krate: &clean::Crate, _cache: &Cache) -> Result<(), Error> {
-        unimplemented!()
+    fn after_krate(&mut self, krate: &clean::Crate, cache: &Cache) -> Result<(), Error> {
+        debug!("Done with crate");
+        // Synthesizing the external traits adds their items to the index, so this has to happen
+        // before the index is taken.
+        let trait_items = self.get_trait_items(cache)?;
+        let mut index = (*self.index).clone().into_inner();
+        index.extend(trait_items);
+        let output = types::Crate {
+            root: types::Id(String::from("0:0")),
+            crate_version: cache.crate_version.clone(),
+            includes_private: cache.document_private,
+            index,
+            paths: cache
+                .paths
+                .clone()
+                .into_iter()
+                .chain(cache.external_paths.clone().into_iter())
+                .map(|(k, (path, kind))| {
+                    (
+                        k.into(),
+                        types::ItemSummary { crate_id: k.krate.as_u32(), path, kind: kind.into() },
+                    )
+                })
+                .collect(),
+            external_crates: cache
+                .extern_locations
+                .iter()
+                .map(|(k, v)| {
+                    (
+                        k.as_u32(),
+                        types::ExternalCrate {
+                            name: v.0.clone(),
+                            html_root_url: match &v.2 {
+                                ExternalLocation::Remote(s) => Some(s.clone()),
+                                _ => None,
+                            },
+                        },
+                    )
+                })
+                .collect(),
+            format_version: FORMAT_VERSION,
+        };
+        try_err!(fs::create_dir_all(&self.out_path), &self.out_path);
+        let mut p = self.out_path.clone();
+        p.push(&krate.name);
+        p.set_extension("json");
+        let file = try_err!(File::create(&p), &p);
+        try_err!(serde_json::ser::to_writer(&file, &output), &p);
+        Ok(())
     }
 
     fn after_run(&mut self, _diag: &rustc_errors::Handler) -> Result<(), Error> {
-        unimplemented!()
+        Ok(())
     }
 }
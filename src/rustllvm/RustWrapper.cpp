@@ -207,6 +207,16 @@ static Attribute::AttrKind fromRust(LLVMRustAttribute Kind) {
     return Attribute::ReadNone;
   case InaccessibleMemOnly:
     return Attribute::InaccessibleMemOnly;
+  case SanitizeHWAddress:
+    return Attribute::SanitizeHWAddress;
+  case ShadowCallStack:
+    return Attribute::ShadowCallStack;
+  case StackProtect:
+    return Attribute::StackProtect;
+  case StackProtectStrong:
+    return Attribute::StackProtectStrong;
+  case StackProtectReq:
+    return Attribute::StackProtectReq;
   }
   report_fatal_error("bad AttributeKind");
 }
@@ -669,6 +679,8 @@ extern "C" uint32_t LLVMRustDebugMetadataVersion() {
   return DEBUG_METADATA_VERSION;
 }
 
+extern "C" uint32_t LLVMRustVersionPatch() { return LLVM_VERSION_PATCH; }
+
 extern "C" uint32_t LLVMRustVersionMinor() { return LLVM_VERSION_MINOR; }
 
 extern "C" uint32_t LLVMRustVersionMajor() { return LLVM_VERSION_MAJOR; }
@@ -682,6 +694,12 @@ extern "C" LLVMValueRef LLVMRustMetadataAsValue(LLVMContextRef C, LLVMMetadataRe
   return wrap(MetadataAsValue::get(*unwrap(C), unwrap(MD)));
 }
 
+extern "C" void LLVMRustGlobalAddMetadata(LLVMValueRef Global, unsigned Kind,
+                                          LLVMValueRef MD) {
+  unwrap<GlobalObject>(Global)->addMetadata(
+      Kind, *cast<MDNode>(unwrap<MetadataAsValue>(MD)->getMetadata()));
+}
+
 extern "C" LLVMRustDIBuilderRef LLVMRustDIBuilderCreate(LLVMModuleRef M) {
   return new DIBuilder(*unwrap(M));
 }
@@ -85,6 +85,11 @@ enum LLVMRustAttribute {
   ReturnsTwice = 25,
   ReadNone = 26,
   InaccessibleMemOnly = 27,
+  SanitizeHWAddress = 28,
+  ShadowCallStack = 29,
+  StackProtect = 30,
+  StackProtectStrong = 31,
+  StackProtectReq = 32,
 };
 
 typedef struct OpaqueRustString *RustStringRef;
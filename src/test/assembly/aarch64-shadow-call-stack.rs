@@ -0,0 +1,49 @@
+// Verifies that ShadowCallStack reserves x18, which holds the shadow stack pointer, on targets
+// that don't reserve it by default, so that the register allocator never hands it out.
+//
+// no-system-llvm
+// assembly-output: emit-asm
+// compile-flags: --target aarch64-unknown-linux-gnu -Zsanitizer=shadow-call-stack
+// compile-flags: -Copt-level=2
+// needs-llvm-components: aarch64
+
+#![feature(no_core, lang_items, rustc_attrs)]
+#![crate_type = "rlib"]
+#![no_core]
+
+#[rustc_builtin_macro]
+macro_rules! asm {
+    () => {};
+}
+
+#[lang = "sized"]
+trait Sized {}
+
+extern "C" {
+    fn opaque();
+}
+
+// CHECK-LABEL: calls_opaque:
+// CHECK:       str x30, [x18], #8
+// CHECK:       bl opaque
+// CHECK:       ldr x30, [x18, #-8]!
+#[no_mangle]
+pub unsafe fn calls_opaque() {
+    opaque();
+}
+
+// LLVM allocates x18 right after x8-x17 unless it is reserved, so the 16 registers needed here
+// would include it.
+// CHECK-LABEL: many_registers:
+// CHECK-NOT:   x18
+// CHECK:       ret
+#[no_mangle]
+pub unsafe fn many_registers() {
+    asm!(
+        "// {0} {1} {2} {3} {4} {5} {6} {7} {8} {9} {10} {11} {12} {13} {14} {15}",
+        out(reg) _, out(reg) _, out(reg) _, out(reg) _,
+        out(reg) _, out(reg) _, out(reg) _, out(reg) _,
+        out(reg) _, out(reg) _, out(reg) _, out(reg) _,
+        out(reg) _, out(reg) _, out(reg) _, out(reg) _,
+    );
+}
@@ -0,0 +1,39 @@
+// compile-flags: -C opt-level=0 -C no-prepopulate-passes
+// ignore-wasm32-bare compiled with panic=abort by default
+
+// Test that `nounwind` attributes are only applied to functions with a non-unwinding ABI.
+
+#![crate_type = "lib"]
+#![feature(c_unwind)]
+
+// CHECK: Function Attrs:{{.*}}nounwind
+// CHECK-NEXT: define void @rust_item_that_cannot_unwind
+#[no_mangle]
+pub extern "C" fn rust_item_that_cannot_unwind() {}
+
+// CHECK-NOT: Function Attrs:{{.*}}nounwind
+// CHECK: define void @rust_item_that_can_unwind
+#[no_mangle]
+pub extern "C-unwind" fn rust_item_that_can_unwind() {}
+
+// CHECK-NOT: Function Attrs:{{.*}}nounwind
+// CHECK: define void @rust_system_item_that_can_unwind
+#[no_mangle]
+pub extern "system-unwind" fn rust_system_item_that_can_unwind() {}
+
+extern "C" {
+// CHECK: Function Attrs:{{.*}}nounwind
+// CHECK-NEXT: declare void @extern_fn
+    fn extern_fn();
+}
+
+extern "C-unwind" {
+// CHECK-NOT: Function Attrs:{{.*}}nounwind
+// CHECK: declare void @unwinding_extern_fn
+    fn unwinding_extern_fn();
+}
+
+pub unsafe fn force_declare() {
+    extern_fn();
+    unwinding_extern_fn();
+}
@@ -0,0 +1,85 @@
+// Verifies that functions and vtables carry type metadata and that indirect and virtual calls are
+// checked against it when compiling with -Zsanitizer=cfi.
+//
+// needs-sanitizer-cfi
+// compile-flags: -Clinker-plugin-lto -Copt-level=0 -Zsanitizer=cfi
+
+#![crate_type="lib"]
+#![feature(no_sanitize)]
+
+pub trait Trait {
+    fn f(&self) -> i32;
+}
+
+impl Trait for i32 {
+    fn f(&self) -> i32 {
+        *self
+    }
+}
+
+// CHECK: @vtable.{{[0-9]+}} = {{.*}}, !type ![[TYPE_VTABLE:[0-9]+]]
+
+pub fn to_trait_object(x: &i32) -> &dyn Trait {
+    x
+}
+
+// CHECK-LABEL: define{{.*}}indirect_call{{.*}}!type
+// CHECK:       call i1 @llvm.type.test(i8* {{%.+}}, metadata !"_RFN{{[0-9a-f]+}}")
+// CHECK:       cfi_fail:
+// CHECK-NEXT:  call void @llvm.trap()
+pub fn indirect_call(f: fn(i32) -> i32, arg: i32) -> i32 {
+    f(arg)
+}
+
+// CHECK-LABEL: define{{.*}}virtual_call{{.*}}!type
+// CHECK:       call i1 @llvm.type.test(i8* {{%.+}}, metadata !"_RVT{{[0-9a-f]+}}")
+pub fn virtual_call(t: &dyn Trait) -> i32 {
+    t.f()
+}
+
+// CHECK-LABEL: define{{.*}}no_cfi_call
+// CHECK-NOT:   call i1 @llvm.type.test
+// CHECK:       }
+#[no_sanitize(cfi)]
+pub fn no_cfi_call(f: fn(i32) -> i32, arg: i32) -> i32 {
+    f(arg)
+}
+
+// Functions with a C calling convention carry the type identifier Clang gives the equivalent C
+// function type, so that they can be called from and can call C code built with
+// -fsanitize=cfi-icall.
+// CHECK-LABEL: define{{.*}}extern_c_call
+// CHECK:       call i1 @llvm.type.test(i8* {{%.+}}, metadata !"_ZTSFiiE")
+pub fn extern_c_call(f: extern "C" fn(i32) -> i32, arg: i32) -> i32 {
+    f(arg)
+}
+
+// CHECK: define{{.*}}extern_c_definition{{.*}}!type ![[TYPE_C_FN:[0-9]+]]
+#[no_mangle]
+pub extern "C" fn extern_c_definition(arg: i32) -> i32 {
+    arg
+}
+
+pub struct Foo;
+
+// Types are encoded once and referred to by substitutions afterwards, as in the mangling of the
+// C++ type `void (const Foo*, const Foo*, Foo*)`.
+// CHECK-LABEL: define{{.*}}extern_c_struct_call
+// CHECK:       call i1 @llvm.type.test(i8* {{%.+}}, metadata !"_ZTSFvPK3FooS1_PS_E")
+pub fn extern_c_struct_call(f: extern "C" fn(*const Foo, &Foo, *mut Foo), foo: *mut Foo) {
+    f(foo, unsafe { &*foo }, foo)
+}
+
+extern "C" {
+    fn foreign_function(arg: i32) -> i32;
+}
+
+// CHECK-LABEL: define{{.*}}foreign_function_pointer
+pub fn foreign_function_pointer() -> unsafe extern "C" fn(i32) -> i32 {
+    foreign_function
+}
+
+// CHECK: declare{{.*}}foreign_function{{.*}}!type ![[TYPE_C_FN]]
+
+// CHECK-DAG: ![[TYPE_VTABLE]] = !{i64 0, !"_RVT{{[0-9a-f]+}}"}
+// CHECK-DAG: ![[TYPE_C_FN]] = !{i64 0, !"_ZTSFiiE"}
@@ -0,0 +1,33 @@
+// Verifies that HWAddressSanitizer and ShadowCallStack add their function attributes and that
+// the no_sanitize attribute can be used to selectively disable them. ShadowCallStack also has to
+// reserve x18 for the shadow stack pointer.
+//
+// needs-llvm-components: aarch64
+// revisions: HWASAN SCS
+//[HWASAN] compile-flags: --target aarch64-unknown-linux-gnu -Zsanitizer=hwaddress
+//[SCS]    compile-flags: --target aarch64-unknown-linux-gnu -Zsanitizer=shadow-call-stack
+// compile-flags: -Copt-level=0
+
+#![crate_type = "lib"]
+#![feature(no_core, lang_items, no_sanitize)]
+#![no_core]
+
+#[lang = "sized"]
+trait Sized {}
+
+// CHECK-LABEL:  ; sanitizer_hwaddress_shadow_call_stack::unsanitized
+// CHECK-NEXT:   ; Function Attrs:
+// HWASAN-NOT:   sanitize_hwaddress
+// SCS-NOT:      shadowcallstack
+// CHECK:        start:
+#[no_sanitize(hwaddress, shadow_call_stack)]
+pub fn unsanitized() {}
+
+// CHECK-LABEL:  ; sanitizer_hwaddress_shadow_call_stack::sanitized
+// CHECK-NEXT:   ; Function Attrs:
+// HWASAN:       sanitize_hwaddress
+// SCS:          shadowcallstack
+// CHECK:        start:
+pub fn sanitized() {}
+
+// SCS: attributes #{{[0-9]+}} = {{.*}}"target-features"="{{[^"]*}}+reserve-x18
@@ -0,0 +1,26 @@
+// Checks that lints replayed from the incremental cache fulfill `#[expect]` attributes, including
+// the ones const propagation emits while codegen builds the MIR.
+
+// revisions: rpass1 rpass2
+// compile-flags: -Z query-dep-graph
+
+#![feature(lint_reasons, rustc_attrs)]
+#![deny(unfulfilled_lint_expectations)]
+#![rustc_partition_reused(module = "lint_expect", cfg = "rpass2")]
+
+#[rustc_clean(label = "typeck", cfg = "rpass2")]
+#[expect(unused_variables)]
+fn unused_variable() {
+    let x = 1;
+}
+
+#[rustc_clean(label = "optimized_mir", cfg = "rpass2")]
+#[expect(arithmetic_overflow)]
+fn overflow(overflow: bool) -> u8 {
+    if overflow { 255u8 + 1 } else { 0 }
+}
+
+fn main() {
+    unused_variable();
+    assert_eq!(overflow(false), 0);
+}
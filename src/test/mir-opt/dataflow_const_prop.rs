@@ -0,0 +1,11 @@
+// compile-flags: -Z mir-opt-level=2
+// EMIT_MIR dataflow_const_prop.join.DataflowConstProp.diff
+
+fn join(c: bool) -> u8 {
+    let x = if c { 4 } else { 4 };
+    x
+}
+
+fn main() {
+    join(true);
+}
@@ -0,0 +1,19 @@
+// compile-flags: -Zmir-opt-level=2
+// Locals that have their address taken must not be unified, since the borrows could then observe
+// a single local where the program expects two distinct ones.
+
+#[inline(never)]
+fn same(a: &u32, b: &u32) -> bool {
+    std::ptr::eq(a, b)
+}
+
+// EMIT_MIR borrowed.f.DestinationPropagation.diff
+fn f(x: u32) -> bool {
+    let a = x;
+    let b = a;
+    same(&a, &b)
+}
+
+fn main() {
+    assert!(!f(0));
+}
@@ -0,0 +1,20 @@
+// compile-flags: -Zmir-opt-level=2
+// Tests that a local that is written to while another one is live is not merged with it, even if
+// the written value is never read.
+
+#[inline(never)]
+fn val() -> u64 {
+    5
+}
+
+// EMIT_MIR dead_store.f.DestinationPropagation.diff
+fn f() -> u64 {
+    let y = val();
+    let mut z = val();
+    z = y;
+    z
+}
+
+fn main() {
+    f();
+}
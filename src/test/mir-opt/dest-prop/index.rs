@@ -0,0 +1,25 @@
+// compile-flags: -Zmir-opt-level=2
+// EMIT_MIR_FOR_EACH_BIT_WIDTH
+// Tests how `DestinationPropagation` treats locals used in `Index` projections, and destinations
+// that index into an array.
+
+#[inline(never)]
+fn val() -> u8 {
+    5
+}
+
+// EMIT_MIR index.read.DestinationPropagation.diff
+fn read(a: &[u8], i: usize) -> u8 {
+    let j = i;
+    a[j]
+}
+
+// EMIT_MIR index.write.DestinationPropagation.diff
+fn write(mut a: [u8; 4], i: usize) {
+    a[i] = val();
+}
+
+fn main() {
+    assert_eq!(read(&[1, 2, 3], 1), 2);
+    write([0; 4], 3);
+}
@@ -0,0 +1,23 @@
+// compile-flags: -Zmir-opt-level=2
+// only-x86_64
+// Tests that `DestinationPropagation` does not merge an input and an output of the same `asm!`.
+
+#![feature(asm)]
+
+#[inline(never)]
+fn val() -> u64 {
+    5
+}
+
+// EMIT_MIR inline_asm.f.DestinationPropagation.diff
+fn f() -> u64 {
+    let mut z = val();
+    unsafe {
+        asm!("mov {}, {}", out(reg) z, in(reg) z);
+    }
+    z
+}
+
+fn main() {
+    f();
+}
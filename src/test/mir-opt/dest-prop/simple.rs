@@ -0,0 +1,12 @@
+// compile-flags: -Zmir-opt-level=2
+// EMIT_MIR simple.test.DestinationPropagation.diff
+
+fn test(x: u32) -> u32 {
+    let y = x;
+    y
+}
+
+fn main() {
+    // Make sure the function actually gets instantiated.
+    test(0);
+}
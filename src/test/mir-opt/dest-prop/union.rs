@@ -0,0 +1,21 @@
+// compile-flags: -Zmir-opt-level=2
+// Tests that projections through unions cancel `DestinationPropagation`.
+
+union Un {
+    us: u32,
+}
+
+#[inline(never)]
+fn val() -> u32 {
+    1
+}
+
+#[inline(never)]
+fn consume(_: u32) {}
+
+// EMIT_MIR union.main.DestinationPropagation.diff
+fn main() {
+    let un = Un { us: val() };
+
+    consume(unsafe { un.us });
+}
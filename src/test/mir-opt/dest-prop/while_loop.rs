@@ -0,0 +1,23 @@
+// compile-flags: -Zmir-opt-level=2
+// Tests that `DestinationPropagation` can merge locals whose values are carried around a loop.
+
+#[inline(never)]
+fn cond(x: u32) -> bool {
+    x < 10
+}
+
+#[inline(never)]
+fn next(x: u32) -> u32 {
+    x + 1
+}
+
+// EMIT_MIR while_loop.f.DestinationPropagation.diff
+fn f(mut x: u32) {
+    while cond(x) {
+        x = next(x);
+    }
+}
+
+fn main() {
+    f(0);
+}
@@ -0,0 +1,16 @@
+// compile-flags: -Z mir-opt-level=2
+// EMIT_MIR jump_threading.nested.JumpThreading.diff
+
+fn nested(x: bool) -> u8 {
+    match x {
+        true => match x {
+            true => 1,
+            false => 2,
+        },
+        false => 3,
+    }
+}
+
+fn main() {
+    nested(true);
+}
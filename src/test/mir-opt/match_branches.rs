@@ -0,0 +1,13 @@
+// compile-flags: -Z mir-opt-level=2
+// EMIT_MIR match_branches.to_int.MatchBranchSimplification.diff
+
+fn to_int(o: Option<u8>) -> u8 {
+    match o {
+        Some(_) => 1,
+        None => 0,
+    }
+}
+
+fn main() {
+    to_int(None);
+}
@@ -0,0 +1,51 @@
+// compile-flags: -Zmir-opt-level=2
+// ignore-wasm32-bare compiled with panic=abort by default
+
+struct Foo {
+    a: u8,
+    b: u16,
+}
+
+struct Guard {
+    a: u8,
+    b: u16,
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {}
+}
+
+// EMIT_MIR sroa.structs.ScalarReplacementOfAggregates.diff
+fn structs(x: u8, y: u16) -> u16 {
+    let foo = Foo { a: x, b: y };
+    foo.b
+}
+
+// A borrowed aggregate must be kept whole, since the borrow may observe its layout.
+// EMIT_MIR sroa.borrowed.ScalarReplacementOfAggregates.diff
+fn borrowed(x: u8, y: u16) -> u16 {
+    let foo = Foo { a: x, b: y };
+    let r = &foo;
+    r.b
+}
+
+// Dropping takes the address of the aggregate as a whole.
+// EMIT_MIR sroa.dropped.ScalarReplacementOfAggregates.diff
+fn dropped(x: u8, y: u16) -> u16 {
+    let guard = Guard { a: x, b: y };
+    guard.b
+}
+
+// EMIT_MIR sroa.used_whole.ScalarReplacementOfAggregates.diff
+fn used_whole(x: u8, y: u16) -> Foo {
+    let foo = Foo { a: x, b: y };
+    foo
+}
+
+fn main() {
+    // Make sure the functions actually get instantiated.
+    structs(0, 0);
+    borrowed(0, 0);
+    dropped(0, 0);
+    used_whole(0, 0);
+}
@@ -0,0 +1,19 @@
+// compile-flags: -Zmir-opt-level=2 -g
+
+// With debuginfo enabled, `foo` can't be split since its debuginfo refers to it as a whole.
+
+struct Foo {
+    a: u8,
+    b: u16,
+}
+
+// EMIT_MIR sroa_debuginfo.structs.ScalarReplacementOfAggregates.diff
+fn structs(x: u8, y: u16) -> u16 {
+    let foo = Foo { a: x, b: y };
+    foo.b
+}
+
+fn main() {
+    // Make sure the function actually gets instantiated.
+    structs(0, 0);
+}
@@ -0,0 +1,17 @@
+pub mod changed {
+    #[cfg(not(changed))]
+    pub fn value() -> u32 {
+        1
+    }
+
+    #[cfg(changed)]
+    pub fn value() -> u32 {
+        2
+    }
+}
+
+pub mod unchanged {
+    pub fn value() -> u32 {
+        3
+    }
+}
@@ -0,0 +1,5 @@
+pub struct Foo(u32);
+
+pub fn foo(x: u32) -> Foo {
+    Foo(x + 1)
+}
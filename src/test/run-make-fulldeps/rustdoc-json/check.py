@@ -0,0 +1,75 @@
+#!/usr/bin/env python
+
+# Checks the structure of the JSON output produced by rustdoc for `foo.rs`.
+
+import sys
+import json
+
+
+def find(index, name, kind):
+    matches = [item for item in index.values() if item["name"] == name and item["kind"] == kind]
+    assert len(matches) == 1, "expected exactly one {} named {}, found {}".format(
+        kind, name, len(matches))
+    return matches[0]
+
+
+with open(sys.argv[1]) as f:
+    krate = json.load(f)
+
+assert krate["format_version"] == 1
+assert krate["includes_private"] is False
+index = krate["index"]
+
+root = index[krate["root"]]
+assert root["kind"] == "module"
+assert root["name"] == "foo"
+assert root["inner"]["is_crate"] is True
+assert root["docs"] == "The crate root."
+
+plain = find(index, "Plain", "struct")
+assert plain["docs"] == "A plain struct."
+assert plain["inner"]["struct_type"] == "plain"
+# The private field is stripped.
+assert plain["inner"]["fields_stripped"] is True
+fields = [index[id] for id in plain["inner"]["fields"]]
+assert [field["name"] for field in fields] == ["a"]
+assert fields[0]["docs"] == "The first field."
+assert fields[0]["inner"] == {"kind": "primitive", "inner": "u32"}
+
+impls = [index[id]["inner"] for id in plain["inner"]["impls"]]
+traits = sorted(impl["trait"]["inner"]["name"] for impl in impls if impl["trait"] is not None)
+assert traits == ["Clone", "Greet"], traits
+inherent = [impl for impl in impls if impl["trait"] is None]
+assert len(inherent) == 1
+new = index[inherent[0]["items"][0]]
+assert new["name"] == "new"
+assert new["inner"]["has_body"] is True
+assert new["inner"]["decl"]["inputs"] == [["a", {"kind": "primitive", "inner": "u32"}]]
+
+shape = find(index, "Shape", "enum")
+variants = {index[id]["name"]: index[id]["inner"] for id in shape["inner"]["variants"]}
+assert variants["Unit"]["variant_kind"] == "plain"
+assert variants["Tuple"]["variant_kind"] == "tuple"
+assert len(variants["Tuple"]["variant_inner"]) == 2
+assert variants["Struct"]["variant_kind"] == "struct"
+
+greet = find(index, "Greet", "trait")
+items = {index[id]["name"]: index[id] for id in greet["inner"]["items"]}
+assert items["NAME"]["kind"] == "assoc_const"
+assert items["greet"]["inner"]["has_body"] is False
+assert items["shout"]["inner"]["has_body"] is True
+assert len(greet["inner"]["implementors"]) == 1
+
+dangerous = find(index, "dangerous", "function")
+assert dangerous["inner"]["header"]["is_unsafe"] is True
+assert dangerous["inner"]["header"]["abi"] == "Rust"
+params = dangerous["inner"]["generics"]["params"]
+assert [param["name"] for param in params] == ["'a", "T"]
+
+import_ = find(index, None, "import")
+assert import_["inner"]["name"] == "Reexported"
+assert import_["inner"]["glob"] is False
+assert import_["inner"]["id"] == plain["id"]
+
+assert krate["paths"][plain["id"]]["path"] == ["foo", "Plain"]
+assert any(c["name"] == "std" for c in krate["external_crates"].values())
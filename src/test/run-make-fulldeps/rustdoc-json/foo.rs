@@ -0,0 +1,58 @@
+#![crate_name = "foo"]
+
+//! The crate root.
+
+/// A plain struct.
+pub struct Plain {
+    /// The first field.
+    pub a: u32,
+    b: Vec<String>,
+}
+
+impl Plain {
+    /// Creates a `Plain`.
+    pub fn new(a: u32) -> Self {
+        Plain { a, b: Vec::new() }
+    }
+}
+
+impl Clone for Plain {
+    fn clone(&self) -> Self {
+        Plain { a: self.a, b: self.b.clone() }
+    }
+}
+
+pub enum Shape {
+    Unit,
+    Tuple(u8, i8),
+    Struct { x: f64 },
+}
+
+pub trait Greet {
+    const NAME: &'static str;
+
+    fn greet(&self) -> String;
+
+    fn shout(&self) -> String {
+        self.greet().to_uppercase()
+    }
+}
+
+impl Greet for Plain {
+    const NAME: &'static str = "plain";
+
+    fn greet(&self) -> String {
+        String::from("hi")
+    }
+}
+
+pub mod inner {
+    pub use super::Plain as Reexported;
+
+    pub unsafe fn dangerous<'a, T: Copy + 'a>(x: &'a mut T) -> T
+    where
+        T: Default,
+    {
+        *x
+    }
+}
@@ -0,0 +1,36 @@
+typedef int (*callback_t)(int);
+
+struct Point {
+    int x;
+    int y;
+};
+
+typedef int (*point_callback_t)(const struct Point *);
+
+int c_add_one(int x) {
+    return x + 1;
+}
+
+int c_sum(const struct Point *p) {
+    return p->x + p->y;
+}
+
+int c_call(callback_t f, int x) {
+    return f(x);
+}
+
+int c_call_point(point_callback_t f, const struct Point *p) {
+    return f(p);
+}
+
+int c_call_mismatched(void *f, int x) {
+    return ((callback_t)f)(x);
+}
+
+callback_t c_get_add_one(void) {
+    return c_add_one;
+}
+
+point_callback_t c_get_sum(void) {
+    return c_sum;
+}
@@ -0,0 +1,48 @@
+#[repr(C)]
+pub struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[link(name = "clib")]
+extern "C" {
+    fn c_call(f: extern "C" fn(i32) -> i32, x: i32) -> i32;
+    fn c_call_point(f: extern "C" fn(&Point) -> i32, p: &Point) -> i32;
+    fn c_call_mismatched(f: *const u8, x: i32) -> i32;
+    fn c_get_add_one() -> extern "C" fn(i32) -> i32;
+    fn c_get_sum() -> extern "C" fn(&Point) -> i32;
+}
+
+extern "C" fn rust_double(x: i32) -> i32 {
+    x * 2
+}
+
+extern "C" fn rust_product(p: &Point) -> i32 {
+    p.x * p.y
+}
+
+extern "C" fn rust_negate(x: i64) -> i64 {
+    -x
+}
+
+fn main() {
+    let point = Point { x: 6, y: 7 };
+
+    // C calls Rust functions through function pointers.
+    assert_eq!(unsafe { c_call(rust_double, 21) }, 42);
+    assert_eq!(unsafe { c_call_point(rust_product, &point) }, 42);
+
+    // Rust calls C functions through function pointers.
+    let add_one = unsafe { c_get_add_one() };
+    assert_eq!(add_one(41), 42);
+    let sum = unsafe { c_get_sum() };
+    assert_eq!(sum(&point), 13);
+
+    if std::env::args().any(|arg| arg == "mismatched") {
+        unsafe { c_call_mismatched(rust_negate as *const u8, 1) };
+        println!("not reached");
+        return;
+    }
+
+    println!("ok");
+}
@@ -0,0 +1,40 @@
+// check-pass
+// edition:2018
+
+#![feature(async_fn_in_trait)]
+#![allow(incomplete_features)]
+
+trait Counter {
+    async fn count(&self) -> u32;
+    async fn add<'a>(&mut self, by: &'a u32);
+    async fn name<'a>(&'a self) -> &'a str;
+}
+
+struct Simple {
+    value: u32,
+}
+
+impl Counter for Simple {
+    async fn count(&self) -> u32 {
+        self.value
+    }
+
+    async fn add<'a>(&mut self, by: &'a u32) {
+        self.value += *by;
+    }
+
+    async fn name<'a>(&'a self) -> &'a str {
+        "simple"
+    }
+}
+
+async fn count_twice<C: Counter>(counter: &mut C) -> u32 {
+    counter.add(&1).await;
+    counter.add(&1).await;
+    let _ = counter.name().await;
+    counter.count().await
+}
+
+fn main() {
+    let _ = count_twice(&mut Simple { value: 0 });
+}
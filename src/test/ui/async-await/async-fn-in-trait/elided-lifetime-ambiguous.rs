@@ -0,0 +1,11 @@
+// edition:2018
+
+#![feature(async_fn_in_trait)]
+#![allow(incomplete_features)]
+
+trait Picker {
+    async fn pick(a: &u32, b: &u32) -> &u32;
+    //~^ ERROR `&` without an explicit lifetime name cannot be used here
+}
+
+fn main() {}
@@ -0,0 +1,71 @@
+// run-pass
+// edition:2018
+
+#![feature(async_fn_in_trait)]
+#![allow(incomplete_features)]
+
+use std::future::Future;
+use std::pin::Pin;
+use std::ptr;
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+trait Store {
+    async fn get(&self) -> &String;
+    async fn get_mut(&mut self) -> &mut String;
+    async fn first<'a>(&self, items: &'a [u32]) -> &'a u32;
+    async fn len(&self) -> usize;
+}
+
+struct Names {
+    name: String,
+}
+
+impl Store for Names {
+    async fn get(&self) -> &String {
+        &self.name
+    }
+
+    async fn get_mut(&mut self) -> &mut String {
+        &mut self.name
+    }
+
+    async fn first<'a>(&self, items: &'a [u32]) -> &'a u32 {
+        &items[0]
+    }
+
+    async fn len(&self) -> usize {
+        self.get().await.len()
+    }
+}
+
+async fn rename<S: Store>(store: &mut S, suffix: &str) -> usize {
+    store.get_mut().await.push_str(suffix);
+    let first = *store.first(&[7, 8]).await;
+    store.len().await + first as usize
+}
+
+fn block_on<F: Future>(mut future: F) -> F::Output {
+    fn noop_raw_waker() -> RawWaker {
+        fn clone(_: *const ()) -> RawWaker {
+            noop_raw_waker()
+        }
+        fn noop(_: *const ()) {}
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+        RawWaker::new(ptr::null(), &VTABLE)
+    }
+
+    let waker = unsafe { Waker::from_raw(noop_raw_waker()) };
+    let mut cx = Context::from_waker(&waker);
+    let mut future = unsafe { Pin::new_unchecked(&mut future) };
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+fn main() {
+    let mut names = Names { name: String::from("foo") };
+    assert_eq!(block_on(rename(&mut names, "bar")), 13);
+    assert_eq!(block_on(names.get()), "foobar");
+}
@@ -0,0 +1,15 @@
+// edition:2018
+
+#![feature(async_fn_in_trait)]
+#![allow(incomplete_features)]
+
+trait Foo {
+    async fn default_body(&self) {}
+    //~^ ERROR `async fn` in traits cannot have a default body yet
+    async fn type_param<T>(&self, t: T);
+    //~^ ERROR `async fn` in traits cannot have type or const parameters yet
+    async fn impl_trait_arg(&self, t: impl Sized);
+    //~^ ERROR `async fn` in traits cannot have type or const parameters yet
+}
+
+fn main() {}
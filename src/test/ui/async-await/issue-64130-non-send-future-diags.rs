@@ -11,7 +11,7 @@ async fn foo() {
 }
 
 async fn bar(x: &Mutex<u32>) {
-    let g = x.lock().unwrap();
+    let g = x.lock().unwrap(); //~ WARN held across a suspend point
     baz().await;
 }
 
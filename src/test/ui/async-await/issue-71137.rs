@@ -8,7 +8,7 @@ fn fake_spawn<F: Future + Send + 'static>(f: F) { }
 async fn wrong_mutex() {
   let m = Mutex::new(1);
   {
-    let mut guard = m.lock().unwrap();
+    let mut guard = m.lock().unwrap(); //~ WARN held across a suspend point
     (async { "right"; }).await;
     *guard += 1;
   }
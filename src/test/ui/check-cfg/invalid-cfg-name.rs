@@ -0,0 +1,19 @@
+// Check that we detect unexpected names in `cfg` conditions
+//
+// check-pass
+// compile-flags: --check-cfg=names(serde) --cfg=rand -Z unstable-options
+
+#[cfg(widnows)]
+//~^ WARNING unexpected `cfg` condition name
+pub fn f() {}
+
+#[cfg(windows)]
+pub fn g() {}
+
+#[cfg(any(unix, target_os = "wasi", test, serde, rand))]
+pub fn h() {}
+
+fn main() {
+    if cfg!(debug_assertoins) {}
+    //~^ WARNING unexpected `cfg` condition name
+}
@@ -0,0 +1,20 @@
+// Check that we detect unexpected values in `cfg` conditions
+//
+// check-pass
+// compile-flags: --check-cfg=values(feature,"serde") --cfg=feature="rand" -Z unstable-options
+
+#[cfg(feature = "sedre")]
+//~^ WARNING unexpected `cfg` condition value
+pub fn f() {}
+
+#[cfg(feature = "serde")]
+pub fn g() {}
+
+#[cfg(feature = "rand")]
+pub fn h() {}
+
+// Values of names without `values()` are not checked.
+#[cfg(target_os = "anything")]
+pub fn i() {}
+
+fn main() {}
@@ -0,0 +1,20 @@
+// Tests that a closure using a field of a variable conflicts with a borrow of
+// another field of that variable when `capture_disjoint_fields` is not enabled,
+// because the closure captures the whole variable.
+
+// gate-test-capture_disjoint_fields
+
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+fn main() {
+    let mut p = Point { x: 10, y: 10 };
+
+    let y = &p.y;
+    let mut c = || p.x += 1;
+    //~^ ERROR cannot borrow `p` as mutable because it is also borrowed as immutable
+    c();
+    println!("{} {}", p.x, y);
+}
@@ -0,0 +1,51 @@
+// run-rustfix
+
+// Tests the migration lint for closures whose captured variables would be
+// dropped in a different order with `capture_disjoint_fields`.
+
+#![deny(disjoint_capture_drop_reorder)]
+
+fn single_var() {
+    let t = (String::new(), String::new());
+    let c = move || t.0.len();
+    //~^ ERROR drop order affected for closure because of `capture_disjoint_fields`
+    c();
+}
+
+fn multiple_vars() {
+    let t = (String::new(), String::new());
+    let u = (String::new(), String::new());
+    let c = move || t.0.len() + u.1.len();
+    //~^ ERROR drop order affected for closure because of `capture_disjoint_fields`
+    c();
+}
+
+fn block_body() {
+    let t = (String::new(), String::new());
+    let c = move || { t.0.len() };
+    //~^ ERROR drop order affected for closure because of `capture_disjoint_fields`
+    c();
+}
+
+fn fully_captured() {
+    let t = (String::new(), String::new());
+    let c = move || {
+        let _ = &t;
+        t.0.len()
+    };
+    c();
+}
+
+fn no_drop() {
+    let t = (0, 0);
+    let c = move || t.0;
+    c();
+}
+
+fn main() {
+    single_var();
+    multiple_vars();
+    block_body();
+    fully_captured();
+    no_drop();
+}
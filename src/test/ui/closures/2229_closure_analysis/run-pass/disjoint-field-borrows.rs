@@ -0,0 +1,51 @@
+// run-pass
+
+// Tests that closures only capture the fields they use, so they can be used
+// while other fields of the same variable are borrowed.
+
+#![feature(capture_disjoint_fields)]
+#![allow(incomplete_features)]
+
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+struct Wrapper {
+    a: Vec<i32>,
+    b: String,
+}
+
+impl Wrapper {
+    fn push_while_borrowed(&mut self) -> usize {
+        let b = &self.b;
+        let mut c = || self.a.push(1);
+        c();
+        b.len() + self.a.len()
+    }
+}
+
+fn main() {
+    let mut p = Point { x: 10, y: 10 };
+    let y = &p.y;
+    let mut c = || p.x += 1;
+    c();
+    assert_eq!(p.x + *y, 21);
+
+    let mut w = Wrapper { a: vec![], b: String::from("hello") };
+    assert_eq!(w.push_while_borrowed(), 6);
+
+    // Moving a field only moves that field.
+    let t = (String::from("moved"), String::from("kept"));
+    let c = move || t.0.len();
+    assert_eq!(c(), 5);
+    assert_eq!(t.1, "kept");
+
+    // Captures through references.
+    let mut pair = (0, 0);
+    let r = &mut pair;
+    let mut c = || r.0 += 1;
+    c();
+    c();
+    assert_eq!(pair, (2, 0));
+}
@@ -0,0 +1,41 @@
+// Checks that overflows and panics are reported even when the values involved are only known
+// after joining several paths through the function.
+
+// compile-flags: -C overflow-checks=on
+// build-fail
+
+fn black_box<T>(_: T) {
+    unimplemented!()
+}
+
+fn after_branch(c: bool) {
+    let x: u8 = if c { 255 } else { 255 };
+    black_box(x + 1);
+    //~^ ERROR this arithmetic operation will overflow
+}
+
+fn after_loop(c: bool) {
+    let mut pair = (3usize, 0u8);
+    while c {
+        pair.1 = 1;
+    }
+    let array = [1, 2, 3];
+    black_box(array[pair.0]);
+    //~^ ERROR this operation will panic at runtime
+}
+
+fn after_match(c: bool) {
+    let option = if c { Some(1u8) } else { Some(2u8) };
+    let divisor = match option {
+        Some(_) => 0,
+        None => 1,
+    };
+    black_box(10 / divisor);
+    //~^ ERROR this operation will panic at runtime
+}
+
+fn main() {
+    after_branch(true);
+    after_loop(true);
+    after_match(true);
+}
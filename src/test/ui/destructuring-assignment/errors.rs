@@ -0,0 +1,10 @@
+#![feature(destructuring_assignment)]
+
+struct S { x: u8, y: u8 }
+
+fn main() {
+    let (mut a, mut b) = (0u8, 0u8);
+    (1, a) = (2, 3); //~ ERROR invalid left-hand side of assignment
+    (a, .., b, ..) = (4, 5, 6); //~ ERROR `..` can only be used once per tuple pattern
+    let _ = S { x: a, .. }; //~ ERROR base expression required after `..`
+}
@@ -0,0 +1,51 @@
+// run-pass
+
+#![feature(destructuring_assignment)]
+
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+struct Pair(i32, i32);
+
+fn pair() -> (i32, i32) {
+    (1, 2)
+}
+
+fn main() {
+    let (mut a, mut b);
+    (a, b) = pair();
+    assert_eq!((a, b), (1, 2));
+
+    // Swapping without a temporary.
+    (a, b) = (b, a);
+    assert_eq!((a, b), (2, 1));
+
+    // Nested tuples and rest patterns.
+    let c;
+    ((a, b), .., c) = ((3, 4), 5, 6, 7);
+    assert_eq!((a, b, c), (3, 4, 7));
+    (..) = (8, 9);
+
+    // Slices.
+    [a, .., b] = [10, 11, 12];
+    assert_eq!((a, b), (10, 12));
+
+    // Structs.
+    Point { x: a, y: b } = Point { x: 13, y: 14 };
+    assert_eq!((a, b), (13, 14));
+    let x;
+    Point { x, .. } = Point { x: 15, y: 16 };
+    assert_eq!(x, 15);
+
+    // Tuple structs.
+    Pair(a, b) = Pair(17, 18);
+    assert_eq!((a, b), (17, 18));
+
+    // Arbitrary places.
+    let mut array = [0; 2];
+    let mut point = Point { x: 0, y: 0 };
+    (array[1], point.y) = (19, 20);
+    assert_eq!((array, point.x, point.y), ([0, 19], 0, 20));
+}
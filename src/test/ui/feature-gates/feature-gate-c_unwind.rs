@@ -0,0 +1,18 @@
+// Test that the "C-unwind" ABI and its siblings are feature-gated.
+
+extern "C-unwind" fn f1() {}
+//~^ ERROR unwinding ABIs are experimental and subject to change
+
+extern "system-unwind" fn f2() {}
+//~^ ERROR unwinding ABIs are experimental and subject to change
+
+type F = extern "C-unwind" fn();
+//~^ ERROR unwinding ABIs are experimental and subject to change
+
+extern "C-unwind" {}
+//~^ ERROR unwinding ABIs are experimental and subject to change
+
+fn main() {
+    f1();
+    f2();
+}
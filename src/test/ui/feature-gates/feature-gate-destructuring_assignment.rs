@@ -0,0 +1,16 @@
+struct S { x: u8, y: u8 }
+
+fn main() {
+    let (mut a, mut b) = (0, 1);
+    assert_eq!((a, b), (0, 1));
+    (a, b) = (b, a); //~ ERROR destructuring assignments are unstable
+    assert_eq!((a, b), (1, 0));
+
+    let s = S { x: 1, y: 2 };
+    let mut x = s.y;
+    assert_eq!(x, 2);
+    S { x, .. } = s;
+    //~^ ERROR destructuring assignments are unstable
+    //~| ERROR destructuring assignments are unstable
+    assert_eq!(x, 1);
+}
@@ -0,0 +1,12 @@
+fn main() {
+    let _ = const {
+        //~^ ERROR inline-const is experimental [E0658]
+        true
+    };
+
+    match 5 {
+        const { 5 } => {}
+        //~^ ERROR inline-const is experimental [E0658]
+        _ => {}
+    }
+}
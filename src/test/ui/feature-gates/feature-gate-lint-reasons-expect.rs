@@ -0,0 +1,4 @@
+fn main() {
+    #[expect(unused_variables)] //~ ERROR the `#[expect]` attribute is an experimental feature
+    let x = 1;
+}
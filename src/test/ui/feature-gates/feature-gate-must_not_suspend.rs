@@ -0,0 +1,7 @@
+#![crate_type = "lib"]
+
+#[must_not_suspend = "You gotta use Umm's, ya know?"]
+//~^ ERROR the `#[must_not_suspend]` attribute is an experimental feature
+struct Umm {
+    _i: i64
+}
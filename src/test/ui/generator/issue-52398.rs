@@ -22,7 +22,7 @@ fn main() {
     // is caught by typeck
     let y = RefCell::new(true);
     static move || { //~ WARN unused generator that must be used
-        yield *y.borrow();
+        yield *y.borrow(); //~ WARN held across a suspend point
         return "Done";
     };
 }
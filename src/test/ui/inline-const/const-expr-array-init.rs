@@ -0,0 +1,22 @@
+// run-pass
+
+#![allow(incomplete_features)]
+#![feature(inline_const, min_const_generics)]
+
+use std::cell::Cell;
+
+fn empty_vecs<T, const N: usize>() -> [Vec<T>; N] {
+    [const { Vec::<T>::new() }; N]
+}
+
+fn main() {
+    let cells = [const { Cell::new(0) }; 20];
+    cells[0].set(1);
+    assert_eq!(cells.iter().map(Cell::get).sum::<i32>(), 1);
+
+    let vecs = [const { Vec::<String>::new() }; 4];
+    assert!(vecs.iter().all(Vec::is_empty));
+
+    let vecs: [Vec<u8>; 3] = empty_vecs();
+    assert!(vecs.iter().all(Vec::is_empty));
+}
@@ -0,0 +1,19 @@
+// run-pass
+
+#![allow(incomplete_features)]
+#![feature(inline_const, min_const_generics)]
+
+fn foo<T>() -> usize {
+    const { std::mem::size_of::<T>() }
+}
+
+fn bar<const N: usize>() -> usize {
+    const { N + 1 }
+}
+
+fn main() {
+    foo::<i32>();
+    foo::<String>();
+    assert_eq!(foo::<u64>(), 8);
+    assert_eq!(bar::<1>(), 2);
+}
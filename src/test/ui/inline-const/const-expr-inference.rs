@@ -0,0 +1,20 @@
+// run-pass
+
+#![allow(incomplete_features)]
+#![feature(inline_const, min_const_generics)]
+
+fn empty_vecs<T, const N: usize>() -> [Vec<T>; N] {
+    [const { Vec::new() }; N]
+}
+
+fn main() {
+    let vecs: [Vec<String>; 4] = [const { Vec::new() }; 4];
+    assert!(vecs.iter().all(Vec::is_empty));
+
+    let vecs: [Vec<u8>; 3] = empty_vecs();
+    assert!(vecs.iter().all(Vec::is_empty));
+
+    let x: u8 = const { 1 };
+    let y = const { 2 } + x;
+    assert_eq!(y, 3u8);
+}
@@ -0,0 +1,20 @@
+// run-pass
+
+#![allow(incomplete_features)]
+#![feature(inline_const)]
+
+fn main() {
+    let s = match read_mmio() {
+        0 => "FOO",
+        const { 1 << 4 } => "BAR",
+        const { 1 << 5 } => "BAZ",
+        const { u8::MAX } => "QUX",
+        _ => unreachable!(),
+    };
+
+    assert_eq!("BAZ", s);
+}
+
+fn read_mmio() -> u8 {
+    1 << 5
+}
@@ -0,0 +1,8 @@
+#![allow(incomplete_features)]
+#![feature(inline_const)]
+
+fn main() {
+    let _: [Option<String>; 2] = [None::<String>; 2];
+    //~^ ERROR the trait bound
+    let _: [Option<String>; 2] = [const { None::<String> }; 2];
+}
@@ -0,0 +1,6 @@
+#![feature(let_else)]
+
+fn main() {
+    let true = true && false else { return }; //~ ERROR a `&&` expression cannot be
+    let true = (true || false) else { return };
+}
@@ -0,0 +1,5 @@
+#![feature(let_else)]
+
+fn main() {
+    let Some(x) = Some(1) else {}; //~ ERROR mismatched types
+}
@@ -0,0 +1,6 @@
+#![feature(let_else)]
+
+fn main() {
+    let x = 1;
+    let 0 = x; //~ ERROR refutable pattern in local binding
+}
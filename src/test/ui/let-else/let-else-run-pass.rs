@@ -0,0 +1,32 @@
+// run-pass
+
+#![feature(let_else)]
+
+fn double(s: &str) -> Result<u32, String> {
+    let Ok(n) = s.parse::<u32>() else {
+        return Err(format!("not a number: {}", s));
+    };
+    Ok(n * 2)
+}
+
+fn main() {
+    assert_eq!(double("21"), Ok(42));
+    assert!(double("x").is_err());
+
+    let mut sum = 0;
+    for s in &["1", "x", "2"] {
+        let Ok(n) = s.parse::<i32>() else { continue };
+        sum += n;
+    }
+    assert_eq!(sum, 3);
+
+    // The type annotation is a coercion site.
+    let [a, b]: &[u8] = &[1, 2] else { panic!() };
+    assert_eq!((*a, *b), (1, 2));
+
+    // Bindings can borrow from the matched place.
+    let mut opt = Some(String::from("hello"));
+    let Some(ref mut s) = opt else { unreachable!() };
+    s.push_str(", world");
+    assert_eq!(opt.as_deref(), Some("hello, world"));
+}
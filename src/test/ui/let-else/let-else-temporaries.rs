@@ -0,0 +1,47 @@
+// run-pass
+// Temporaries created by the initializer of a `let...else` are dropped at the end of the
+// statement, before the rest of the block runs.
+
+#![feature(let_else)]
+
+use std::cell::RefCell;
+
+struct Noisy<'a>(&'static str, &'a RefCell<Vec<&'static str>>);
+
+impl Drop for Noisy<'_> {
+    fn drop(&mut self) {
+        self.1.borrow_mut().push(self.0);
+    }
+}
+
+impl<'a> Noisy<'a> {
+    fn make(&self, name: &'static str) -> Option<Noisy<'a>> {
+        Some(Noisy(name, self.1))
+    }
+}
+
+fn borrow_then_mutate(cell: &RefCell<Vec<i32>>) -> Option<i32> {
+    let Some(v) = cell.borrow().first().copied() else { return None };
+    cell.borrow_mut().push(1);
+    Some(v)
+}
+
+fn drop_order(log: &RefCell<Vec<&'static str>>) {
+    let Some(_binding) = Noisy("temporary", log).make("binding") else { unreachable!() };
+    log.borrow_mut().push("tail");
+    let _later = Noisy("later", log);
+}
+
+fn main() {
+    let cell = RefCell::new(vec![7]);
+    assert_eq!(borrow_then_mutate(&cell), Some(7));
+    assert_eq!(*cell.borrow(), [7, 1]);
+
+    let log = RefCell::new(Vec::new());
+    drop_order(&log);
+    assert_eq!(*log.borrow(), ["temporary", "tail", "later", "binding"]);
+
+    // Temporaries whose lifetime a `let` would extend are still alive for the rest of the block.
+    let Some(n) = &Some(String::from("extended")) else { unreachable!() };
+    assert_eq!(n, "extended");
+}
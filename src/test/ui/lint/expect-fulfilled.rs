@@ -0,0 +1,30 @@
+// check-pass
+
+#![feature(lint_reasons)]
+
+#[expect(dead_code, reason = "only called from the tests")]
+fn helper() {}
+
+#[expect(unused)]
+fn group() {
+    let x = 1;
+}
+
+#[expect(non_snake_case)]
+fn camelCase() {}
+
+#[expect(unfulfilled_lint_expectations)]
+mod nested {
+    // This expectation is unfulfilled, which fulfills the outer one.
+    #[expect(dead_code)]
+    pub fn used() {}
+}
+
+fn main() {
+    group();
+    camelCase();
+    nested::used();
+
+    #[expect(unused_parens)]
+    let _ = (1);
+}
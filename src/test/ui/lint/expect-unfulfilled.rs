@@ -0,0 +1,32 @@
+// check-pass
+
+#![feature(lint_reasons)]
+
+#[expect(dead_code)]
+//~^ WARN this lint expectation is unfulfilled: `dead_code` was never emitted
+pub fn used() {}
+
+#[expect(unused_variables, reason = "`x` will be used once the parser lands")]
+//~^ WARN this lint expectation is unfulfilled: `unused_variables` was never emitted
+//~| NOTE `x` will be used once the parser lands
+fn consume(x: u32) -> u32 {
+    x
+}
+
+#[expect(unused)]
+//~^ WARN this lint expectation is unfulfilled: `unused` was never emitted
+fn group() {}
+
+#[deny(unfulfilled_lint_expectations)]
+mod denied {
+    #[allow(unfulfilled_lint_expectations)]
+    #[expect(non_snake_case)]
+    pub fn snake_case() {}
+}
+
+fn main() {
+    used();
+    consume(1);
+    group();
+    denied::snake_case();
+}
@@ -0,0 +1,39 @@
+// edition:2018
+#![feature(must_not_suspend)]
+#![deny(must_not_suspend)]
+
+#[must_not_suspend = "You gotta use Umm's, ya know?"]
+struct Umm {
+    _i: i64,
+}
+
+fn umm() -> Umm {
+    Umm { _i: 1 }
+}
+
+async fn other() {}
+
+pub async fn held() {
+    let _guard = umm(); //~ ERROR `Umm` held across a suspend point, but should not be
+    other().await;
+}
+
+pub async fn held_in_tuple() {
+    let _pair = (umm(), 1); //~ ERROR `Umm` held across a suspend point, but should not be
+    other().await;
+}
+
+pub async fn dropped_before_suspend() {
+    {
+        let _guard = umm();
+    }
+    other().await;
+}
+
+pub async fn allowed() {
+    #[allow(must_not_suspend)]
+    let _guard = umm();
+    other().await;
+}
+
+fn main() {}
@@ -0,0 +1,23 @@
+// edition:2018
+// check-pass
+
+// `must_not_suspend` is allow-by-default: the generator interior is computed from scopes,
+// so a guard that is explicitly dropped before the suspend point still counts as held
+// across it. Code like this must keep compiling without warnings.
+
+use std::sync::Mutex;
+
+async fn other() {}
+
+pub async fn dropped_guard(m: &Mutex<i32>) {
+    let guard = m.lock().unwrap();
+    drop(guard);
+    other().await;
+}
+
+pub async fn held_guard(m: &Mutex<i32>) {
+    let _guard = m.lock().unwrap();
+    other().await;
+}
+
+fn main() {}
@@ -0,0 +1,41 @@
+// edition:2018
+#![deny(must_not_suspend)]
+
+use std::cell::RefCell;
+use std::sync::{Mutex, RwLock};
+
+async fn other() {}
+
+pub async fn mutex(m: &Mutex<i32>) {
+    let _guard = m.lock().unwrap(); //~ ERROR `std::sync::MutexGuard` held across
+    other().await;
+}
+
+pub async fn rwlock_read(l: &RwLock<i32>) {
+    let _guard = l.read().unwrap(); //~ ERROR `std::sync::RwLockReadGuard` held across
+    other().await;
+}
+
+pub async fn rwlock_write(l: &RwLock<i32>) {
+    let _guard = l.write().unwrap(); //~ ERROR `std::sync::RwLockWriteGuard` held across
+    other().await;
+}
+
+pub async fn ref_cell_borrow(c: &RefCell<i32>) {
+    let _r = c.borrow(); //~ ERROR `std::cell::Ref` held across
+    other().await;
+}
+
+pub async fn ref_cell_borrow_mut(c: &RefCell<i32>) {
+    let _r = c.borrow_mut(); //~ ERROR `std::cell::RefMut` held across
+    other().await;
+}
+
+pub async fn mutex_dropped_before_await(m: &Mutex<i32>) {
+    {
+        let _guard = m.lock().unwrap();
+    }
+    other().await;
+}
+
+fn main() {}
@@ -0,0 +1,49 @@
+// run-pass
+// compile-flags: -Zmir-opt-level=2
+// Moves a large buffer through a chain of by-value builder methods, which is the pattern
+// destination propagation removes copies from. Checks that no write gets lost on the way.
+
+const LEN: usize = 8 * 1024;
+
+struct Builder {
+    buf: [u8; LEN],
+    pos: usize,
+}
+
+impl Builder {
+    #[inline(never)]
+    fn new() -> Builder {
+        Builder { buf: [0; LEN], pos: 0 }
+    }
+
+    #[inline(never)]
+    fn fill(mut self, byte: u8, n: usize) -> Builder {
+        for b in &mut self.buf[self.pos..self.pos + n] {
+            *b = byte;
+        }
+        self.pos += n;
+        self
+    }
+
+    fn skip(mut self, n: usize) -> Builder {
+        self.pos += n;
+        self
+    }
+
+    #[inline(never)]
+    fn finish(self) -> [u8; LEN] {
+        self.buf
+    }
+}
+
+fn main() {
+    let builder = Builder::new().fill(1, 1024);
+    let builder = builder.skip(1024);
+    let tmp = builder;
+    let buf = tmp.fill(2, 2048).fill(3, LEN - 4096).finish();
+
+    assert!(buf[..1024].iter().all(|&b| b == 1));
+    assert!(buf[1024..2048].iter().all(|&b| b == 0));
+    assert!(buf[2048..4096].iter().all(|&b| b == 2));
+    assert!(buf[4096..].iter().all(|&b| b == 3));
+}
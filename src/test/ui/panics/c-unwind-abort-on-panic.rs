@@ -0,0 +1,50 @@
+// run-pass
+
+#![allow(unused_must_use)]
+#![feature(c_unwind)]
+// With `c_unwind`, a panic escaping an `extern "C"` function aborts, while an
+// `extern "C-unwind"` function lets it unwind into the caller.
+
+// ignore-cloudabi no env and process
+// ignore-emscripten no processes
+// ignore-sgx no processes
+
+use std::{env, panic};
+use std::io::prelude::*;
+use std::io;
+use std::process::{Command, Stdio};
+
+extern "C" fn panic_in_ffi() {
+    panic!("Test");
+}
+
+extern "C-unwind" fn panic_in_unwind_ffi() {
+    panic!("TestUnwind");
+}
+
+fn test() {
+    let _ = panic::catch_unwind(|| { panic_in_ffi(); });
+    // The process should have aborted by now.
+    io::stdout().write(b"This should never be printed.\n");
+    let _ = io::stdout().flush();
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() > 1 {
+        // This is inside the self-executed command.
+        match &*args[1] {
+            "test" => return test(),
+            _ => panic!("bad test"),
+        }
+    }
+
+    // This ends up calling the self-execution branch above.
+    let mut p = Command::new(&args[0])
+                        .stdout(Stdio::piped())
+                        .stdin(Stdio::piped())
+                        .arg("test").spawn().unwrap();
+    assert!(!p.wait().unwrap().success());
+
+    assert!(panic::catch_unwind(|| { panic_in_unwind_ffi(); }).is_err());
+}
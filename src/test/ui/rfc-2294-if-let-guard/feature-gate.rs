@@ -0,0 +1,9 @@
+fn _f(x: Option<u32>) -> u32 {
+    match x {
+        Some(y) if let Some(z) = y.checked_sub(1) => z,
+        //~^ ERROR `if let` guards are experimental
+        _ => 0,
+    }
+}
+
+fn main() {}
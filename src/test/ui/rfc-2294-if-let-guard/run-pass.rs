@@ -0,0 +1,51 @@
+// run-pass
+
+#![feature(if_let_guard)]
+#![allow(incomplete_features)]
+
+fn parse(s: &str) -> Option<i32> {
+    s.parse().ok()
+}
+
+fn classify(x: Option<&str>) -> i32 {
+    match x {
+        Some(s) if let Some(n) = parse(s) => n,
+        Some(s) if let Some(rest) = s.strip_prefix('-') => -(rest.len() as i32),
+        Some(_) => 0,
+        None => i32::MIN,
+    }
+}
+
+fn last_plus_len(v: &mut Vec<i32>) -> i32 {
+    match v.len() {
+        n if let Some(&last) = v.last() => last + n as i32,
+        _ => {
+            v.push(0);
+            0
+        }
+    }
+}
+
+fn or_pattern(x: (u8, Option<String>)) -> String {
+    match x {
+        (1, Some(s)) | (2, Some(s)) if let Some(c) = s.chars().next() => format!("{}{}", c, s),
+        (_, s) => s.unwrap_or_default(),
+    }
+}
+
+fn main() {
+    assert_eq!(classify(Some("12")), 12);
+    assert_eq!(classify(Some("-abc")), -3);
+    assert_eq!(classify(Some("abc")), 0);
+    assert_eq!(classify(None), i32::MIN);
+
+    let mut v = vec![1, 2, 3];
+    assert_eq!(last_plus_len(&mut v), 6);
+    let mut v = vec![];
+    assert_eq!(last_plus_len(&mut v), 0);
+    assert_eq!(v, [0]);
+
+    assert_eq!(or_pattern((2, Some(String::from("ab")))), "aab");
+    assert_eq!(or_pattern((2, Some(String::new()))), "");
+    assert_eq!(or_pattern((3, Some(String::from("x")))), "x");
+}
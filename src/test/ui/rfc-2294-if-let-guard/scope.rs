@@ -0,0 +1,9 @@
+#![feature(if_let_guard)]
+#![allow(incomplete_features)]
+
+fn main() {
+    match Some(1) {
+        Some(x) if let Some(y) = Some(x) => {}
+        _ => { y; } //~ ERROR cannot find value `y` in this scope
+    }
+}
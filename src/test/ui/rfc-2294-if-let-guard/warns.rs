@@ -0,0 +1,10 @@
+#![feature(if_let_guard)]
+#![allow(incomplete_features)]
+#![deny(irrefutable_let_patterns)]
+
+fn main() {
+    match Some(1) {
+        Some(x) if let y = x => {} //~ ERROR irrefutable if-let guard
+        _ => {}
+    }
+}
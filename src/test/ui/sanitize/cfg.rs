@@ -3,12 +3,14 @@
 
 // needs-sanitizer-support
 // needs-sanitizer-address
+// needs-sanitizer-cfi
 // needs-sanitizer-leak
 // needs-sanitizer-memory
 // needs-sanitizer-thread
 // check-pass
-// revisions: address leak memory thread
+// revisions: address cfi leak memory thread
 //[address]compile-flags: -Zsanitizer=address --cfg address
+//[cfi]compile-flags:     -Zsanitizer=cfi     --cfg cfi -Clto
 //[leak]compile-flags:    -Zsanitizer=leak    --cfg leak
 //[memory]compile-flags:  -Zsanitizer=memory  --cfg memory
 //[thread]compile-flags:  -Zsanitizer=thread  --cfg thread
@@ -18,6 +20,9 @@
 #[cfg(all(sanitize = "address", address))]
 fn main() {}
 
+#[cfg(all(sanitize = "cfi", cfi))]
+fn main() {}
+
 #[cfg(all(sanitize = "leak", leak))]
 fn main() {}
 
@@ -0,0 +1,9 @@
+// Verifies that `-Zsanitizer=cfi` requires `-Clto` or `-Clinker-plugin-lto`.
+//
+// needs-sanitizer-cfi
+// compile-flags: -Z sanitizer=cfi
+// error-pattern: error: `-Zsanitizer=cfi` requires `-Clto` or `-Clinker-plugin-lto`
+
+#![feature(no_core)]
+#![no_core]
+#![no_main]
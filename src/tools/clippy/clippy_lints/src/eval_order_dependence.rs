@@ -115,7 +115,7 @@ impl<'a, 'tcx> DivergenceVisitor<'a, 'tcx> {
             ExprKind::Match(ref e, arms, _) => {
                 self.visit_expr(e);
                 for arm in arms {
-                    if let Some(Guard::If(if_expr)) = arm.guard {
+                    if let Some(Guard::If(if_expr) | Guard::IfLet(_, if_expr)) = arm.guard {
                         self.visit_expr(if_expr)
                     }
                     // make sure top level arm expressions aren't linted
@@ -154,6 +154,14 @@ impl<'a, 'tcx> SpanlessEq<'a, 'tcx> {
             (&ExprKind::Tup(l_tup), &ExprKind::Tup(r_tup)) => self.eq_exprs(l_tup, r_tup),
             (&ExprKind::Unary(l_op, ref le), &ExprKind::Unary(r_op, ref re)) => l_op == r_op && self.eq_expr(le, re),
             (&ExprKind::Array(l), &ExprKind::Array(r)) => self.eq_exprs(l, r),
+            (&ExprKind::ConstBlock(ref l_id), &ExprKind::ConstBlock(ref r_id)) => {
+                let mut celcx = constant_context(self.cx, self.cx.tcx.typeck_body(l_id.body));
+                let l = celcx.expr(&self.cx.tcx.hir().body(l_id.body).value);
+                let mut celcx = constant_context(self.cx, self.cx.tcx.typeck_body(r_id.body));
+                let r = celcx.expr(&self.cx.tcx.hir().body(r_id.body).value);
+
+                l.is_some() && l == r
+            },
             (&ExprKind::DropTemps(ref le), &ExprKind::DropTemps(ref re)) => self.eq_expr(le, re),
             _ => false,
         }
@@ -170,6 +178,8 @@ impl<'a, 'tcx> SpanlessEq<'a, 'tcx> {
     fn eq_guard(&mut self, left: &Guard<'_>, right: &Guard<'_>) -> bool {
         match (left, right) {
             (Guard::If(l), Guard::If(r)) => self.eq_expr(l, r),
+            (Guard::IfLet(lp, le), Guard::IfLet(rp, re)) => self.eq_pat(lp, rp) && self.eq_expr(le, re),
+            _ => false,
         }
     }
 
@@ -546,6 +556,9 @@ impl<'a, 'tcx> SpanlessHash<'a, 'tcx> {
                 self.hash_name(path.ident.name);
                 self.hash_exprs(args);
             },
+            ExprKind::ConstBlock(ref l_id) => {
+                self.hash_body(l_id.body);
+            },
             ExprKind::Repeat(ref e, ref l_id) => {
                 self.hash_expr(e);
                 self.hash_body(l_id.body);
@@ -633,6 +646,10 @@ impl<'a, 'tcx> SpanlessHash<'a, 'tcx> {
             Guard::If(ref expr) => {
                 self.hash_expr(expr);
             },
+            // TODO: pat?
+            Guard::IfLet(_, ref expr) => {
+                self.hash_expr(expr);
+            },
         }
     }
 
@@ -106,6 +106,7 @@ impl<'a> Sugg<'a> {
             | hir::ExprKind::Block(..)
             | hir::ExprKind::Break(..)
             | hir::ExprKind::Call(..)
+            | hir::ExprKind::ConstBlock(..)
             | hir::ExprKind::Field(..)
             | hir::ExprKind::Index(..)
             | hir::ExprKind::InlineAsm(..)
@@ -146,6 +147,7 @@ impl<'a> Sugg<'a> {
             | ast::ExprKind::Block(..)
             | ast::ExprKind::Break(..)
             | ast::ExprKind::Call(..)
+            | ast::ExprKind::ConstBlock(..)
             | ast::ExprKind::Continue(..)
             | ast::ExprKind::Yield(..)
             | ast::ExprKind::Field(..)
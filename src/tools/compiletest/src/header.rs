@@ -45,9 +45,13 @@ impl EarlyProps {
         let rustc_has_profiler_support = env::var_os("RUSTC_PROFILER_SUPPORT").is_some();
         let rustc_has_sanitizer_support = env::var_os("RUSTC_SANITIZER_SUPPORT").is_some();
         let has_asan = util::ASAN_SUPPORTED_TARGETS.contains(&&*config.target);
+        let has_cfi = util::CFI_SUPPORTED_TARGETS.contains(&&*config.target);
+        let has_hwasan = util::HWASAN_SUPPORTED_TARGETS.contains(&&*config.target);
         let has_lsan = util::LSAN_SUPPORTED_TARGETS.contains(&&*config.target);
         let has_msan = util::MSAN_SUPPORTED_TARGETS.contains(&&*config.target);
         let has_tsan = util::TSAN_SUPPORTED_TARGETS.contains(&&*config.target);
+        let has_shadow_call_stack =
+            util::SHADOWCALLSTACK_SUPPORTED_TARGETS.contains(&&*config.target);
 
         iter_header(testfile, None, rdr, &mut |ln| {
             // we should check if any only-<platform> exists and if it exists
@@ -89,6 +93,14 @@ impl EarlyProps {
                     props.ignore = true;
                 }
 
+                if !has_cfi && config.parse_name_directive(ln, "needs-sanitizer-cfi") {
+                    props.ignore = true;
+                }
+
+                if !has_hwasan && config.parse_name_directive(ln, "needs-sanitizer-hwaddress") {
+                    props.ignore = true;
+                }
+
                 if !has_lsan && config.parse_name_directive(ln, "needs-sanitizer-leak") {
                     props.ignore = true;
                 }
@@ -101,6 +113,12 @@ impl EarlyProps {
                     props.ignore = true;
                 }
 
+                if !has_shadow_call_stack
+                    && config.parse_name_directive(ln, "needs-sanitizer-shadow-call-stack")
+                {
+                    props.ignore = true;
+                }
+
                 if config.target == "wasm32-unknown-unknown" && config.parse_check_run_results(ln) {
                     props.ignore = true;
                 }